@@ -21,6 +21,7 @@ fn create_test_results(count: usize) -> Vec<SearchResult> {
             snippet: format!("Test snippet for result {}", i),
             score: Some(1.0 - (i as f64 / count as f64)),
             source_provider: "benchmark".to_string(),
+            ..Default::default()
         })
         .collect()
 }
@@ -32,6 +33,13 @@ fn create_test_params(query: &str, limit: Option<u32>) -> BaseSearchParams {
         limit,
         include_domains: Some(vec!["github.com".to_string(), "docs.rs".to_string()]),
         exclude_domains: Some(vec!["spam.com".to_string()]),
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     }
 }
 
@@ -156,7 +164,7 @@ fn bench_validation(c: &mut Criterion) {
     let mut group = c.benchmark_group("validation");
     group.throughput(Throughput::Elements(1));
 
-    let test_cases = vec![
+    let test_cases = [
         create_test_params("simple query", Some(10)),
         create_test_params("longer query with multiple words and complexity", Some(50)),
         create_test_params(&"word ".repeat(100), Some(100)),
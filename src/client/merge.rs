@@ -0,0 +1,323 @@
+//! Strategies for combining ranked results from several providers.
+//!
+//! Providers score results on unrelated scales (relevance floats, upvotes, or
+//! nothing at all), so a merger decides the combined order instead of sorting on
+//! the raw `score`. Results whose URLs share a canonical form are combined into
+//! one entry that lists every provider that returned it.
+
+use crate::common::canonical_url::canonicalize_url;
+use crate::common::types::SearchResult;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The conventional `k` for reciprocal rank fusion; it damps the advantage of
+/// the very top ranks
+pub const DEFAULT_RRF_K: f64 = 60.0;
+
+pub use crate::common::types::MergeStrategy;
+
+/// One provider's results, best first
+#[derive(Debug, Clone)]
+pub struct ProviderResults {
+    pub provider: String,
+    pub results: Vec<SearchResult>,
+}
+
+/// Combines per-provider result lists into a single ranking.
+///
+/// Lists are passed in the order the providers were routed, which strategies
+/// use to break ties.
+pub trait ResultMerger: Send + Sync {
+    fn merge(&self, ranked: Vec<ProviderResults>) -> Vec<SearchResult>;
+}
+
+impl MergeStrategy {
+    /// The merger for this strategy. `weights` is only used by `Weighted`.
+    pub fn merger(&self, weights: &HashMap<String, f64>) -> Arc<dyn ResultMerger> {
+        match self {
+            MergeStrategy::ReciprocalRankFusion => Arc::new(ReciprocalRankFusion::default()),
+            MergeStrategy::RoundRobin => Arc::new(RoundRobin),
+            MergeStrategy::Weighted => Arc::new(WeightedTrust::new(weights.clone())),
+            MergeStrategy::ScoreNormalization => Arc::new(ScoreNormalization),
+        }
+    }
+}
+
+// Sum per-result scores across lists, keeping the first copy of each page, and
+// order by the total. Ties keep their first-seen order.
+fn fuse<F>(ranked: Vec<ProviderResults>, scores_for: F) -> Vec<SearchResult>
+where
+    F: Fn(&ProviderResults) -> Vec<f64>,
+{
+    let mut fused: Vec<(SearchResult, f64)> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for list in ranked {
+        let scores = scores_for(&list);
+        for (result, score) in list.results.into_iter().zip(scores) {
+            let key = canonicalize_url(&result.url);
+            match positions.get(&key) {
+                Some(&position) => {
+                    fused[position].0.merge_providers_from(&result);
+                    fused[position].1 += score;
+                }
+                None => {
+                    positions.insert(key, fused.len());
+                    fused.push((result, score));
+                }
+            }
+        }
+    }
+
+    fused.sort_by(|a, b| b.1.total_cmp(&a.1));
+    fused
+        .into_iter()
+        .map(|(mut result, score)| {
+            result.score = Some(score);
+            result
+        })
+        .collect()
+}
+
+fn reciprocal_ranks(len: usize, k: f64, weight: f64) -> Vec<f64> {
+    (0..len)
+        .map(|rank| weight / (k + rank as f64 + 1.0))
+        .collect()
+}
+
+/// Reciprocal rank fusion: only ranks matter, so no provider's scale dominates
+#[derive(Debug, Clone)]
+pub struct ReciprocalRankFusion {
+    pub k: f64,
+}
+
+impl Default for ReciprocalRankFusion {
+    fn default() -> Self {
+        Self { k: DEFAULT_RRF_K }
+    }
+}
+
+impl ResultMerger for ReciprocalRankFusion {
+    fn merge(&self, ranked: Vec<ProviderResults>) -> Vec<SearchResult> {
+        fuse(ranked, |list| {
+            reciprocal_ranks(list.results.len(), self.k, 1.0)
+        })
+    }
+}
+
+/// Interleave providers' results, first from each provider, then second, and
+/// so on. Scores are left as the providers reported them.
+#[derive(Debug, Clone, Default)]
+pub struct RoundRobin;
+
+impl ResultMerger for RoundRobin {
+    fn merge(&self, ranked: Vec<ProviderResults>) -> Vec<SearchResult> {
+        let mut lists: Vec<_> = ranked
+            .into_iter()
+            .map(|list| list.results.into_iter())
+            .collect();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        let mut merged: Vec<SearchResult> = Vec::new();
+
+        loop {
+            let mut exhausted = true;
+            for list in lists.iter_mut() {
+                if let Some(result) = list.next() {
+                    exhausted = false;
+                    let key = canonicalize_url(&result.url);
+                    match positions.get(&key) {
+                        Some(&position) => merged[position].merge_providers_from(&result),
+                        None => {
+                            positions.insert(key, merged.len());
+                            merged.push(result);
+                        }
+                    }
+                }
+            }
+            if exhausted {
+                return merged;
+            }
+        }
+    }
+}
+
+/// Reciprocal rank fusion with each provider's contribution scaled by how much
+/// it is trusted. Providers without a weight get `default_weight`.
+#[derive(Debug, Clone)]
+pub struct WeightedTrust {
+    pub weights: HashMap<String, f64>,
+    pub default_weight: f64,
+    pub k: f64,
+}
+
+impl WeightedTrust {
+    pub fn new(weights: HashMap<String, f64>) -> Self {
+        Self {
+            weights,
+            default_weight: 1.0,
+            k: DEFAULT_RRF_K,
+        }
+    }
+
+    fn weight(&self, provider: &str) -> f64 {
+        self.weights
+            .get(provider)
+            .copied()
+            .unwrap_or(self.default_weight)
+    }
+}
+
+impl ResultMerger for WeightedTrust {
+    fn merge(&self, ranked: Vec<ProviderResults>) -> Vec<SearchResult> {
+        fuse(ranked, |list| {
+            reciprocal_ranks(list.results.len(), self.k, self.weight(&list.provider))
+        })
+    }
+}
+
+/// Min-max normalise each provider's scores to 0-1, then sum them per URL.
+///
+/// A provider that reports no scores is scored by rank instead, from 1 for its
+/// first result down towards 0. Unscored results from a provider that scores
+/// others count as 0.
+#[derive(Debug, Clone, Default)]
+pub struct ScoreNormalization;
+
+impl ScoreNormalization {
+    fn normalise(results: &[SearchResult]) -> Vec<f64> {
+        let scores: Vec<f64> = results.iter().filter_map(|r| r.score).collect();
+        if scores.is_empty() {
+            let len = results.len() as f64;
+            return (0..results.len())
+                .map(|rank| 1.0 - rank as f64 / len)
+                .collect();
+        }
+
+        let min = scores.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        results
+            .iter()
+            .map(|r| match r.score {
+                Some(_) if max == min => 1.0,
+                Some(score) => (score - min) / (max - min),
+                None => 0.0,
+            })
+            .collect()
+    }
+}
+
+impl ResultMerger for ScoreNormalization {
+    fn merge(&self, ranked: Vec<ProviderResults>) -> Vec<SearchResult> {
+        fuse(ranked, |list| Self::normalise(&list.results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(provider: &str, results: &[(&str, Option<f64>)]) -> ProviderResults {
+        ProviderResults {
+            provider: provider.to_string(),
+            results: results
+                .iter()
+                .map(|(url, score)| SearchResult {
+                    title: url.to_string(),
+                    url: format!("https://{}", url),
+                    snippet: String::new(),
+                    score: *score,
+                    source_provider: provider.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    fn urls(results: &[SearchResult]) -> Vec<&str> {
+        results
+            .iter()
+            .map(|r| r.url.trim_start_matches("https://"))
+            .collect()
+    }
+
+    fn sample() -> Vec<ProviderResults> {
+        vec![
+            list(
+                "tavily",
+                &[("a", Some(0.9)), ("b", Some(0.8)), ("c", Some(0.1))],
+            ),
+            list("reddit", &[("c", Some(5000.0)), ("d", Some(20.0))]),
+            list("duckduckgo", &[("e", None), ("c", None)]),
+        ]
+    }
+
+    #[test]
+    fn test_reciprocal_rank_fusion_rewards_agreement() {
+        let merged = ReciprocalRankFusion::default().merge(sample());
+
+        // "c" is returned by all three providers, so it outranks every single top hit
+        assert_eq!(urls(&merged), vec!["c", "a", "e", "b", "d"]);
+        let expected = 1.0 / 63.0 + 1.0 / 61.0 + 1.0 / 62.0;
+        assert!((merged[0].score.unwrap() - expected).abs() < 1e-12);
+        assert_eq!(merged[0].source_provider, "tavily");
+        assert_eq!(merged[0].also_found_by, vec!["reddit", "duckduckgo"]);
+    }
+
+    #[test]
+    fn test_variants_of_a_page_are_fused() {
+        let ranked = vec![
+            list(
+                "google_custom_search",
+                &[("www.example.com/a/?utm_source=x", None)],
+            ),
+            list("exa", &[("example.com/a", None), ("example.com/b", None)]),
+        ];
+        let merged = ReciprocalRankFusion::default().merge(ranked.clone());
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].also_found_by, vec!["exa"]);
+
+        let merged = RoundRobin.merge(ranked);
+        assert_eq!(
+            urls(&merged),
+            vec!["www.example.com/a/?utm_source=x", "example.com/b"]
+        );
+        assert_eq!(merged[0].also_found_by, vec!["exa"]);
+    }
+
+    #[test]
+    fn test_round_robin_interleaves_without_duplicates() {
+        let merged = RoundRobin.merge(sample());
+
+        assert_eq!(urls(&merged), vec!["a", "c", "e", "b", "d"]);
+        assert_eq!(merged[1].score, Some(5000.0));
+    }
+
+    #[test]
+    fn test_weighted_trust_favours_trusted_providers() {
+        let weights = HashMap::from([("reddit".to_string(), 3.0)]);
+        let merged = WeightedTrust::new(weights).merge(sample());
+
+        assert_eq!(urls(&merged), vec!["c", "d", "a", "e", "b"]);
+    }
+
+    #[test]
+    fn test_score_normalization_rescales_each_provider() {
+        let merged = ScoreNormalization.merge(sample());
+
+        // Reddit's upvotes no longer swamp Tavily's relevance scores
+        assert_eq!(urls(&merged), vec!["c", "a", "e", "b", "d"]);
+        assert_eq!(merged[0].score, Some(1.5));
+        assert_eq!(merged[1].score, Some(1.0));
+        assert_eq!(merged.last().unwrap().score, Some(0.0));
+    }
+
+    #[test]
+    fn test_strategy_names() {
+        let strategy: MergeStrategy = serde_json::from_str("\"score_normalization\"").unwrap();
+        assert_eq!(strategy, MergeStrategy::ScoreNormalization);
+        assert_eq!(
+            MergeStrategy::default(),
+            MergeStrategy::ReciprocalRankFusion
+        );
+    }
+}
@@ -0,0 +1,2178 @@
+//! High-level client API for using omnisearch as a library.
+//!
+//! This module provides a convenient interface for applications that want to use
+//! omnisearch functionality without running a full MCP server.
+
+mod merge;
+mod stream;
+
+pub use merge::{
+    MergeStrategy, ProviderResults, ReciprocalRankFusion, ResultMerger, RoundRobin,
+    ScoreNormalization, WeightedTrust, DEFAULT_RRF_K,
+};
+pub use stream::ProviderEvent;
+
+use crate::common::cache::CacheManager;
+use crate::common::canonical_url::dedup_results;
+use crate::common::capabilities::{ignored_filters, post_filter, within_limits};
+use crate::common::circuit_breaker::{CircuitBreakerManager, CircuitBreakerProvider};
+use crate::common::metrics::{MetricsCollector, RequestMetrics, METRICS_COLLECTOR};
+use crate::common::provider_factory::ProviderFactory;
+use crate::common::rate_limiter::RateLimiterManager;
+use crate::common::types::{
+    BaseSearchParams, ErrorType, ProviderCapabilities, ProviderError, SafeSearch, SearchFilter,
+    SearchProvider, SearchResult, SearchVertical, TimeRange,
+};
+use crate::common::validation::validate_page_token;
+use crate::config::{Config, HedgingConfig, CONFIG};
+use crate::{create_providers, validate_config};
+use futures::{Stream, StreamExt};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use stream::{Completion, FetchedPage, ProviderStream};
+use tokio::task::JoinSet;
+use tracing::{debug, warn, Instrument};
+
+// Provider preference for general web searches, best first
+const GENERAL_PROVIDER_ORDER: &[&str] = &[
+    "tavily",
+    "google_custom_search",
+    "brave",
+    "exa",
+    "brightdata",
+    "duckduckgo",
+    "baidu",
+    "reddit",
+];
+
+/// A hint about what kind of results a search is after, used to pick providers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchCategory {
+    /// General web pages
+    General,
+    /// Recent news and current events
+    News,
+    /// Academic, scientific and technical research
+    Research,
+    /// Programming documentation, code and issues
+    Code,
+    /// Forum threads and community opinions
+    Discussion,
+}
+
+impl SearchCategory {
+    /// Providers to try for this category, best first
+    pub fn provider_order(&self) -> &'static [&'static str] {
+        match self {
+            SearchCategory::General => GENERAL_PROVIDER_ORDER,
+            SearchCategory::News => &[
+                "tavily",
+                "brave",
+                "google_custom_search",
+                "brightdata",
+                "duckduckgo",
+                "exa",
+                "baidu",
+                "reddit",
+            ],
+            SearchCategory::Research => &[
+                "exa",
+                "tavily",
+                "google_custom_search",
+                "brave",
+                "brightdata",
+                "duckduckgo",
+                "baidu",
+                "reddit",
+            ],
+            SearchCategory::Code => &[
+                "google_custom_search",
+                "brave",
+                "exa",
+                "tavily",
+                "brightdata",
+                "duckduckgo",
+                "reddit",
+                "baidu",
+            ],
+            SearchCategory::Discussion => &[
+                "reddit",
+                "tavily",
+                "google_custom_search",
+                "brave",
+                "exa",
+                "brightdata",
+                "duckduckgo",
+                "baidu",
+            ],
+        }
+    }
+}
+
+// Whether the text contains Chinese, Japanese or Korean characters
+fn contains_cjk(text: &str) -> bool {
+    text.chars().any(|c| {
+        matches!(c as u32,
+            0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF)
+    })
+}
+
+fn move_to_front(order: &mut Vec<String>, provider: &str) {
+    if let Some(index) = order.iter().position(|p| p == provider) {
+        let provider = order.remove(index);
+        order.insert(0, provider);
+    }
+}
+
+/// A high-level client for performing omnisearch operations.
+///
+/// The client automatically discovers and initializes available search providers
+/// based on environment variables and API keys.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use omnisearch_mcp::{OmnisearchClient, SearchRequest};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = OmnisearchClient::new().await?;
+///
+///     let request = SearchRequest::new("rust async programming")
+///         .limit(5)
+///         .provider("tavily");
+///
+///     let response = client.search(request).await?;
+///
+///     println!("Found {} results from {} providers",
+///         response.results.len(),
+///         response.providers_used.len());
+///
+///     for result in response.results {
+///         println!("- {}: {}", result.title, result.url);
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+pub struct OmnisearchClient {
+    providers: HashMap<String, Arc<dyn SearchProvider>>,
+    provider_timeout: Option<Duration>,
+    merge_strategy: MergeStrategy,
+    provider_weights: HashMap<String, f64>,
+    merger: Option<Arc<dyn ResultMerger>>,
+    hedging: HedgingConfig,
+    // Where provider timeouts come from; the global config unless built with one
+    config: Option<Arc<Config>>,
+    services: SearchServices,
+}
+
+impl OmnisearchClient {
+    /// Create a new omnisearch client.
+    ///
+    /// This will validate the configuration and initialize all available providers
+    /// based on the current environment variables.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if configuration validation fails or no providers can be initialized.
+    pub async fn new() -> Result<Self, ProviderError> {
+        // Validate configuration first
+        validate_config().map_err(|e| {
+            ProviderError::new(
+                crate::common::types::ErrorType::ApiError,
+                format!("Configuration validation failed: {}", e),
+                "client".to_string(),
+                None,
+            )
+        })?;
+
+        // Initialize providers
+        let provider_list = create_providers();
+
+        if provider_list.is_empty() {
+            return Err(ProviderError::new(
+                crate::common::types::ErrorType::ApiError,
+                "No search providers could be initialized. Please check your API keys.".to_string(),
+                "client".to_string(),
+                None,
+            ));
+        }
+
+        Ok(Self::from_providers(
+            provider_list.into_iter().map(Arc::from),
+        ))
+    }
+
+    /// Create a client over an explicit set of providers.
+    ///
+    /// No configuration is validated; this is how the MCP server shares its
+    /// registered providers with the `omnisearch` tool.
+    pub fn from_providers(providers: impl IntoIterator<Item = Arc<dyn SearchProvider>>) -> Self {
+        let providers = providers
+            .into_iter()
+            .map(|provider| (provider.name().to_string(), provider))
+            .collect();
+        Self {
+            providers,
+            provider_timeout: None,
+            merge_strategy: CONFIG.search.merge_strategy,
+            provider_weights: CONFIG.search.provider_weights.clone(),
+            merger: None,
+            hedging: CONFIG.search.hedging.clone(),
+            config: None,
+            services: SearchServices {
+                metrics: Arc::clone(&METRICS_COLLECTOR),
+                cache: None,
+                rate_limiter: None,
+                circuit_breaker: None,
+                cache_ttl: Duration::from_secs(CONFIG.cache.ttl_seconds),
+            },
+        }
+    }
+
+    /// Start building a client that does not touch any global state.
+    ///
+    /// See [`OmnisearchClientBuilder`].
+    pub fn builder() -> OmnisearchClientBuilder {
+        OmnisearchClientBuilder::default()
+    }
+
+    /// Hedge slow providers in [`search`](Self::search). Defaults to `[search.hedging]`.
+    pub fn with_hedging(mut self, hedging: HedgingConfig) -> Self {
+        self.hedging = hedging;
+        self
+    }
+
+    /// Merge [`multi_search`](Self::multi_search) results with this strategy
+    /// unless a request chooses another. Defaults to `[search] merge_strategy`.
+    pub fn with_merge_strategy(mut self, strategy: MergeStrategy) -> Self {
+        self.merge_strategy = strategy;
+        self.merger = None;
+        self
+    }
+
+    /// Trust weights for [`MergeStrategy::Weighted`], by provider name.
+    /// Defaults to `[search] provider_weights`.
+    pub fn with_provider_weights(mut self, weights: HashMap<String, f64>) -> Self {
+        self.provider_weights = weights;
+        self
+    }
+
+    /// Merge results with a custom strategy unless a request chooses a built-in one
+    pub fn with_merger(mut self, merger: impl ResultMerger + 'static) -> Self {
+        self.merger = Some(Arc::new(merger));
+        self
+    }
+
+    /// Use the same timeout for every provider search instead of each
+    /// provider's configured `timeout_seconds`.
+    pub fn with_provider_timeout(mut self, timeout: Duration) -> Self {
+        self.provider_timeout = Some(timeout);
+        self
+    }
+
+    /// Serve repeated provider searches from this cache
+    pub fn with_cache(mut self, cache: Arc<CacheManager>) -> Self {
+        self.services.cache = Some(cache);
+        self
+    }
+
+    /// Check each provider search against this rate limiter
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiterManager>) -> Self {
+        self.services.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Run each provider search through this circuit breaker
+    pub fn with_circuit_breaker(mut self, circuit_breaker: Arc<CircuitBreakerManager>) -> Self {
+        self.services.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    /// Get the names of all available providers.
+    pub fn available_providers(&self) -> Vec<&str> {
+        self.providers.keys().map(|s| s.as_str()).collect()
+    }
+
+    /// Check if a specific provider is available.
+    pub fn has_provider(&self, name: &str) -> bool {
+        self.providers.contains_key(name)
+    }
+
+    /// Get information about a specific provider.
+    pub fn provider_info(&self, name: &str) -> Option<(String, String)> {
+        self.providers
+            .get(name)
+            .map(|p| (p.name().to_string(), p.description().to_string()))
+    }
+
+    /// What a specific provider can do.
+    pub fn provider_capabilities(&self, name: &str) -> Option<ProviderCapabilities> {
+        self.providers.get(name).map(|p| p.capabilities())
+    }
+
+    /// The available providers to try for a request, best first.
+    ///
+    /// The order starts from the request's category (general web search by
+    /// default). When the request sets filters, providers that can apply more of
+    /// them move ahead, as do providers whose languages include the requested
+    /// one. Reddit moves to the front when the query or domains mention it, and
+    /// Baidu moves to the front for Chinese, Japanese or Korean queries. For a
+    /// [`vertical`](SearchRequest::vertical) other than the web, only providers
+    /// that return that kind of result are kept.
+    pub fn route(&self, request: &SearchRequest) -> Vec<String> {
+        let category = request.category.unwrap_or(SearchCategory::General);
+        let mut order: Vec<String> = category
+            .provider_order()
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+
+        // Providers without a routing preference go last, in a stable order
+        let mut others: Vec<String> = self
+            .providers
+            .keys()
+            .filter(|name| !order.contains(name))
+            .cloned()
+            .collect();
+        others.sort();
+        order.extend(others);
+
+        let params = request.clone().into_search_params();
+        if !params.filters().is_empty() {
+            order.sort_by_key(|name| match self.providers.get(name) {
+                Some(provider) => {
+                    let capabilities = provider.capabilities();
+                    let other_language = params.language.as_deref().is_some_and(|language| {
+                        !capabilities.languages.is_empty()
+                            && !capabilities
+                                .languages
+                                .iter()
+                                .any(|known| language.starts_with(known.as_str()))
+                    });
+                    (other_language, provider.unsupported_filters(&params).len())
+                }
+                None => (false, 0),
+            });
+        }
+
+        let include_domains = request.include_domains.as_deref().unwrap_or_default();
+
+        let query = request.query.to_lowercase();
+        if query.contains("reddit") || include_domains.iter().any(|d| d.contains("reddit.com")) {
+            move_to_front(&mut order, "reddit");
+        }
+        if contains_cjk(&request.query) {
+            move_to_front(&mut order, "baidu");
+        }
+
+        let vertical = request.vertical.unwrap_or_default();
+        order.retain(|name| {
+            self.providers
+                .get(name)
+                .is_some_and(|provider| provider.capabilities().serves(vertical))
+        });
+        order
+    }
+
+    /// Perform a search using the specified request parameters.
+    ///
+    /// If no specific provider is requested, providers are tried in the order
+    /// chosen by [`route`](Self::route) until one succeeds or all fail. Providers
+    /// that failed before one succeeded are listed in [`SearchResponse::failures`].
+    ///
+    /// With hedging enabled, a provider that has not answered within its usual
+    /// latency (the configured percentile of its recent requests) is raced
+    /// against the next provider in the order. The first to succeed is used and
+    /// the other request is cancelled.
+    ///
+    /// If the request has a [`deadline`](SearchRequest::deadline) and no provider
+    /// has succeeded by then, the search stops with an error.
+    ///
+    /// A [`page_token`](SearchRequest::page_token) from an earlier response
+    /// fetches the next page from the provider that answered it.
+    pub async fn search(&self, request: SearchRequest) -> Result<SearchResponse, ProviderError> {
+        let query = request.query.clone();
+        let deadline = request.deadline;
+        let page = request
+            .page_token
+            .as_deref()
+            .map(decode_page_token)
+            .transpose()?
+            .and_then(|pages| pages.into_iter().next());
+        let preferred_provider = match &page {
+            Some((provider_name, _)) => Some(provider_name.clone()),
+            None => request.preferred_provider.clone(),
+        };
+        let provider_order = self.route(&request);
+        let mut params = request.into_search_params();
+        params.page_token = page.map(|(_, token)| token);
+
+        if let Some(provider_name) = &preferred_provider {
+            // Use specific provider
+            if let Some(provider) = self.providers.get(provider_name) {
+                let search = self.services.search(
+                    Arc::clone(provider),
+                    params.clone(),
+                    self.timeout_for(provider_name),
+                );
+                let results = within(deadline, search).await.ok_or_else(|| {
+                    deadline_error(deadline, &[], std::slice::from_ref(provider_name))
+                })??;
+                return Ok(SearchResponse {
+                    next_page_token: self.next_page_token(provider_name, &params, &results),
+                    ignored_filters: self
+                        .ignored_filters(std::slice::from_ref(provider_name), &params),
+                    results: dedup_results(post_filter(provider.as_ref(), &params, results)),
+                    providers_used: vec![provider_name.clone()],
+                    query: query.clone(),
+                    failures: Vec::new(),
+                    partial: false,
+                    timed_out: Vec::new(),
+                });
+            } else {
+                return Err(ProviderError::new(
+                    crate::common::types::ErrorType::InvalidInput,
+                    format!("Provider '{}' is not available", provider_name),
+                    "client".to_string(),
+                    None,
+                ));
+            }
+        }
+
+        let mut failures = Vec::new();
+        let mut attempting = Vec::new();
+        let fallback = self.fall_back(provider_order, &params, &mut failures, &mut attempting);
+        let success = match within(deadline, fallback).await {
+            Some(success) => success,
+            None => return Err(deadline_error(deadline, &failures, &attempting)),
+        };
+
+        match success {
+            Some((provider_name, results)) => Ok(SearchResponse {
+                next_page_token: self.next_page_token(&provider_name, &params, &results),
+                ignored_filters: self
+                    .ignored_filters(std::slice::from_ref(&provider_name), &params),
+                results: dedup_results(post_filter(
+                    self.providers[&provider_name].as_ref(),
+                    &params,
+                    results,
+                )),
+                providers_used: vec![provider_name],
+                query,
+                failures,
+                partial: false,
+                timed_out: Vec::new(),
+            }),
+            // If we get here, all providers failed
+            None => Err(failures.pop().unwrap_or_else(|| {
+                ProviderError::new(
+                    crate::common::types::ErrorType::ProviderError,
+                    "No providers available for search".to_string(),
+                    "client".to_string(),
+                    None,
+                )
+            })),
+        }
+    }
+
+    // Fall back through providers in routing order, hedging slow ones with the
+    // next, until one succeeds. `attempting` holds the providers currently being
+    // searched, for reporting if the caller gives up waiting.
+    async fn fall_back(
+        &self,
+        provider_order: Vec<String>,
+        params: &BaseSearchParams,
+        failures: &mut Vec<ProviderError>,
+        attempting: &mut Vec<String>,
+    ) -> Option<(String, Vec<SearchResult>)> {
+        let mut remaining = provider_order.into_iter().peekable();
+
+        while let Some(provider_name) = remaining.next() {
+            let hedge = match remaining.peek() {
+                Some(next) => self
+                    .hedge_delay(&provider_name)
+                    .await
+                    .map(|delay| (next.clone(), delay)),
+                None => None,
+            };
+
+            attempting.clear();
+            attempting.push(provider_name.clone());
+            let attempt = match hedge {
+                Some((hedge_name, delay)) => {
+                    attempting.push(hedge_name.clone());
+                    self.hedged_search(&provider_name, &hedge_name, delay, params)
+                        .await
+                }
+                None => {
+                    let provider = Arc::clone(&self.providers[&provider_name]);
+                    let timeout = self.timeout_for(&provider_name);
+                    let result = self
+                        .services
+                        .search(provider, params.clone(), timeout)
+                        .await;
+                    SearchAttempt::from_result(provider_name, result, false)
+                }
+            };
+            attempting.clear();
+
+            if attempt.hedged {
+                remaining.next();
+            }
+            for e in attempt.failures {
+                warn!("{} failed, trying the next provider: {}", e.provider, e);
+                failures.push(e);
+            }
+            if attempt.success.is_some() {
+                return attempt.success;
+            }
+        }
+        None
+    }
+
+    /// Perform a search across multiple providers at once and combine results.
+    ///
+    /// Up to `max_providers` providers, taken in the order chosen by
+    /// [`route`](Self::route), are queried concurrently, each with its own
+    /// timeout. Results from every provider that succeeds are combined by the
+    /// request's merge strategy, or the client's, deduplicated on their canonical
+    /// URLs and cut to the request's `limit`. Providers that fail or time out are listed in
+    /// [`SearchResponse::failures`]. An error is returned only if all of them fail.
+    ///
+    /// If the request has a [`deadline`](SearchRequest::deadline), providers that
+    /// have not answered by then are cancelled and listed in
+    /// [`SearchResponse::timed_out`], and the response is marked partial.
+    pub async fn multi_search(
+        &self,
+        request: SearchRequest,
+        max_providers: usize,
+    ) -> Result<SearchResponse, ProviderError> {
+        let events = self.search_stream(request.max_providers(max_providers));
+        tokio::pin!(events);
+        while let Some(event) = events.next().await {
+            if let ProviderEvent::Completed(result) = event {
+                return result;
+            }
+        }
+        Err(ProviderError::new(
+            crate::common::types::ErrorType::ProviderError,
+            "Search ended without a result".to_string(),
+            "client".to_string(),
+            None,
+        ))
+    }
+
+    /// Run many searches, at most `concurrency` of them at a time.
+    ///
+    /// Each request runs as in [`search`](Self::search), or as in
+    /// [`multi_search`](Self::multi_search) if it sets
+    /// [`max_providers`](SearchRequest::max_providers) above 1, sharing this
+    /// client's cache, rate limiter and circuit breakers. The outcomes are in
+    /// the order of `requests`; a failed search does not stop the others.
+    pub async fn search_batch(
+        &self,
+        requests: impl IntoIterator<Item = SearchRequest>,
+        concurrency: usize,
+    ) -> Vec<Result<SearchResponse, ProviderError>> {
+        let requests: Vec<SearchRequest> = requests.into_iter().collect();
+        let mut outcomes: Vec<Option<Result<SearchResponse, ProviderError>>> =
+            requests.iter().map(|_| None).collect();
+        let finished = self.search_batch_stream(requests, concurrency);
+        tokio::pin!(finished);
+        while let Some((index, outcome)) = finished.next().await {
+            outcomes[index] = Some(outcome);
+        }
+        outcomes.into_iter().flatten().collect()
+    }
+
+    // The batch's outcomes as they finish, with each request's position
+    pub(crate) fn search_batch_stream(
+        &self,
+        requests: Vec<SearchRequest>,
+        concurrency: usize,
+    ) -> impl Stream<Item = (usize, Result<SearchResponse, ProviderError>)> + '_ {
+        futures::stream::iter(requests.into_iter().enumerate())
+            .map(move |(index, request)| async move {
+                let outcome = match request.max_providers {
+                    Some(max_providers) if max_providers > 1 => {
+                        self.multi_search(request, max_providers).await
+                    }
+                    _ => self.search(request).await,
+                };
+                (index, outcome)
+            })
+            .buffer_unordered(concurrency.max(1))
+    }
+
+    /// Search several providers concurrently, as [`multi_search`](Self::multi_search)
+    /// does, reporting each provider the moment it finishes.
+    ///
+    /// The stream starts with [`ProviderEvent::Started`], then yields one
+    /// [`ProviderEvent::Results`] or [`ProviderEvent::Failed`] per provider in
+    /// the order they finish, and ends with [`ProviderEvent::Completed`] holding
+    /// the merged response. Every routed provider is queried unless the request
+    /// sets [`max_providers`](SearchRequest::max_providers). When the request's
+    /// deadline passes, the stream completes with the results so far. Dropping
+    /// the stream cancels the searches still running.
+    pub fn search_stream(
+        &self,
+        request: SearchRequest,
+    ) -> impl Stream<Item = ProviderEvent> + Send + 'static {
+        // With a page token, each provider in it continues from its own page
+        let pages = match request.page_token.as_deref().map(decode_page_token) {
+            Some(Ok(pages)) => Some(pages),
+            Some(Err(e)) => return ProviderStream::failed(e).into_stream(),
+            None => None,
+        };
+        let provider_order: Vec<String> = match (&pages, &request.preferred_provider) {
+            (Some(pages), _) => pages.iter().map(|(name, _)| name.clone()).collect(),
+            (None, Some(preferred)) => vec![preferred.clone()],
+            (None, None) => self
+                .route(&request)
+                .into_iter()
+                .take(request.max_providers.unwrap_or(usize::MAX))
+                .collect(),
+        };
+        if let Some(missing) = provider_order
+            .iter()
+            .find(|name| !self.providers.contains_key(*name))
+        {
+            return ProviderStream::failed(ProviderError::new(
+                crate::common::types::ErrorType::InvalidInput,
+                format!("Preferred provider '{}' not available", missing),
+                "client".to_string(),
+                None,
+            ))
+            .into_stream();
+        }
+        let pages: HashMap<String, String> = pages.unwrap_or_default().into_iter().collect();
+        let deadline = request
+            .deadline
+            .map(|deadline| tokio::time::Instant::now() + deadline);
+        let merger = self.merger_for(&request);
+        let query = request.query.clone();
+        let limit = request.limit;
+        let params = request.into_search_params();
+        let completion = Completion {
+            query,
+            merger,
+            limit,
+            ignored_filters: self.ignored_filters(&provider_order, &params),
+        };
+
+        let mut searches = JoinSet::new();
+        for (index, provider_name) in provider_order.iter().enumerate() {
+            let provider = Arc::clone(&self.providers[provider_name]);
+            let mut params = params.clone();
+            params.page_token = pages.get(provider_name).cloned();
+            let timeout = self.timeout_for(provider_name);
+            let services = self.services.clone();
+            // Spawned searches log within the caller's span
+            searches.spawn(
+                async move {
+                    let result = services
+                        .search(Arc::clone(&provider), params.clone(), timeout)
+                        .await;
+                    // Paged on what the provider returned, so a thinned page does not end the paging
+                    let page = result.as_ref().ok().map(|results| FetchedPage {
+                        provider: Arc::clone(&provider),
+                        params: params.clone(),
+                        results: results.clone(),
+                    });
+                    let result =
+                        result.map(|results| post_filter(provider.as_ref(), &params, results));
+                    (index, result, page)
+                }
+                .in_current_span(),
+            );
+        }
+
+        ProviderStream::new(provider_order, searches, deadline, completion).into_stream()
+    }
+
+    // The client page token for the results after `results` from `provider`
+    fn next_page_token(
+        &self,
+        provider: &str,
+        params: &BaseSearchParams,
+        results: &[SearchResult],
+    ) -> Option<String> {
+        let page = self.providers[provider].next_page_token(params, results)?;
+        encode_page_token([(provider.to_string(), page)])
+    }
+
+    // The filters in `params` that each of `providers` cannot apply
+    fn ignored_filters(
+        &self,
+        providers: &[String],
+        params: &BaseSearchParams,
+    ) -> BTreeMap<String, Vec<SearchFilter>> {
+        providers
+            .iter()
+            .filter_map(|name| {
+                let ignored = ignored_filters(self.providers.get(name)?.as_ref(), params);
+                (!ignored.is_empty()).then(|| (name.clone(), ignored))
+            })
+            .collect()
+    }
+
+    // A request's own strategy wins over a custom merger, which wins over the default
+    fn merger_for(&self, request: &SearchRequest) -> Arc<dyn ResultMerger> {
+        match (request.merge_strategy, &self.merger) {
+            (Some(strategy), _) => strategy.merger(&self.provider_weights),
+            (None, Some(merger)) => Arc::clone(merger),
+            (None, None) => self.merge_strategy.merger(&self.provider_weights),
+        }
+    }
+
+    // The configured timeout for a provider, unless overridden on the client
+    fn timeout_for(&self, provider: &str) -> Duration {
+        self.provider_timeout.unwrap_or_else(|| {
+            self.config
+                .as_deref()
+                .unwrap_or(&CONFIG)
+                .timeout_duration(provider)
+        })
+    }
+
+    // How long to wait for a provider before hedging it, if hedging is enabled
+    // and the provider has enough recent requests to judge its latency
+    async fn hedge_delay(&self, provider: &str) -> Option<Duration> {
+        if !self.hedging.enabled {
+            return None;
+        }
+        let stats = self.services.metrics.get_provider_stats(provider).await?;
+        if stats.recent_durations.len() < self.hedging.min_samples {
+            return None;
+        }
+        stats.latency_percentile(self.hedging.percentile)
+    }
+
+    // Search `primary`; if it has not answered within `delay`, also search
+    // `hedge` and use whichever succeeds first. The slower search is dropped,
+    // which cancels its request.
+    async fn hedged_search(
+        &self,
+        primary: &str,
+        hedge: &str,
+        delay: Duration,
+        params: &BaseSearchParams,
+    ) -> SearchAttempt {
+        let primary_search = self.services.search(
+            Arc::clone(&self.providers[primary]),
+            params.clone(),
+            self.timeout_for(primary),
+        );
+        tokio::pin!(primary_search);
+
+        tokio::select! {
+            result = &mut primary_search => {
+                return SearchAttempt::from_result(primary.to_string(), result, false);
+            }
+            _ = tokio::time::sleep(delay) => {}
+        }
+
+        debug!(
+            "{} has not answered within {:?}, hedging with {}",
+            primary, delay, hedge
+        );
+        let hedge_search = self.services.search(
+            Arc::clone(&self.providers[hedge]),
+            params.clone(),
+            self.timeout_for(hedge),
+        );
+        tokio::pin!(hedge_search);
+
+        let (first, first_result, second, hedge_first) = tokio::select! {
+            result = &mut primary_search => (primary, result, hedge, false),
+            result = &mut hedge_search => (hedge, result, primary, true),
+        };
+        let (first_result, second_result) = match first_result {
+            Ok(results) => (Ok(results), None),
+            Err(e) if hedge_first => (Err(e), Some(primary_search.await)),
+            Err(e) => (Err(e), Some(hedge_search.await)),
+        };
+
+        let hedge_won = match (&first_result, &second_result) {
+            (Ok(_), _) => hedge_first,
+            (Err(_), Some(Ok(_))) => !hedge_first,
+            _ => false,
+        };
+        self.services
+            .metrics
+            .record_hedge(primary, hedge, hedge_won)
+            .await;
+
+        let mut attempt = SearchAttempt::from_result(first.to_string(), first_result, true);
+        if let Some(result) = second_result {
+            let second = SearchAttempt::from_result(second.to_string(), result, true);
+            attempt.success = attempt.success.or(second.success);
+            attempt.failures.extend(second.failures);
+        }
+        attempt
+    }
+}
+
+/// Builds an [`OmnisearchClient`] from explicit parts rather than global state.
+///
+/// Nothing is read from the global `CONFIG` or environment, and the client
+/// records metrics in its own collector. Settings such as merging, hedging and
+/// provider timeouts come from the [`config`](Self::config) given, or the
+/// defaults. The built-in providers that config has credentials for are created
+/// alongside any custom [`provider`](Self::provider)s; a custom provider replaces
+/// a built-in one with the same name. The cache, rate limiter and circuit breaker
+/// are only used when supplied.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use omnisearch_mcp::{Config, OmnisearchClient, SearchRequest};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut config = Config::default();
+///     config.providers.tavily.api_key = Some("tvly-...".to_string());
+///
+///     let client = OmnisearchClient::builder()
+///         .config(config)
+///         .http_client(reqwest::Client::new())
+///         .build()?;
+///
+///     let response = client.search(SearchRequest::new("rust async")).await?;
+///     println!("{} results", response.len());
+///     Ok(())
+/// }
+/// ```
+#[derive(Default)]
+pub struct OmnisearchClientBuilder {
+    config: Option<Config>,
+    providers: Vec<Arc<dyn SearchProvider>>,
+    http_client: Option<reqwest::Client>,
+    cache: Option<Arc<CacheManager>>,
+    rate_limiter: Option<Arc<RateLimiterManager>>,
+    circuit_breaker: Option<Arc<CircuitBreakerManager>>,
+    metrics: Option<Arc<MetricsCollector>>,
+}
+
+impl OmnisearchClientBuilder {
+    /// Use this configuration and create the built-in providers it has credentials for
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Add a custom provider
+    pub fn provider(mut self, provider: Box<dyn SearchProvider>) -> Self {
+        self.providers.push(Arc::from(provider));
+        self
+    }
+
+    /// Add several custom providers
+    pub fn providers(
+        mut self,
+        providers: impl IntoIterator<Item = Box<dyn SearchProvider>>,
+    ) -> Self {
+        self.providers.extend(providers.into_iter().map(Arc::from));
+        self
+    }
+
+    /// Share this HTTP client between the built-in providers.
+    ///
+    /// The client's own timeouts are kept; each search is still cut off at the
+    /// provider's configured timeout.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Serve repeated provider searches from this cache
+    pub fn cache(mut self, cache: Arc<CacheManager>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Check each provider search against this rate limiter
+    pub fn rate_limiter(mut self, rate_limiter: Arc<RateLimiterManager>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Run each provider search through this circuit breaker
+    pub fn circuit_breaker(mut self, circuit_breaker: Arc<CircuitBreakerManager>) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    /// Record provider metrics in this collector instead of a new one
+    pub fn metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Build the client.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are no providers to search with.
+    pub fn build(self) -> Result<OmnisearchClient, ProviderError> {
+        let builtin = match &self.config {
+            Some(config) => {
+                ProviderFactory::create_search_providers_from(config, self.http_client.as_ref())
+            }
+            None => Vec::new(),
+        };
+        let providers: HashMap<String, Arc<dyn SearchProvider>> = builtin
+            .into_iter()
+            .map(Arc::from)
+            .chain(self.providers)
+            .map(|provider| (provider.name().to_string(), provider))
+            .collect();
+
+        if providers.is_empty() {
+            return Err(ProviderError::new(
+                ErrorType::ApiError,
+                "No search providers could be initialized. Please check your API keys.".to_string(),
+                "client".to_string(),
+                None,
+            ));
+        }
+
+        let config = self.config.unwrap_or_default();
+        let metrics = self
+            .metrics
+            .unwrap_or_else(|| Arc::new(MetricsCollector::with_config(&config.metrics)));
+        Ok(OmnisearchClient {
+            providers,
+            provider_timeout: None,
+            merge_strategy: config.search.merge_strategy,
+            provider_weights: config.search.provider_weights.clone(),
+            merger: None,
+            hedging: config.search.hedging.clone(),
+            services: SearchServices {
+                metrics,
+                cache: self.cache,
+                rate_limiter: self.rate_limiter,
+                circuit_breaker: self.circuit_breaker,
+                cache_ttl: Duration::from_secs(config.cache.ttl_seconds),
+            },
+            config: Some(Arc::new(config)),
+        })
+    }
+}
+
+// A client page token holds each provider's own token, as `provider=token&...`
+pub(crate) fn encode_page_token(
+    pages: impl IntoIterator<Item = (String, String)>,
+) -> Option<String> {
+    let mut pages = pages.into_iter().peekable();
+    pages.peek()?;
+    let mut token = url::form_urlencoded::Serializer::new(String::new());
+    for (provider, page) in pages {
+        token.append_pair(&provider, &page);
+    }
+    Some(token.finish())
+}
+
+fn decode_page_token(token: &str) -> Result<Vec<(String, String)>, ProviderError> {
+    let pages: Vec<(String, String)> = url::form_urlencoded::parse(token.as_bytes())
+        .into_owned()
+        .collect();
+    if pages.is_empty() || pages.iter().any(|(provider, _)| provider.is_empty()) {
+        return Err(ProviderError::new(
+            crate::common::types::ErrorType::InvalidInput,
+            "Invalid page token".to_string(),
+            "client".to_string(),
+            None,
+        ));
+    }
+    Ok(pages)
+}
+
+// A provider search that gives up after `timeout`, whatever HTTP client the
+// provider was built with
+async fn search_within(
+    provider: &dyn SearchProvider,
+    params: BaseSearchParams,
+    timeout: Duration,
+) -> Result<Vec<SearchResult>, ProviderError> {
+    tokio::time::timeout(timeout, provider.search(params))
+        .await
+        .unwrap_or_else(|_| {
+            Err(ProviderError::new(
+                ErrorType::Timeout,
+                format!("Search timed out after {}s", timeout.as_secs_f64()),
+                provider.name().to_string(),
+                None,
+            ))
+        })
+}
+
+// Run `future` to completion, or until `deadline` passes
+async fn within<F: std::future::Future>(
+    deadline: Option<Duration>,
+    future: F,
+) -> Option<F::Output> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout(deadline, future).await.ok(),
+        None => Some(future.await),
+    }
+}
+
+// The error for a search whose deadline passed before any provider succeeded
+fn deadline_error(
+    deadline: Option<Duration>,
+    failures: &[ProviderError],
+    timed_out: &[String],
+) -> ProviderError {
+    let reasons: Vec<String> = failures
+        .iter()
+        .map(|e| format!("{}: {}", e.provider, e.message))
+        .chain(
+            timed_out
+                .iter()
+                .map(|provider| format!("{}: no answer before the deadline", provider)),
+        )
+        .collect();
+    ProviderError::new(
+        crate::common::types::ErrorType::Timeout,
+        format!(
+            "No provider answered within the {}s deadline ({})",
+            deadline.unwrap_or_default().as_secs_f64(),
+            reasons.join("; ")
+        ),
+        "client".to_string(),
+        None,
+    )
+}
+
+// The outcome of trying one provider, or a provider and its hedge
+struct SearchAttempt {
+    success: Option<(String, Vec<SearchResult>)>,
+    failures: Vec<ProviderError>,
+    // Whether the next provider in the order was used as a hedge
+    hedged: bool,
+}
+
+impl SearchAttempt {
+    fn from_result(
+        provider: String,
+        result: Result<Vec<SearchResult>, ProviderError>,
+        hedged: bool,
+    ) -> Self {
+        match result {
+            Ok(results) => Self {
+                success: Some((provider, results)),
+                failures: Vec::new(),
+                hedged,
+            },
+            Err(e) => Self {
+                success: None,
+                failures: vec![e],
+                hedged,
+            },
+        }
+    }
+}
+
+// What each provider search goes through: the client's metrics and, when the
+// client was built with them, its cache, rate limiter and circuit breaker
+#[derive(Clone)]
+struct SearchServices {
+    metrics: Arc<MetricsCollector>,
+    cache: Option<Arc<CacheManager>>,
+    rate_limiter: Option<Arc<RateLimiterManager>>,
+    circuit_breaker: Option<Arc<CircuitBreakerManager>>,
+    cache_ttl: Duration,
+}
+
+impl SearchServices {
+    // Run a provider search, giving up after `timeout`, and record it in the
+    // provider's metrics
+    async fn search(
+        &self,
+        provider: Arc<dyn SearchProvider>,
+        params: BaseSearchParams,
+        timeout: Duration,
+    ) -> Result<Vec<SearchResult>, ProviderError> {
+        let name = provider.name();
+        if let Err(e) = validate_page_token(provider.as_ref(), &params) {
+            return Err(ProviderError::new(
+                ErrorType::InvalidInput,
+                e.to_string(),
+                name.to_string(),
+                None,
+            ));
+        }
+        let params = within_limits(provider.as_ref(), params);
+        let start = Instant::now();
+
+        // Results depend on every parameter, not just the query and limit
+        let cache_key = format!(
+            "{}:{}",
+            name,
+            serde_json::to_string(&params).unwrap_or_default()
+        );
+        if let Some(cache) = &self.cache {
+            if let Ok(Some(results)) = cache.get(&cache_key).await {
+                self.record(name, start, true, true).await;
+                return Ok(results);
+            }
+        }
+
+        if let Some(rate_limiter) = &self.rate_limiter {
+            if let Err(e) = rate_limiter.check_rate_limit(name).await {
+                self.record(name, start, false, false).await;
+                return Err(ProviderError::new(
+                    ErrorType::RateLimit,
+                    e.to_string(),
+                    name.to_string(),
+                    None,
+                ));
+            }
+        }
+
+        let result = match &self.circuit_breaker {
+            Some(circuit_breaker) => {
+                let search = Arc::clone(&provider);
+                circuit_breaker
+                    .call(name, move || async move {
+                        search_within(search.as_ref(), params, timeout)
+                            .await
+                            .map_err(eyre::Report::new)
+                    })
+                    .await
+                    .map_err(|e| match e.downcast::<ProviderError>() {
+                        Ok(e) => e,
+                        // Anything else is the breaker turning the call away
+                        Err(e) => ProviderError::new(
+                            ErrorType::CircuitOpen,
+                            e.to_string(),
+                            name.to_string(),
+                            None,
+                        ),
+                    })
+            }
+            None => search_within(provider.as_ref(), params, timeout).await,
+        };
+        self.record(name, start, result.is_ok(), false).await;
+
+        if let (Some(cache), Ok(results)) = (&self.cache, &result) {
+            if let Err(e) = cache.set(&cache_key, results.clone(), self.cache_ttl).await {
+                warn!("Failed to cache {} results: {}", name, e);
+            }
+        }
+        result
+    }
+
+    async fn record(&self, provider: &str, start: Instant, success: bool, cache_hit: bool) {
+        self.metrics
+            .record_request(RequestMetrics {
+                provider: provider.to_string(),
+                operation: "search".to_string(),
+                duration: start.elapsed(),
+                success,
+                response_size: None,
+                cache_hit,
+            })
+            .await;
+    }
+}
+
+/// A request for performing a search.
+///
+/// This builder-style struct allows you to configure search parameters
+/// in a fluent, readable way.
+#[derive(Debug, Clone)]
+pub struct SearchRequest {
+    pub query: String,
+    pub limit: Option<u32>,
+    pub include_domains: Option<Vec<String>>,
+    pub exclude_domains: Option<Vec<String>>,
+    pub preferred_provider: Option<String>,
+    pub category: Option<SearchCategory>,
+    pub merge_strategy: Option<MergeStrategy>,
+    pub max_providers: Option<usize>,
+    pub deadline: Option<Duration>,
+    pub offset: Option<u32>,
+    pub page_token: Option<String>,
+    pub time_range: Option<TimeRange>,
+    pub country: Option<String>,
+    pub language: Option<String>,
+    pub safe_search: Option<SafeSearch>,
+    pub vertical: Option<SearchVertical>,
+}
+
+impl SearchRequest {
+    /// Create a new search request with the given query.
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            limit: None,
+            include_domains: None,
+            exclude_domains: None,
+            preferred_provider: None,
+            category: None,
+            merge_strategy: None,
+            max_providers: None,
+            deadline: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
+        }
+    }
+
+    /// Set the maximum number of results to return.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Include only results from the specified domains.
+    pub fn include_domains(mut self, domains: &[&str]) -> Self {
+        self.include_domains = Some(domains.iter().map(|s| s.to_string()).collect());
+        self
+    }
+
+    /// Exclude results from the specified domains.
+    pub fn exclude_domains(mut self, domains: &[&str]) -> Self {
+        self.exclude_domains = Some(domains.iter().map(|s| s.to_string()).collect());
+        self
+    }
+
+    /// Prefer a specific search provider.
+    pub fn provider(mut self, provider: impl Into<String>) -> Self {
+        self.preferred_provider = Some(provider.into());
+        self
+    }
+
+    /// Hint at the kind of results wanted, which steers provider routing.
+    pub fn category(mut self, category: SearchCategory) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    /// Choose how results from several providers are combined in `multi_search`.
+    pub fn merge_strategy(mut self, strategy: MergeStrategy) -> Self {
+        self.merge_strategy = Some(strategy);
+        self
+    }
+
+    /// Query at most this many providers in `search_stream`.
+    pub fn max_providers(mut self, max_providers: usize) -> Self {
+        self.max_providers = Some(max_providers);
+        self
+    }
+
+    /// Give up waiting for providers after this long.
+    ///
+    /// `multi_search` and `search_stream` then return the results that have
+    /// arrived, marking the response as partial.
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Skip this many results from each provider.
+    pub fn offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Continue from the `next_page_token` of an earlier response.
+    pub fn page_token(mut self, token: impl Into<String>) -> Self {
+        self.page_token = Some(token.into());
+        self
+    }
+
+    /// Only return pages published within this time range.
+    pub fn time_range(mut self, time_range: TimeRange) -> Self {
+        self.time_range = Some(time_range);
+        self
+    }
+
+    /// Favour results from a country, given as an ISO 3166-1 code such as `us`.
+    pub fn country(mut self, country: impl Into<String>) -> Self {
+        self.country = Some(country.into());
+        self
+    }
+
+    /// Favour results in a language, given as an ISO 639-1 code such as `en`.
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Set how strictly adult content is filtered.
+    pub fn safe_search(mut self, safe_search: SafeSearch) -> Self {
+        self.safe_search = Some(safe_search);
+        self
+    }
+
+    /// Ask for news, images, videos or academic results rather than web pages.
+    ///
+    /// Only providers that return that kind of result are used.
+    pub fn vertical(mut self, vertical: SearchVertical) -> Self {
+        self.vertical = Some(vertical);
+        self
+    }
+
+    /// Convert this request into BaseSearchParams for use with providers.
+    pub(crate) fn into_search_params(self) -> BaseSearchParams {
+        BaseSearchParams {
+            query: self.query,
+            limit: self.limit,
+            include_domains: self.include_domains,
+            exclude_domains: self.exclude_domains,
+            offset: self.offset,
+            // Client page tokens are split into each provider's own token
+            page_token: None,
+            time_range: self.time_range,
+            country: self.country,
+            language: self.language,
+            safe_search: self.safe_search,
+            vertical: self.vertical,
+        }
+    }
+}
+
+/// The response from a search operation.
+#[derive(Debug)]
+pub struct SearchResponse {
+    /// The search results.
+    pub results: Vec<SearchResult>,
+    /// The names of providers that were used to generate these results.
+    pub providers_used: Vec<String>,
+    /// The original search query.
+    pub query: String,
+    /// Providers that were tried and failed, with the reason each one failed.
+    pub failures: Vec<ProviderError>,
+    /// Whether the request's deadline passed before every provider answered.
+    pub partial: bool,
+    /// Providers that had not answered when the deadline passed.
+    pub timed_out: Vec<String>,
+    /// Pass to [`SearchRequest::page_token`] to fetch the next page, if there is one.
+    pub next_page_token: Option<String>,
+    /// Filters that providers whose results were used could not apply.
+    pub ignored_filters: BTreeMap<String, Vec<SearchFilter>>,
+}
+
+impl SearchResponse {
+    /// Get the number of results.
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Check if the response is empty.
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// Get results from a specific provider, including pages it returned that
+    /// were merged with another provider's copy.
+    pub fn results_from_provider(&self, provider: &str) -> Vec<&SearchResult> {
+        self.results
+            .iter()
+            .filter(|r| r.providers().any(|p| p == provider))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::metrics::{get_provider_stats, record_request_metrics};
+    use crate::common::types::Pagination;
+
+    #[test]
+    fn test_search_request_builder() {
+        let request = SearchRequest::new("test query")
+            .limit(10)
+            .include_domains(&["example.com", "test.org"])
+            .provider("tavily");
+
+        assert_eq!(request.query, "test query");
+        assert_eq!(request.limit, Some(10));
+        assert_eq!(request.preferred_provider, Some("tavily".to_string()));
+        assert_eq!(
+            request.include_domains,
+            Some(vec!["example.com".to_string(), "test.org".to_string()])
+        );
+    }
+
+    struct NamedProvider(&'static str);
+
+    #[async_trait::async_trait]
+    impl SearchProvider for NamedProvider {
+        async fn search(
+            &self,
+            _params: BaseSearchParams,
+        ) -> Result<Vec<SearchResult>, ProviderError> {
+            Ok(vec![])
+        }
+
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn description(&self) -> &'static str {
+            "Named test provider"
+        }
+    }
+
+    fn client(names: &[&'static str]) -> OmnisearchClient {
+        OmnisearchClient::from_providers(
+            names
+                .iter()
+                .map(|name| Arc::new(NamedProvider(name)) as Arc<dyn SearchProvider>),
+        )
+    }
+
+    #[test]
+    fn test_route_follows_category() {
+        let client = client(&["duckduckgo", "exa", "reddit", "tavily", "custom"]);
+
+        assert_eq!(
+            client.route(&SearchRequest::new("rust")),
+            vec!["tavily", "exa", "duckduckgo", "reddit", "custom"]
+        );
+        assert_eq!(
+            client.route(&SearchRequest::new("rust").category(SearchCategory::Research))[0],
+            "exa"
+        );
+        assert_eq!(
+            client.route(&SearchRequest::new("rust").category(SearchCategory::Discussion))[0],
+            "reddit"
+        );
+    }
+
+    #[test]
+    fn test_route_uses_query_and_domain_hints() {
+        let client = client(&["duckduckgo", "baidu", "reddit", "exa"]);
+
+        let request = SearchRequest::new("rust").include_domains(&["docs.rs"]);
+        assert_eq!(client.route(&request)[0], "exa");
+
+        let request = SearchRequest::new("best keyboard reddit");
+        assert_eq!(client.route(&request)[0], "reddit");
+
+        let request = SearchRequest::new("rust").include_domains(&["www.reddit.com"]);
+        assert_eq!(client.route(&request)[0], "reddit");
+
+        assert_eq!(client.route(&SearchRequest::new("异步编程"))[0], "baidu");
+    }
+
+    // A provider that applies the given filters and answers in the given languages
+    struct CapableProvider(&'static str, Vec<SearchFilter>, Vec<String>);
+
+    #[async_trait::async_trait]
+    impl SearchProvider for CapableProvider {
+        async fn search(
+            &self,
+            _params: BaseSearchParams,
+        ) -> Result<Vec<SearchResult>, ProviderError> {
+            Ok(vec![])
+        }
+
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn description(&self) -> &'static str {
+            "Capable test provider"
+        }
+
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                filters: self.1.clone(),
+                languages: self.2.clone(),
+                ..Default::default()
+            }
+        }
+    }
+
+    #[test]
+    fn test_route_prefers_providers_that_apply_filters() {
+        let client = OmnisearchClient::from_providers([
+            Arc::new(CapableProvider("tavily", vec![], vec![])) as Arc<dyn SearchProvider>,
+            Arc::new(CapableProvider(
+                "duckduckgo",
+                vec![SearchFilter::Country, SearchFilter::Language],
+                vec![],
+            )),
+            Arc::new(CapableProvider(
+                "baidu",
+                vec![SearchFilter::Country, SearchFilter::Language],
+                vec!["zh".to_string()],
+            )),
+        ]);
+
+        assert_eq!(
+            client.route(&SearchRequest::new("rust")),
+            vec!["tavily", "duckduckgo", "baidu"]
+        );
+        assert_eq!(
+            client.route(&SearchRequest::new("rust").country("de")),
+            vec!["duckduckgo", "baidu", "tavily"]
+        );
+        assert_eq!(
+            client.route(&SearchRequest::new("rust").language("en")),
+            vec!["duckduckgo", "tavily", "baidu"]
+        );
+        assert_eq!(
+            client.route(&SearchRequest::new("rust").language("zh-CN")),
+            vec!["duckduckgo", "baidu", "tavily"]
+        );
+    }
+
+    struct SlowProvider {
+        name: &'static str,
+        delay: Duration,
+        fail: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl SearchProvider for SlowProvider {
+        async fn search(
+            &self,
+            params: BaseSearchParams,
+        ) -> Result<Vec<SearchResult>, ProviderError> {
+            tokio::time::sleep(self.delay).await;
+            if self.fail {
+                return Err(ProviderError::new(
+                    crate::common::types::ErrorType::ApiError,
+                    "Upstream unavailable".to_string(),
+                    self.name.to_string(),
+                    None,
+                ));
+            }
+            Ok(vec![SearchResult {
+                title: format!("{}: {}", self.name, params.query),
+                url: format!("https://{}.example.com", self.name),
+                snippet: String::new(),
+                score: None,
+                source_provider: self.name.to_string(),
+                ..Default::default()
+            }])
+        }
+
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn description(&self) -> &'static str {
+            "Slow test provider"
+        }
+    }
+
+    fn slow(name: &'static str, millis: u64, fail: bool) -> Arc<dyn SearchProvider> {
+        Arc::new(SlowProvider {
+            name,
+            delay: Duration::from_millis(millis),
+            fail,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_multi_search_merges_concurrent_results() {
+        let client = OmnisearchClient::from_providers([
+            slow("tavily", 200, false),
+            slow("exa", 200, false),
+            slow("duckduckgo", 200, true),
+            slow("reddit", 5_000, false),
+        ])
+        .with_provider_timeout(Duration::from_millis(500));
+
+        let start = std::time::Instant::now();
+        let response = client
+            .multi_search(SearchRequest::new("rust"), 4)
+            .await
+            .unwrap();
+        assert!(start.elapsed() < Duration::from_secs(2));
+
+        assert_eq!(response.providers_used, vec!["tavily", "exa"]);
+        assert_eq!(response.results.len(), 2);
+        assert_eq!(response.results[0].source_provider, "tavily");
+
+        let failed: Vec<&str> = response
+            .failures
+            .iter()
+            .map(|e| e.provider.as_str())
+            .collect();
+        assert_eq!(failed, vec!["duckduckgo", "reddit"]);
+        assert!(response.failures[1].message.contains("timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_search_times_out_each_provider() {
+        let client = OmnisearchClient::from_providers([
+            slow("reddit", 5_000, false),
+            slow("exa", 5_000, false),
+        ])
+        .with_provider_timeout(Duration::from_millis(100));
+
+        let start = std::time::Instant::now();
+        let error = client
+            .search(SearchRequest::new("rust").provider("reddit"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.error_type, ErrorType::Timeout);
+        assert_eq!(error.provider, "reddit");
+
+        // Falling back, each provider gets its own timeout
+        let error = client.search(SearchRequest::new("rust")).await.unwrap_err();
+        assert_eq!(error.error_type, ErrorType::Timeout);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_search_reports_ignored_filters() {
+        let client = client(&["tavily", "exa"]);
+        let request = SearchRequest::new("rust")
+            .time_range(TimeRange::Week)
+            .country("de");
+
+        let response = client.multi_search(request.clone(), 2).await.unwrap();
+        assert_eq!(
+            response.ignored_filters.get("tavily"),
+            Some(&vec![SearchFilter::TimeRange, SearchFilter::Country])
+        );
+        assert!(response.ignored_filters.contains_key("exa"));
+
+        let response = client.search(request.provider("exa")).await.unwrap();
+        assert_eq!(response.ignored_filters.keys().collect::<Vec<_>>(), ["exa"]);
+
+        let response = client.search(SearchRequest::new("rust")).await.unwrap();
+        assert!(response.ignored_filters.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_stream_reports_providers_as_they_finish() {
+        let client = OmnisearchClient::from_providers([
+            slow("tavily", 300, false),
+            slow("exa", 10, false),
+            slow("duckduckgo", 100, true),
+        ]);
+
+        let events: Vec<ProviderEvent> = client
+            .search_stream(SearchRequest::new("rust"))
+            .collect()
+            .await;
+        assert_eq!(events.len(), 5);
+
+        match &events[0] {
+            ProviderEvent::Started { providers } => {
+                assert_eq!(providers, &["tavily", "exa", "duckduckgo"])
+            }
+            event => panic!("unexpected first event: {:?}", event),
+        }
+        let finished: Vec<&str> = events[1..4]
+            .iter()
+            .map(|event| match event {
+                ProviderEvent::Results { provider, .. } => provider.as_str(),
+                ProviderEvent::Failed { provider, error } => {
+                    assert_eq!(error.message, "Upstream unavailable");
+                    provider.as_str()
+                }
+                event => panic!("unexpected provider event: {:?}", event),
+            })
+            .collect();
+        assert_eq!(finished, vec!["exa", "duckduckgo", "tavily"]);
+
+        // The summary keeps routing order, not completion order
+        match &events[4] {
+            ProviderEvent::Completed(Ok(response)) => {
+                assert_eq!(response.providers_used, vec!["tavily", "exa"]);
+                assert_eq!(response.results[0].source_provider, "tavily");
+                assert_eq!(response.failures.len(), 1);
+            }
+            event => panic!("unexpected last event: {:?}", event),
+        }
+
+        let events: Vec<ProviderEvent> = client
+            .search_stream(SearchRequest::new("rust").provider("brave"))
+            .collect()
+            .await;
+        assert!(matches!(
+            events.as_slice(),
+            [ProviderEvent::Completed(Err(_))]
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_deadline_returns_partial_results() {
+        let client = OmnisearchClient::from_providers([
+            slow("tavily", 10, false),
+            slow("exa", 5_000, false),
+            slow("duckduckgo", 20, true),
+        ]);
+
+        let start = std::time::Instant::now();
+        let response = client
+            .multi_search(
+                SearchRequest::new("rust").deadline(Duration::from_millis(300)),
+                3,
+            )
+            .await
+            .unwrap();
+        assert!(start.elapsed() < Duration::from_secs(2));
+
+        assert!(response.partial);
+        assert_eq!(response.timed_out, vec!["exa"]);
+        assert_eq!(response.providers_used, vec!["tavily"]);
+        assert_eq!(response.failures.len(), 1);
+
+        // A deadline that is not reached leaves the response complete
+        let response = client
+            .multi_search(
+                SearchRequest::new("rust").deadline(Duration::from_secs(10)),
+                1,
+            )
+            .await
+            .unwrap();
+        assert!(!response.partial);
+        assert!(response.timed_out.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_deadline_with_no_answers_is_an_error() {
+        let client =
+            OmnisearchClient::from_providers([slow("tavily", 20, true), slow("exa", 5_000, false)]);
+        let request = SearchRequest::new("rust").deadline(Duration::from_millis(200));
+
+        let error = client.multi_search(request.clone(), 2).await.unwrap_err();
+        assert!(error.message.contains("exa: no answer before the deadline"));
+
+        let error = client.search(request).await.unwrap_err();
+        assert!(error.message.contains("deadline"));
+        assert!(error.message.contains("tavily: Upstream unavailable"));
+        assert!(error.message.contains("exa: no answer before the deadline"));
+    }
+
+    #[tokio::test]
+    async fn test_deadline_before_any_answer_is_a_timeout() {
+        let client = OmnisearchClient::from_providers([
+            slow("tavily", 5_000, false),
+            slow("exa", 5_000, false),
+        ]);
+        let request = SearchRequest::new("rust").deadline(Duration::from_millis(100));
+
+        let error = client.multi_search(request.clone(), 2).await.unwrap_err();
+        assert_eq!(error.error_type, ErrorType::Timeout);
+        assert!(error.is_retryable());
+
+        let error = client.search(request).await.unwrap_err();
+        assert_eq!(error.error_type, ErrorType::Timeout);
+    }
+
+    #[tokio::test]
+    async fn test_multi_search_limits_providers_and_reports_total_failure() {
+        let client = OmnisearchClient::from_providers([
+            slow("tavily", 0, true),
+            slow("exa", 0, true),
+            slow("duckduckgo", 0, false),
+        ]);
+
+        let error = client
+            .multi_search(SearchRequest::new("rust"), 2)
+            .await
+            .unwrap_err();
+        assert!(error.message.contains("tavily: Upstream unavailable"));
+        assert!(error.message.contains("exa: Upstream unavailable"));
+
+        let response = client
+            .multi_search(SearchRequest::new("rust").provider("duckduckgo"), 2)
+            .await
+            .unwrap();
+        assert_eq!(response.providers_used, vec!["duckduckgo"]);
+        assert!(response.failures.is_empty());
+    }
+
+    // Serves `total` numbered results, a page at a time
+    struct PagedProvider {
+        name: &'static str,
+        total: u32,
+    }
+
+    #[async_trait::async_trait]
+    impl SearchProvider for PagedProvider {
+        async fn search(
+            &self,
+            params: BaseSearchParams,
+        ) -> Result<Vec<SearchResult>, ProviderError> {
+            let start = params.start_offset();
+            let end = self.total.min(start + params.limit.unwrap_or(10));
+            Ok((start..end)
+                .map(|n| SearchResult {
+                    title: format!("{} {}", self.name, n),
+                    url: format!("https://{}.example.com/{}", self.name, n),
+                    snippet: String::new(),
+                    score: None,
+                    source_provider: self.name.to_string(),
+                    ..Default::default()
+                })
+                .collect())
+        }
+
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn description(&self) -> &'static str {
+            "Paged test provider"
+        }
+
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                pagination: Pagination::Offset,
+                ..Default::default()
+            }
+        }
+    }
+
+    fn titles(response: &SearchResponse) -> Vec<&str> {
+        response.results.iter().map(|r| r.title.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_search_pages_through_provider() {
+        let client = OmnisearchClient::from_providers([
+            Arc::new(PagedProvider {
+                name: "tavily",
+                total: 5,
+            }) as Arc<dyn SearchProvider>,
+            Arc::new(PagedProvider {
+                name: "exa",
+                total: 50,
+            }),
+        ]);
+
+        let first = client
+            .search(SearchRequest::new("rust").limit(2))
+            .await
+            .unwrap();
+        assert_eq!(titles(&first), vec!["tavily 0", "tavily 1"]);
+        let token = first.next_page_token.unwrap();
+        assert_eq!(token, "tavily=2");
+
+        // The token pins the provider that answered the first page
+        let second = client
+            .search(SearchRequest::new("rust").limit(2).page_token(token))
+            .await
+            .unwrap();
+        assert_eq!(titles(&second), vec!["tavily 2", "tavily 3"]);
+
+        let last = client
+            .search(
+                SearchRequest::new("rust")
+                    .limit(2)
+                    .page_token(second.next_page_token.unwrap()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(titles(&last), vec!["tavily 4"]);
+        assert_eq!(last.next_page_token, None);
+
+        let offset = client
+            .search(
+                SearchRequest::new("rust")
+                    .limit(2)
+                    .offset(3)
+                    .provider("exa"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(titles(&offset), vec!["exa 3", "exa 4"]);
+        assert_eq!(offset.next_page_token.as_deref(), Some("exa=5"));
+
+        let error = client
+            .search(SearchRequest::new("rust").page_token("&&"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.error_type, ErrorType::InvalidInput);
+    }
+
+    #[tokio::test]
+    async fn test_multi_search_pages_each_provider() {
+        let client = OmnisearchClient::from_providers([
+            Arc::new(PagedProvider {
+                name: "tavily",
+                total: 3,
+            }) as Arc<dyn SearchProvider>,
+            Arc::new(PagedProvider {
+                name: "exa",
+                total: 50,
+            }),
+        ]);
+
+        let first = client
+            .multi_search(SearchRequest::new("rust").limit(2), 2)
+            .await
+            .unwrap();
+        // Each provider continues after the one result the limit kept
+        let token = first.next_page_token.unwrap();
+        assert_eq!(token, "tavily=1&exa=1");
+
+        // Tavily runs out on the second page and drops out of the third
+        let second = client
+            .multi_search(SearchRequest::new("rust").limit(4).page_token(token), 2)
+            .await
+            .unwrap();
+        assert_eq!(second.providers_used, vec!["tavily", "exa"]);
+        assert_eq!(
+            titles(&second),
+            vec!["tavily 1", "exa 1", "tavily 2", "exa 2"]
+        );
+        assert_eq!(second.next_page_token.as_deref(), Some("exa=3"));
+    }
+
+    #[tokio::test]
+    async fn test_multi_search_pages_lose_no_results() {
+        let client = OmnisearchClient::from_providers([
+            Arc::new(PagedProvider {
+                name: "tavily",
+                total: 5,
+            }) as Arc<dyn SearchProvider>,
+            Arc::new(PagedProvider {
+                name: "exa",
+                total: 7,
+            }),
+        ]);
+
+        // Each page asks both providers for 3 results but keeps only 3 in all
+        let mut seen = Vec::new();
+        let mut request = SearchRequest::new("rust").limit(3);
+        for _ in 0..10 {
+            let page = client.multi_search(request.clone(), 2).await.unwrap();
+            seen.extend(titles(&page).into_iter().map(String::from));
+            match page.next_page_token {
+                Some(token) => request = SearchRequest::new("rust").limit(3).page_token(token),
+                None => break,
+            }
+        }
+
+        seen.sort();
+        let mut expected: Vec<String> = (0..5)
+            .map(|n| format!("tavily {}", n))
+            .chain((0..7).map(|n| format!("exa {}", n)))
+            .collect();
+        expected.sort();
+        assert_eq!(seen, expected);
+    }
+
+    #[tokio::test]
+    async fn test_search_batch_keeps_order_and_bounds_concurrency() {
+        let client =
+            OmnisearchClient::from_providers([slow("tavily", 100, false), slow("exa", 0, true)]);
+        let requests = vec![
+            SearchRequest::new("one"),
+            SearchRequest::new("two").provider("exa"),
+            SearchRequest::new("three"),
+            SearchRequest::new("four"),
+        ];
+
+        // Three searches of 100ms each, two at a time, take two rounds
+        let start = std::time::Instant::now();
+        let outcomes = client.search_batch(requests, 2).await;
+        assert!(start.elapsed() >= Duration::from_millis(200));
+
+        assert_eq!(outcomes.len(), 4);
+        assert_eq!(
+            outcomes[0].as_ref().unwrap().results[0].title,
+            "tavily: one"
+        );
+        assert_eq!(outcomes[1].as_ref().unwrap_err().provider, "exa");
+        assert_eq!(
+            outcomes[2].as_ref().unwrap().results[0].title,
+            "tavily: three"
+        );
+        assert_eq!(
+            outcomes[3].as_ref().unwrap().results[0].title,
+            "tavily: four"
+        );
+    }
+
+    struct ReverseMerger;
+
+    impl ResultMerger for ReverseMerger {
+        fn merge(&self, ranked: Vec<ProviderResults>) -> Vec<SearchResult> {
+            ranked
+                .into_iter()
+                .rev()
+                .flat_map(|list| list.results)
+                .collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multi_search_merge_strategy_selection() {
+        let client =
+            OmnisearchClient::from_providers([slow("tavily", 0, false), slow("exa", 0, false)])
+                .with_merger(ReverseMerger);
+
+        let response = client
+            .multi_search(SearchRequest::new("rust"), 2)
+            .await
+            .unwrap();
+        assert_eq!(response.results[0].source_provider, "exa");
+
+        // A strategy on the request overrides the client's merger
+        let request = SearchRequest::new("rust").merge_strategy(MergeStrategy::RoundRobin);
+        let response = client.multi_search(request, 2).await.unwrap();
+        assert_eq!(response.results[0].source_provider, "tavily");
+
+        let client = client
+            .with_merge_strategy(MergeStrategy::Weighted)
+            .with_provider_weights(HashMap::from([("exa".to_string(), 2.0)]));
+        let response = client
+            .multi_search(SearchRequest::new("rust").limit(1), 2)
+            .await
+            .unwrap();
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.results[0].source_provider, "exa");
+    }
+
+    #[tokio::test]
+    async fn test_search_records_fallback_failures() {
+        let client =
+            OmnisearchClient::from_providers([slow("tavily", 0, true), slow("exa", 0, false)]);
+
+        let response = client.search(SearchRequest::new("rust")).await.unwrap();
+        assert_eq!(response.providers_used, vec!["exa"]);
+        assert_eq!(response.failures.len(), 1);
+        assert_eq!(response.failures[0].provider, "tavily");
+    }
+
+    fn hedging() -> HedgingConfig {
+        HedgingConfig {
+            enabled: true,
+            percentile: 95.0,
+            min_samples: 5,
+        }
+    }
+
+    async fn record_latency(provider: &str, millis: u64) {
+        for _ in 0..5 {
+            record_request_metrics(
+                provider,
+                "search",
+                Duration::from_millis(millis),
+                true,
+                None,
+                false,
+            )
+            .await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hedged_search_uses_faster_provider() {
+        // Unknown providers are routed in name order, so "hedge-a-slow" goes first
+        let client = OmnisearchClient::from_providers([
+            slow("hedge-a-slow", 5_000, false),
+            slow("hedge-b-fast", 0, false),
+        ])
+        .with_hedging(hedging());
+        record_latency("hedge-a-slow", 20).await;
+
+        let start = std::time::Instant::now();
+        let response = client.search(SearchRequest::new("rust")).await.unwrap();
+
+        if crate::common::metrics::METRICS_COLLECTOR.is_enabled() {
+            assert!(start.elapsed() < Duration::from_secs(2));
+            assert_eq!(response.providers_used, vec!["hedge-b-fast"]);
+            assert!(response.failures.is_empty());
+
+            let slow_stats = get_provider_stats("hedge-a-slow").await.unwrap();
+            assert_eq!(slow_stats.hedged_requests, 1);
+            let fast_stats = get_provider_stats("hedge-b-fast").await.unwrap();
+            assert_eq!(fast_stats.hedge_wins, 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hedged_search_falls_back_when_both_fail() {
+        let client = OmnisearchClient::from_providers([
+            slow("hedge-c-failing", 300, true),
+            slow("hedge-d-failing", 0, true),
+            slow("hedge-e-ok", 0, false),
+        ])
+        .with_hedging(hedging());
+        record_latency("hedge-c-failing", 10).await;
+
+        let response = client.search(SearchRequest::new("rust")).await.unwrap();
+        assert_eq!(response.providers_used, vec!["hedge-e-ok"]);
+        let failed: Vec<&str> = response
+            .failures
+            .iter()
+            .map(|e| e.provider.as_str())
+            .collect();
+        if crate::common::metrics::METRICS_COLLECTOR.is_enabled() {
+            // The hedge fails first, then the primary
+            assert_eq!(failed, vec!["hedge-d-failing", "hedge-c-failing"]);
+        } else {
+            assert_eq!(failed, vec!["hedge-c-failing", "hedge-d-failing"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_no_hedging_without_latency_history() {
+        let client = OmnisearchClient::from_providers([
+            slow("hedge-f-primary", 200, false),
+            slow("hedge-g-backup", 0, false),
+        ])
+        .with_hedging(hedging());
+
+        let response = client.search(SearchRequest::new("rust")).await.unwrap();
+        assert_eq!(response.providers_used, vec!["hedge-f-primary"]);
+    }
+
+    #[test]
+    fn test_results_from_provider_includes_merged_pages() {
+        let mut merged = SearchResult {
+            title: "Rust".to_string(),
+            url: "https://www.rust-lang.org".to_string(),
+            snippet: String::new(),
+            score: None,
+            source_provider: "tavily".to_string(),
+            also_found_by: vec!["exa".to_string()],
+            ..Default::default()
+        };
+        let response = SearchResponse {
+            results: vec![merged.clone()],
+            providers_used: vec!["tavily".to_string(), "exa".to_string()],
+            query: "rust".to_string(),
+            failures: Vec::new(),
+            partial: false,
+            timed_out: Vec::new(),
+            next_page_token: None,
+            ignored_filters: BTreeMap::new(),
+        };
+        assert_eq!(response.results_from_provider("exa").len(), 1);
+        assert!(response.results_from_provider("brave").is_empty());
+
+        merged.also_found_by.clear();
+        assert_eq!(merged.providers().collect::<Vec<_>>(), vec!["tavily"]);
+    }
+
+    #[test]
+    fn test_search_params_conversion() {
+        let request = SearchRequest::new("test")
+            .limit(5)
+            .exclude_domains(&["spam.com"]);
+
+        let params = request.into_search_params();
+        assert_eq!(params.query, "test");
+        assert_eq!(params.limit, Some(5));
+        assert_eq!(params.exclude_domains, Some(vec!["spam.com".to_string()]));
+    }
+}
@@ -0,0 +1,339 @@
+//! Multi-provider search as a stream of per-provider events.
+
+use super::{encode_page_token, ProviderResults, ResultMerger, SearchResponse};
+use crate::common::canonical_url::{canonicalize_url, dedup_results};
+use crate::common::types::{
+    BaseSearchParams, ErrorType, Pagination, ProviderError, SearchFilter, SearchProvider,
+    SearchResult,
+};
+use futures::stream::{self, Stream};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use tracing::warn;
+
+pub(crate) type SearchOutcome = Result<Vec<SearchResult>, ProviderError>;
+
+// A finished search: its position in the routing order, its outcome and the
+// page it fetched
+pub(crate) type FinishedSearch = (usize, SearchOutcome, Option<FetchedPage>);
+
+// A provider's page as it answered, before filtering, so the next page can
+// start after the results the merged response kept
+pub(crate) struct FetchedPage {
+    pub(crate) provider: Arc<dyn SearchProvider>,
+    pub(crate) params: BaseSearchParams,
+    pub(crate) results: Vec<SearchResult>,
+}
+
+impl FetchedPage {
+    // The provider's token for the page after its results up to the first one
+    // in `dropped`
+    fn next_page_token(&self, dropped: &HashSet<String>) -> Option<String> {
+        let seen = self
+            .results
+            .iter()
+            .take_while(|result| !dropped.contains(&canonicalize_url(&result.url)))
+            .count();
+        if seen == self.results.len() {
+            return self.provider.next_page_token(&self.params, &self.results);
+        }
+        if seen == 0 {
+            // Nothing was kept, so the same page is fetched again. A provider
+            // paging by cursor cannot name its first page and drops out.
+            return self.params.page_token.clone().or_else(|| {
+                (self.provider.capabilities().pagination == Pagination::Offset)
+                    .then(|| self.params.start_offset().to_string())
+            });
+        }
+        // A page of the kept results alone is full, so it is not the last
+        let params = BaseSearchParams {
+            limit: Some(seen as u32),
+            ..self.params.clone()
+        };
+        self.provider
+            .next_page_token(&params, &self.results[..seen])
+    }
+}
+
+/// An update from [`OmnisearchClient::search_stream`](super::OmnisearchClient::search_stream).
+#[derive(Debug)]
+pub enum ProviderEvent {
+    /// The providers being queried, in routing order. Always the first event
+    /// unless the request could not be routed.
+    Started { providers: Vec<String> },
+    /// A provider answered, with results in the provider's own ranking.
+    Results {
+        provider: String,
+        results: Vec<SearchResult>,
+    },
+    /// A provider failed or timed out.
+    Failed {
+        provider: String,
+        error: ProviderError,
+    },
+    /// Every provider has finished: the merged response, or the error returned
+    /// when none succeeded. Always the last event.
+    Completed(Result<SearchResponse, ProviderError>),
+}
+
+// How the finished searches become a response
+pub(crate) struct Completion {
+    pub(crate) query: String,
+    pub(crate) merger: Arc<dyn ResultMerger>,
+    pub(crate) limit: Option<u32>,
+    // Filters each provider leaves out of its search
+    pub(crate) ignored_filters: BTreeMap<String, Vec<SearchFilter>>,
+}
+
+// Searches in flight, indexed by their position in the routing order
+pub(crate) struct ProviderStream {
+    order: Vec<String>,
+    searches: JoinSet<FinishedSearch>,
+    outcomes: Vec<Option<SearchOutcome>>,
+    pages: Vec<Option<FetchedPage>>,
+    started: bool,
+    error: Option<ProviderError>,
+    // When to stop waiting for the searches still running
+    deadline: Option<Instant>,
+    deadline_passed: bool,
+    completion: Option<Completion>,
+}
+
+impl ProviderStream {
+    pub(crate) fn new(
+        order: Vec<String>,
+        searches: JoinSet<FinishedSearch>,
+        deadline: Option<Instant>,
+        completion: Completion,
+    ) -> Self {
+        Self {
+            outcomes: order.iter().map(|_| None).collect(),
+            pages: order.iter().map(|_| None).collect(),
+            order,
+            searches,
+            started: false,
+            error: None,
+            deadline,
+            deadline_passed: false,
+            completion: Some(completion),
+        }
+    }
+
+    // A stream that only reports that the request could not be routed
+    pub(crate) fn failed(error: ProviderError) -> Self {
+        Self {
+            order: Vec::new(),
+            searches: JoinSet::new(),
+            outcomes: Vec::new(),
+            pages: Vec::new(),
+            started: true,
+            error: Some(error),
+            deadline: None,
+            deadline_passed: false,
+            completion: None,
+        }
+    }
+
+    // Dropping the stream drops the JoinSet, which cancels searches still running
+    pub(crate) fn into_stream(self) -> impl Stream<Item = ProviderEvent> + Send + 'static {
+        stream::unfold(self, |mut state| async move {
+            let event = state.next_event().await?;
+            Some((event, state))
+        })
+    }
+
+    async fn next_event(&mut self) -> Option<ProviderEvent> {
+        if let Some(error) = self.error.take() {
+            return Some(ProviderEvent::Completed(Err(error)));
+        }
+        if !self.started {
+            self.started = true;
+            return Some(ProviderEvent::Started {
+                providers: self.order.clone(),
+            });
+        }
+
+        while let Some(joined) = self.join_next().await {
+            let (index, outcome, page) = match joined {
+                Ok(joined) => joined,
+                Err(e) => {
+                    warn!("Provider search task failed: {}", e);
+                    continue;
+                }
+            };
+            let provider = self.order[index].clone();
+            let event = match &outcome {
+                Ok(results) => ProviderEvent::Results {
+                    provider,
+                    results: results.clone(),
+                },
+                Err(e) => {
+                    warn!("{} failed during multi-provider search: {}", provider, e);
+                    ProviderEvent::Failed {
+                        provider,
+                        error: copy_error(e),
+                    }
+                }
+            };
+            self.outcomes[index] = Some(outcome);
+            self.pages[index] = page;
+            return Some(event);
+        }
+
+        let completion = self.completion.take()?;
+        let order = std::mem::take(&mut self.order);
+        let outcomes = std::mem::take(&mut self.outcomes);
+        let pages = std::mem::take(&mut self.pages);
+        Some(ProviderEvent::Completed(completion.complete(
+            order,
+            outcomes,
+            pages,
+            self.deadline_passed,
+        )))
+    }
+
+    // The next search to finish, or none once they all have or the deadline passes
+    async fn join_next(&mut self) -> Option<Result<FinishedSearch, tokio::task::JoinError>> {
+        let Some(deadline) = self.deadline else {
+            return self.searches.join_next().await;
+        };
+        tokio::select! {
+            joined = self.searches.join_next() => joined,
+            _ = tokio::time::sleep_until(deadline) => {
+                // Dropping the old set cancels the searches still running
+                self.searches = JoinSet::new();
+                self.deadline = None;
+                self.deadline_passed = true;
+                None
+            }
+        }
+    }
+}
+
+impl Completion {
+    // Merge the successful results in routing order, or explain why there are none.
+    // Providers without an outcome were cut off by the deadline if it passed.
+    fn complete(
+        self,
+        order: Vec<String>,
+        outcomes: Vec<Option<SearchOutcome>>,
+        pages: Vec<Option<FetchedPage>>,
+        deadline_passed: bool,
+    ) -> Result<SearchResponse, ProviderError> {
+        // What each provider returned, to find what the limit cuts
+        let returned: Vec<HashSet<String>> = outcomes
+            .iter()
+            .map(|outcome| match outcome {
+                Some(Ok(results)) => results
+                    .iter()
+                    .map(|result| canonicalize_url(&result.url))
+                    .collect(),
+                _ => HashSet::new(),
+            })
+            .collect();
+        let mut response = SearchResponse {
+            results: Vec::new(),
+            providers_used: Vec::new(),
+            query: self.query,
+            failures: Vec::new(),
+            partial: false,
+            timed_out: Vec::new(),
+            next_page_token: None,
+            ignored_filters: BTreeMap::new(),
+        };
+        let mut ranked = Vec::new();
+        for (provider_name, outcome) in order.into_iter().zip(outcomes) {
+            match outcome {
+                Some(Ok(results)) => {
+                    response.providers_used.push(provider_name.clone());
+                    ranked.push(ProviderResults {
+                        provider: provider_name,
+                        results,
+                    });
+                }
+                Some(Err(e)) => response.failures.push(e),
+                None if deadline_passed => response.timed_out.push(provider_name),
+                None => response.failures.push(ProviderError::new(
+                    ErrorType::ProviderError,
+                    "Search task did not complete".to_string(),
+                    provider_name,
+                    None,
+                )),
+            }
+        }
+
+        if response.providers_used.is_empty() {
+            let reasons: Vec<String> = response
+                .failures
+                .iter()
+                .map(|e| format!("{}: {}", e.provider, e.message))
+                .chain(
+                    response
+                        .timed_out
+                        .iter()
+                        .map(|provider| format!("{}: no answer before the deadline", provider)),
+                )
+                .collect();
+            let message = if reasons.is_empty() {
+                "No providers available for search".to_string()
+            } else {
+                format!("All provider searches failed ({})", reasons.join("; "))
+            };
+            // Only the deadline stopped them, as when a single search runs out of time
+            let error_type = if response.failures.is_empty() && !response.timed_out.is_empty() {
+                ErrorType::Timeout
+            } else {
+                ErrorType::ProviderError
+            };
+            return Err(ProviderError::new(
+                error_type,
+                message,
+                "client".to_string(),
+                None,
+            ));
+        }
+
+        response.partial = !response.timed_out.is_empty();
+        response.ignored_filters = self.ignored_filters;
+        response
+            .ignored_filters
+            .retain(|provider, _| response.providers_used.contains(provider));
+        // Custom mergers may not recognise variants of the same page
+        response.results = dedup_results(self.merger.merge(ranked));
+        if let Some(limit) = self.limit {
+            response.results.truncate(limit as usize);
+        }
+
+        // Each provider's next page starts after the results the response kept.
+        // Providers that reached their last page drop out of it.
+        response.next_page_token = encode_page_token(pages.into_iter().zip(returned).filter_map(
+            |(page, mut dropped)| {
+                let page = page?;
+                let name = page.provider.name();
+                for result in response
+                    .results
+                    .iter()
+                    .filter(|result| result.providers().any(|p| p == name))
+                {
+                    dropped.remove(&canonicalize_url(&result.url));
+                }
+                Some((name.to_string(), page.next_page_token(&dropped)?))
+            },
+        ));
+        Ok(response)
+    }
+}
+
+// The error also goes into the final response; its source cannot be cloned
+fn copy_error(error: &ProviderError) -> ProviderError {
+    ProviderError {
+        error_type: error.error_type.clone(),
+        message: error.message.clone(),
+        provider: error.provider.clone(),
+        http_status: error.http_status,
+        retry_after: error.retry_after,
+        source: None,
+    }
+}
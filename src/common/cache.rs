@@ -77,15 +77,18 @@ pub struct CacheManager {
 
 impl CacheManager {
     pub async fn new() -> Result<Self> {
-        let config = &CONFIG.cache;
+        Ok(Self::with_config(&CONFIG.cache))
+    }
 
+    /// Create a cache from explicit settings rather than the global config
+    pub fn with_config(config: &CacheConfig) -> Self {
         if !config.enabled {
             info!("Cache is disabled");
             let dummy_cache = MemoryCache::new(config);
-            return Ok(Self {
+            return Self {
                 provider: Box::new(dummy_cache),
                 enabled: false,
-            });
+            };
         }
 
         let provider: Box<dyn CacheProvider> = match config.cache_type {
@@ -95,10 +98,10 @@ impl CacheManager {
             }
         };
 
-        Ok(Self {
+        Self {
             provider,
             enabled: config.enabled,
-        })
+        }
     }
 
     pub async fn get(&self, key: &str) -> Result<Option<CacheValue>> {
@@ -154,6 +157,7 @@ mod tests {
                 snippet: "This is a test result 1".to_string(),
                 source_provider: "test".to_string(),
                 score: Some(0.95),
+                ..Default::default()
             },
             SearchResult {
                 title: "Test Result 2".to_string(),
@@ -161,6 +165,7 @@ mod tests {
                 snippet: "This is a test result 2".to_string(),
                 source_provider: "test".to_string(),
                 score: Some(0.90),
+                ..Default::default()
             },
         ]
     }
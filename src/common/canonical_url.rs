@@ -0,0 +1,243 @@
+use crate::common::types::SearchResult;
+use std::collections::HashMap;
+use url::Url;
+
+// Query parameters that only track where a visitor came from
+const TRACKING_PARAMS: &[&str] = &[
+    "gclid", "gclsrc", "dclid", "fbclid", "msclkid", "yclid", "mc_cid", "mc_eid", "_ga", "_gl",
+    "igshid", "ref_src",
+];
+
+// Query parameters that select an AMP rendering of the same page
+const AMP_PARAMS: &[(&str, Option<&str>)] = &[("amp", None), ("outputType", Some("amp"))];
+
+// Host prefixes for mobile and AMP variants of a site
+const VARIANT_HOST_PREFIXES: &[&str] = &["www.", "m.", "mobile.", "amp."];
+
+fn is_tracking_param(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.starts_with("utm_") || TRACKING_PARAMS.contains(&name.as_str())
+}
+
+fn is_amp_param(name: &str, value: &str) -> bool {
+    AMP_PARAMS
+        .iter()
+        .any(|(param, wanted)| *param == name && wanted.is_none_or(|wanted| wanted == value))
+}
+
+// The page an AMP cache URL serves, e.g. `www.google.com/amp/s/example.com/a`
+// or `example-com.cdn.ampproject.org/c/s/example.com/a`
+fn amp_cache_target(url: &Url) -> Option<String> {
+    let host = url.host_str()?;
+    let path = url.path();
+    let rest = if host.ends_with(".cdn.ampproject.org") {
+        let rest = path.strip_prefix('/')?;
+        let (kind, rest) = rest.split_once('/')?;
+        if !matches!(kind, "c" | "v" | "i") {
+            return None;
+        }
+        rest
+    } else if host.trim_start_matches("www.").starts_with("google.") {
+        path.strip_prefix("/amp/")?
+    } else {
+        return None;
+    };
+
+    let target = rest.strip_prefix("s/").unwrap_or(rest);
+    let mut target = format!("https://{}", target);
+    if let Some(query) = url.query() {
+        target.push('?');
+        target.push_str(query);
+    }
+    Some(target)
+}
+
+fn canonical_path(path: &str) -> String {
+    let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    // `/amp/article` and `/article/amp` both render `/article`
+    if segments.first() == Some(&"amp") {
+        segments.remove(0);
+    }
+    if segments.last() == Some(&"amp") {
+        segments.pop();
+    }
+
+    let path = segments.join("/");
+    let path = match path.strip_suffix(".amp.html") {
+        Some(stem) => format!("{}.html", stem),
+        None => path,
+    };
+    format!("/{}", path)
+}
+
+/// The canonical form of a URL, used to recognise the same page across providers.
+///
+/// The scheme becomes `https`, the host is lowercased and loses `www.`, `m.`,
+/// `mobile.` and `amp.` prefixes, and default ports, fragments, trailing slashes
+/// and tracking parameters (`utm_*`, `gclid`, `fbclid`, ...) are dropped. AMP
+/// variants and AMP cache URLs resolve to the page they render. The remaining
+/// query parameters are sorted. Strings that are not absolute URLs are only trimmed.
+pub fn canonicalize_url(raw: &str) -> String {
+    let raw = raw.trim();
+    let Ok(mut url) = Url::parse(raw) else {
+        return raw.to_string();
+    };
+    if let Some(target) = amp_cache_target(&url) {
+        match Url::parse(&target) {
+            Ok(target) => url = target,
+            Err(_) => return raw.to_string(),
+        }
+    }
+    if !matches!(url.scheme(), "http" | "https") {
+        return raw.to_string();
+    }
+    let Some(mut host) = url.host_str().map(str::to_ascii_lowercase) else {
+        return raw.to_string();
+    };
+
+    while let Some(stripped) = VARIANT_HOST_PREFIXES
+        .iter()
+        .find_map(|prefix| host.strip_prefix(prefix))
+        .filter(|stripped| stripped.contains('.'))
+    {
+        host = stripped.to_string();
+    }
+
+    let mut params: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, value)| !is_tracking_param(name) && !is_amp_param(name, value))
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    params.sort();
+
+    let mut canonical = format!("https://{}", host);
+    if let Some(port) = url.port().filter(|port| *port != 80 && *port != 443) {
+        canonical.push_str(&format!(":{}", port));
+    }
+    let path = canonical_path(url.path());
+    if path != "/" {
+        canonical.push_str(&path);
+    }
+    if !params.is_empty() {
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(params)
+            .finish();
+        canonical.push('?');
+        canonical.push_str(&query);
+    }
+    canonical
+}
+
+/// Drop results whose canonical URL has already been seen.
+///
+/// The first copy of each page is kept in place, and the providers of later
+/// copies are added to its `also_found_by`.
+pub fn dedup_results(results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let mut deduped: Vec<SearchResult> = Vec::with_capacity(results.len());
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for result in results {
+        let key = canonicalize_url(&result.url);
+        match positions.get(&key) {
+            Some(&position) => deduped[position].merge_providers_from(&result),
+            None => {
+                positions.insert(key, deduped.len());
+                deduped.push(result);
+            }
+        }
+    }
+    deduped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracking_params_host_and_path_normalised() {
+        let canonical = "https://example.com/guide?page=2&q=rust";
+        for url in [
+            "https://example.com/guide?q=rust&page=2",
+            "http://www.example.com/guide/?page=2&q=rust&utm_source=news&utm_medium=email",
+            "https://Example.COM:443/guide?gclid=abc&q=rust&page=2#section",
+            "https://m.example.com/guide?fbclid=xyz&page=2&q=rust",
+        ] {
+            assert_eq!(canonicalize_url(url), canonical, "{}", url);
+        }
+
+        assert_eq!(
+            canonicalize_url("https://www.example.com/"),
+            "https://example.com"
+        );
+        assert_eq!(
+            canonicalize_url("http://example.com:8080/a"),
+            "https://example.com:8080/a"
+        );
+        // A bare registrable domain keeps its only label
+        assert_eq!(canonicalize_url("https://m.com/"), "https://m.com");
+    }
+
+    #[test]
+    fn test_amp_variants_resolve_to_the_page() {
+        let canonical = "https://news.example.com/2024/story";
+        for url in [
+            "https://news.example.com/2024/story/amp/",
+            "https://news.example.com/amp/2024/story",
+            "https://amp.news.example.com/2024/story",
+            "https://news.example.com/2024/story?amp=1",
+            "https://news.example.com/2024/story?outputType=amp",
+            "https://www.google.com/amp/s/news.example.com/2024/story/amp",
+            "https://news-example-com.cdn.ampproject.org/c/s/news.example.com/2024/story",
+        ] {
+            assert_eq!(canonicalize_url(url), canonical, "{}", url);
+        }
+
+        assert_eq!(
+            canonicalize_url("https://example.com/post.amp.html"),
+            "https://example.com/post.html"
+        );
+        // Only the `amp` value of outputType selects an AMP page
+        assert_eq!(
+            canonicalize_url("https://example.com/a?outputType=json"),
+            "https://example.com/a?outputType=json"
+        );
+    }
+
+    #[test]
+    fn test_non_web_urls_are_left_alone() {
+        assert_eq!(canonicalize_url("  not a url "), "not a url");
+        assert_eq!(
+            canonicalize_url("mailto:someone@example.com"),
+            "mailto:someone@example.com"
+        );
+    }
+
+    #[test]
+    fn test_dedup_keeps_first_copy_and_every_provider() {
+        let result = |url: &str, provider: &str| SearchResult {
+            title: provider.to_string(),
+            url: url.to_string(),
+            snippet: String::new(),
+            score: None,
+            source_provider: provider.to_string(),
+            ..Default::default()
+        };
+        let results = dedup_results(vec![
+            result(
+                "https://www.example.com/page?utm_source=x",
+                "google_custom_search",
+            ),
+            result("https://example.com/other", "exa"),
+            result("https://example.com/page/", "exa"),
+            result("http://m.example.com/page", "brave"),
+            result("https://example.com/page", "google_custom_search"),
+        ]);
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].url, "https://www.example.com/page?utm_source=x");
+        assert_eq!(results[0].source_provider, "google_custom_search");
+        assert_eq!(results[0].also_found_by, vec!["exa", "brave"]);
+        assert!(results[1].also_found_by.is_empty());
+    }
+}
@@ -0,0 +1,200 @@
+//! Fitting searches to what a provider can do, and making up for the filters
+//! it cannot apply by filtering its results afterwards.
+
+use crate::common::dates::published_within;
+use crate::common::types::{BaseSearchParams, SearchFilter, SearchProvider, SearchResult};
+use url::Url;
+
+/// `params` with the limit cut to the most results `provider` can return
+pub fn within_limits(
+    provider: &dyn SearchProvider,
+    mut params: BaseSearchParams,
+) -> BaseSearchParams {
+    let max_limit = provider.capabilities().max_limit;
+    params.limit = params.limit.map(|limit| limit.min(max_limit));
+    params
+}
+
+/// Drop the results that fail a filter `provider` left out of its search.
+///
+/// Domain filters are applied exactly. A time range or language only drops
+/// results whose publication date or language is known to fall outside it.
+pub fn post_filter(
+    provider: &dyn SearchProvider,
+    params: &BaseSearchParams,
+    mut results: Vec<SearchResult>,
+) -> Vec<SearchResult> {
+    for filter in provider.unsupported_filters(params) {
+        match filter {
+            SearchFilter::IncludeDomains => {
+                let domains = params.include_domains.as_deref().unwrap_or_default();
+                results.retain(|result| domains.iter().any(|d| on_domain(&result.url, d)));
+            }
+            SearchFilter::ExcludeDomains => {
+                let domains = params.exclude_domains.as_deref().unwrap_or_default();
+                results.retain(|result| !domains.iter().any(|d| on_domain(&result.url, d)));
+            }
+            SearchFilter::TimeRange => {
+                if let Some(time_range) = &params.time_range {
+                    results.retain(|result| {
+                        result
+                            .published_at
+                            .as_deref()
+                            .is_none_or(|published| published_within(published, time_range))
+                    });
+                }
+            }
+            SearchFilter::Language => {
+                if let Some(language) = &params.language {
+                    results.retain(|result| {
+                        result
+                            .language
+                            .as_deref()
+                            .is_none_or(|found| same_language(found, language))
+                    });
+                }
+            }
+            SearchFilter::Country | SearchFilter::SafeSearch | SearchFilter::Vertical => {}
+        }
+    }
+    results
+}
+
+/// Filters set in `params` that `provider` leaves out of its search and that
+/// [`post_filter`] cannot apply exactly
+pub fn ignored_filters(
+    provider: &dyn SearchProvider,
+    params: &BaseSearchParams,
+) -> Vec<SearchFilter> {
+    let mut ignored = provider.unsupported_filters(params);
+    ignored.retain(|filter| {
+        !matches!(
+            filter,
+            SearchFilter::IncludeDomains | SearchFilter::ExcludeDomains
+        )
+    });
+    ignored
+}
+
+// Whether `url` is on `domain` or one of its subdomains
+fn on_domain(url: &str, domain: &str) -> bool {
+    let Some(host) = Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_lowercase))
+    else {
+        return false;
+    };
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let domain = domain.trim().to_lowercase();
+    let domain = domain.strip_prefix("www.").unwrap_or(&domain);
+    host == domain
+        || host
+            .strip_suffix(domain)
+            .is_some_and(|subdomain| subdomain.ends_with('.'))
+}
+
+// Whether two language tags, such as `en` and `en-GB`, name the same language
+fn same_language(a: &str, b: &str) -> bool {
+    let primary = |tag: &str| {
+        tag.split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase()
+    };
+    primary(a) == primary(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::types::{ProviderError, TimeRange};
+
+    struct PlainProvider;
+
+    #[async_trait::async_trait]
+    impl SearchProvider for PlainProvider {
+        async fn search(
+            &self,
+            _params: BaseSearchParams,
+        ) -> Result<Vec<SearchResult>, ProviderError> {
+            Ok(vec![])
+        }
+
+        fn name(&self) -> &'static str {
+            "plain"
+        }
+
+        fn description(&self) -> &'static str {
+            "Applies no filters"
+        }
+    }
+
+    fn result(url: &str, published_at: Option<&str>) -> SearchResult {
+        SearchResult {
+            url: url.to_string(),
+            published_at: published_at.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn urls(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.url.as_str()).collect()
+    }
+
+    #[test]
+    fn test_post_filter_domains() {
+        let results = vec![
+            result("https://docs.rs/tokio", None),
+            result("https://www.rust-lang.org/learn", None),
+            result("https://blog.rust-lang.org/2024/", None),
+            result("https://notrust-lang.org/", None),
+        ];
+        let params = BaseSearchParams {
+            include_domains: Some(vec!["rust-lang.org".to_string(), "docs.rs".to_string()]),
+            exclude_domains: Some(vec!["blog.rust-lang.org".to_string()]),
+            ..Default::default()
+        };
+
+        let filtered = post_filter(&PlainProvider, &params, results);
+        assert_eq!(
+            urls(&filtered),
+            ["https://docs.rs/tokio", "https://www.rust-lang.org/learn"]
+        );
+        assert!(ignored_filters(&PlainProvider, &params).is_empty());
+    }
+
+    #[test]
+    fn test_post_filter_time_range_keeps_undated_results() {
+        let results = vec![
+            result("https://a.example/", Some("2024-03-05T10:00:00Z")),
+            result("https://b.example/", Some("2023-12-31T10:00:00Z")),
+            result("https://c.example/", None),
+        ];
+        let params = BaseSearchParams {
+            time_range: Some(TimeRange::Custom {
+                start: Some("2024-01-01".to_string()),
+                end: None,
+            }),
+            ..Default::default()
+        };
+
+        let filtered = post_filter(&PlainProvider, &params, results);
+        assert_eq!(
+            urls(&filtered),
+            ["https://a.example/", "https://c.example/"]
+        );
+        assert_eq!(
+            ignored_filters(&PlainProvider, &params),
+            vec![SearchFilter::TimeRange]
+        );
+    }
+
+    #[test]
+    fn test_within_limits() {
+        let params = BaseSearchParams {
+            limit: Some(500),
+            ..Default::default()
+        };
+        assert_eq!(within_limits(&PlainProvider, params).limit, Some(100));
+    }
+}
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use eyre::{eyre, Result};
+use serde::Serialize;
 use std::{
     collections::HashMap,
     sync::Arc,
@@ -8,9 +9,10 @@ use std::{
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
-use crate::config::CONFIG;
+use crate::config::{CircuitBreakerConfig, CONFIG};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CircuitState {
     Closed,   // Normal operation
     Open,     // Failing, requests rejected
@@ -75,6 +77,14 @@ impl CircuitBreaker {
         F: FnOnce() -> Fut,
         Fut: std::future::Future<Output = Result<T>>,
     {
+        self.admit(provider)?;
+        let result = operation().await;
+        self.record(provider, result.is_ok()).await;
+        result
+    }
+
+    // Decide whether a call may go ahead, moving an expired open breaker to half-open
+    fn admit(&mut self, provider: &str) -> Result<()> {
         match self.state {
             CircuitState::Open => {
                 if self.should_attempt_reset() {
@@ -111,15 +121,14 @@ impl CircuitBreaker {
             }
         }
 
-        match operation().await {
-            Ok(result) => {
-                self.on_success(provider).await;
-                Ok(result)
-            }
-            Err(err) => {
-                self.on_failure(provider).await;
-                Err(err)
-            }
+        Ok(())
+    }
+
+    async fn record(&mut self, provider: &str, success: bool) {
+        if success {
+            self.on_success(provider).await;
+        } else {
+            self.on_failure(provider).await;
         }
     }
 
@@ -223,6 +232,7 @@ impl CircuitBreaker {
     }
 }
 
+#[derive(Clone)]
 pub struct CircuitBreakerManager {
     breakers: Arc<RwLock<HashMap<String, CircuitBreaker>>>,
     enabled: bool,
@@ -239,8 +249,11 @@ impl Default for CircuitBreakerManager {
 
 impl CircuitBreakerManager {
     pub fn new() -> Self {
-        let config = &CONFIG.circuit_breaker;
+        Self::with_config(&CONFIG.circuit_breaker)
+    }
 
+    /// Create a manager from explicit settings rather than the global config
+    pub fn with_config(config: &CircuitBreakerConfig) -> Self {
         Self {
             breakers: Arc::new(RwLock::new(HashMap::new())),
             enabled: config.enabled,
@@ -250,6 +263,19 @@ impl CircuitBreakerManager {
         }
     }
 
+    /// Stats for every provider that has a circuit breaker
+    pub async fn get_all_stats(&self) -> HashMap<String, CircuitBreakerStats> {
+        if !self.enabled {
+            return HashMap::new();
+        }
+
+        let breakers = self.breakers.read().await;
+        breakers
+            .iter()
+            .map(|(provider, breaker)| (provider.clone(), breaker.get_stats(provider)))
+            .collect()
+    }
+
     #[allow(dead_code)]
     async fn get_or_create_breaker(&self, provider: &str) -> Arc<RwLock<CircuitBreaker>> {
         let mut breakers = self.breakers.write().await;
@@ -291,20 +317,27 @@ impl CircuitBreakerProvider for CircuitBreakerManager {
             return operation().await;
         }
 
-        let mut breakers = self.breakers.write().await;
-
-        if !breakers.contains_key(provider) {
-            let breaker = CircuitBreaker::new(
-                self.failure_threshold,
-                self.timeout_duration,
-                self.half_open_max_calls,
-            );
-            breakers.insert(provider.to_string(), breaker);
-            debug!("Created circuit breaker for provider: {}", provider);
+        // The lock is only held to update the breaker, so calls to the same
+        // provider run concurrently rather than queueing behind each other
+        {
+            let mut breakers = self.breakers.write().await;
+            let breaker = breakers.entry(provider.to_string()).or_insert_with(|| {
+                debug!("Created circuit breaker for provider: {}", provider);
+                CircuitBreaker::new(
+                    self.failure_threshold,
+                    self.timeout_duration,
+                    self.half_open_max_calls,
+                )
+            });
+            breaker.admit(provider)?;
         }
 
-        let breaker = breakers.get_mut(provider).unwrap();
-        breaker.call(provider, operation).await
+        let result = operation().await;
+
+        if let Some(breaker) = self.breakers.write().await.get_mut(provider) {
+            breaker.record(provider, result.is_ok()).await;
+        }
+        result
     }
 
     async fn get_stats(&self, provider: &str) -> Option<CircuitBreakerStats> {
@@ -353,6 +386,10 @@ pub async fn get_circuit_breaker_stats(provider: &str) -> Option<CircuitBreakerS
     CIRCUIT_BREAKER_MANAGER.get_stats(provider).await
 }
 
+pub async fn get_all_circuit_breaker_stats() -> HashMap<String, CircuitBreakerStats> {
+    CIRCUIT_BREAKER_MANAGER.get_all_stats().await
+}
+
 pub async fn reset_circuit_breaker(provider: &str) -> Result<()> {
     CIRCUIT_BREAKER_MANAGER.reset(provider).await
 }
@@ -443,6 +480,27 @@ mod tests {
         assert_eq!(counter.load(Ordering::SeqCst), 1);
     }
 
+    #[tokio::test]
+    async fn test_circuit_breaker_manager_all_stats() {
+        let manager = CircuitBreakerManager {
+            breakers: Arc::new(RwLock::new(HashMap::new())),
+            enabled: true,
+            failure_threshold: 1,
+            timeout_duration: Duration::from_secs(60),
+            half_open_max_calls: 2,
+        };
+
+        let _ = manager.call("healthy", || async { Ok(()) }).await;
+        let _ = manager
+            .call("failing", || async { Err::<(), _>(eyre!("boom")) })
+            .await;
+
+        let stats = manager.get_all_stats().await;
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats["healthy"].state, CircuitState::Closed);
+        assert_eq!(stats["failing"].state, CircuitState::Open);
+    }
+
     #[tokio::test]
     async fn test_disabled_circuit_breaker() {
         let manager = CircuitBreakerManager {
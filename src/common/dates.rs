@@ -0,0 +1,138 @@
+//! Publication dates as providers report them, normalised to RFC 3339.
+
+use crate::common::types::TimeRange;
+use time::format_description::well_known::{Rfc2822, Rfc3339};
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+/// A provider's date as an RFC 3339 timestamp.
+///
+/// Accepts RFC 3339, RFC 2822 and bare `YYYY-MM-DD` dates, taken as midnight UTC.
+/// Anything else, such as "3 days ago", gives `None`.
+pub fn normalize_date(date: &str) -> Option<String> {
+    let date = date.trim();
+    let parsed = OffsetDateTime::parse(date, &Rfc3339)
+        .or_else(|_| OffsetDateTime::parse(date, &Rfc2822))
+        .ok()
+        .or_else(|| calendar_date(date))?;
+    parsed.format(&Rfc3339).ok()
+}
+
+/// A Unix timestamp in seconds as RFC 3339
+pub fn from_unix_timestamp(seconds: f64) -> Option<String> {
+    if !seconds.is_finite() {
+        return None;
+    }
+    OffsetDateTime::from_unix_timestamp(seconds as i64)
+        .ok()?
+        .format(&Rfc3339)
+        .ok()
+}
+
+/// The time `days` days ago as RFC 3339
+pub fn days_ago(days: u32) -> Option<String> {
+    let then = OffsetDateTime::now_utc() - time::Duration::days(days.into());
+    then.replace_nanosecond(0).ok()?.format(&Rfc3339).ok()
+}
+
+/// Whether `date` is a real date written `YYYY-MM-DD`
+pub fn is_calendar_date(date: &str) -> bool {
+    date.len() == 10 && calendar_date(date).is_some()
+}
+
+/// Whether an RFC 3339 `published_at` falls within `time_range`, comparing UTC
+/// calendar dates. A date that cannot be read is taken to be within it.
+pub fn published_within(published_at: &str, time_range: &TimeRange) -> bool {
+    let Ok(published) = OffsetDateTime::parse(published_at, &Rfc3339) else {
+        return true;
+    };
+    let published = published.to_offset(UtcOffset::UTC).date();
+    let (start, end) = match time_range {
+        TimeRange::Custom { start, end } => (
+            start.as_deref().and_then(calendar_date),
+            end.as_deref().and_then(calendar_date),
+        ),
+        preset => (
+            preset
+                .days()
+                .map(|days| OffsetDateTime::now_utc() - time::Duration::days(days.into())),
+            None,
+        ),
+    };
+    start.is_none_or(|start| published >= start.date())
+        && end.is_none_or(|end| published <= end.date())
+}
+
+fn calendar_date(date: &str) -> Option<OffsetDateTime> {
+    let mut parts = date.splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month: u8 = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    let date = Date::from_calendar_date(year, Month::try_from(month).ok()?, day).ok()?;
+    Some(PrimitiveDateTime::new(date, Time::MIDNIGHT).assume_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_date_formats() {
+        assert_eq!(
+            normalize_date("2024-03-05T10:30:00.000Z").as_deref(),
+            Some("2024-03-05T10:30:00Z")
+        );
+        assert_eq!(
+            normalize_date("Tue, 05 Mar 2024 10:30:00 +0100").as_deref(),
+            Some("2024-03-05T10:30:00+01:00")
+        );
+        assert_eq!(
+            normalize_date("2024-03-05").as_deref(),
+            Some("2024-03-05T00:00:00Z")
+        );
+        assert_eq!(normalize_date("3 days ago"), None);
+        assert_eq!(normalize_date("2024-13-01"), None);
+    }
+
+    #[test]
+    fn test_is_calendar_date() {
+        assert!(is_calendar_date("2024-02-29"));
+        assert!(!is_calendar_date("2023-02-29"));
+        assert!(!is_calendar_date("2024-3-5"));
+        assert!(!is_calendar_date("yesterday"));
+    }
+
+    #[test]
+    fn test_days_ago() {
+        let week_ago = days_ago(7).unwrap();
+        let parsed = OffsetDateTime::parse(&week_ago, &Rfc3339).unwrap();
+        let elapsed = OffsetDateTime::now_utc() - parsed;
+        assert!((elapsed - time::Duration::days(7)).abs() < time::Duration::minutes(1));
+    }
+
+    #[test]
+    fn test_published_within() {
+        let range = TimeRange::Custom {
+            start: Some("2024-03-01".to_string()),
+            end: Some("2024-03-31".to_string()),
+        };
+        assert!(published_within("2024-03-31T23:30:00Z", &range));
+        // The last evening of March in UTC
+        assert!(published_within("2024-04-01T01:00:00+02:00", &range));
+        assert!(!published_within("2024-04-01T00:00:00Z", &range));
+        assert!(!published_within("2024-02-29T12:00:00Z", &range));
+        assert!(published_within("not a date", &range));
+
+        let yesterday = days_ago(1).unwrap();
+        assert!(published_within(&yesterday, &TimeRange::Week));
+        assert!(!published_within(&days_ago(30).unwrap(), &TimeRange::Week));
+    }
+
+    #[test]
+    fn test_from_unix_timestamp() {
+        assert_eq!(
+            from_unix_timestamp(1709634600.0).as_deref(),
+            Some("2024-03-05T10:30:00Z")
+        );
+        assert_eq!(from_unix_timestamp(f64::NAN), None);
+    }
+}
@@ -521,8 +521,8 @@ mod tests {
     async fn test_readiness_check() {
         let result = check_readiness().await;
         // This should generally pass unless configuration is severely broken
-        if result.is_err() {
-            println!("Readiness check failed: {}", result.unwrap_err());
+        if let Err(e) = result {
+            println!("Readiness check failed: {}", e);
         }
     }
 
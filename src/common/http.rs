@@ -1,7 +1,11 @@
 //! Common HTTP utilities for providers
 
+use crate::common::types::{ErrorType, ProviderError};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
 use reqwest::Client;
 use std::time::Duration;
+use time::format_description::well_known::Rfc2822;
+use time::OffsetDateTime;
 
 /// Create a HTTP client with timeout
 pub fn create_http_client(timeout_ms: u64) -> Client {
@@ -12,6 +16,9 @@ pub fn create_http_client(timeout_ms: u64) -> Client {
 }
 
 /// Handle common HTTP error responses
+///
+/// Quota and plan limits are told apart from short-term rate limits by the
+/// status (402) or by the response body mentioning a quota.
 pub fn handle_http_error(
     status: reqwest::StatusCode,
     error_message: String,
@@ -20,45 +27,79 @@ pub fn handle_http_error(
     auth_error_message: &str,
     forbidden_message: &str,
     internal_error_message: &str,
-) -> crate::common::types::ProviderError {
-    use crate::common::types::{ErrorType, ProviderError};
+) -> ProviderError {
+    let quota_exhausted = error_message.to_lowercase().contains("quota");
 
-    match status.as_u16() {
-        400 => ProviderError::new(
+    let (error_type, message) = match status.as_u16() {
+        400 => (
             ErrorType::InvalidInput,
             "Invalid request parameters".to_string(),
-            provider_name.to_string(),
-            None,
         ),
-        401 => ProviderError::new(
-            ErrorType::ApiError,
-            auth_error_message.to_string(),
-            provider_name.to_string(),
-            None,
+        401 => (ErrorType::Authentication, auth_error_message.to_string()),
+        402 => (
+            ErrorType::QuotaExhausted,
+            format!("Quota exhausted: {}", error_message),
         ),
-        403 => ProviderError::new(
-            ErrorType::ApiError,
-            forbidden_message.to_string(),
-            provider_name.to_string(),
-            None,
+        403 | 429 if quota_exhausted => (
+            ErrorType::QuotaExhausted,
+            format!("Quota exhausted: {}", error_message),
         ),
-        429 => ProviderError::new(
-            ErrorType::RateLimit,
-            rate_limit_message.to_string(),
-            provider_name.to_string(),
-            None,
+        403 => (ErrorType::Authentication, forbidden_message.to_string()),
+        408 | 504 => (
+            ErrorType::Timeout,
+            format!("Request timed out: {}", error_message),
         ),
-        500 => ProviderError::new(
-            ErrorType::ProviderError,
-            internal_error_message.to_string(),
-            provider_name.to_string(),
-            None,
+        429 => (ErrorType::RateLimit, rate_limit_message.to_string()),
+        500 => (ErrorType::ProviderError, internal_error_message.to_string()),
+        502 | 503 => (
+            ErrorType::Unavailable,
+            format!("Service unavailable: {}", error_message),
         ),
-        _ => ProviderError::new(
+        _ => (
             ErrorType::ApiError,
             format!("Unexpected error: {}", error_message),
-            provider_name.to_string(),
-            None,
         ),
+    };
+    ProviderError::new(error_type, message, provider_name.to_string(), None)
+        .with_http_status(status.as_u16())
+}
+
+/// How long a response's `Retry-After` header asks us to wait.
+///
+/// The header is either a number of seconds or an HTTP date; a date in the
+/// past means no wait.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
     }
+    let date = OffsetDateTime::parse(value, &Rfc2822).ok()?;
+    let wait = date - OffsetDateTime::now_utc();
+    Some(wait.try_into().unwrap_or(Duration::ZERO))
+}
+
+/// Classify a failure to send a request or read its response
+pub fn request_error(error: reqwest::Error, provider_name: &str) -> ProviderError {
+    let error_type = if error.is_timeout() {
+        ErrorType::Timeout
+    } else if error.is_decode() {
+        ErrorType::ParseError
+    } else if error.is_connect() || error.is_request() || error.is_body() {
+        ErrorType::Network
+    } else {
+        ErrorType::ApiError
+    };
+    let message = match error_type {
+        ErrorType::ParseError => format!("Failed to parse response: {}", error),
+        _ => format!("Failed to send request: {}", error),
+    };
+    let http_status = error.status().map(|status| status.as_u16());
+    let mut error = ProviderError::new(
+        error_type,
+        message,
+        provider_name.to_string(),
+        Some(error.into()),
+    );
+    error.http_status = http_status;
+    error
 }
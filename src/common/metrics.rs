@@ -1,14 +1,17 @@
 use eyre::Result;
 use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     sync::Arc,
     time::{Duration, Instant},
 };
 use tokio::sync::RwLock;
 use tracing::{debug, info};
 
-use crate::config::CONFIG;
+use crate::config::{MetricsConfig, CONFIG};
+
+// Recent successful request durations kept per provider for latency percentiles
+const LATENCY_SAMPLE_SIZE: usize = 100;
 
 #[derive(Debug, Clone)]
 pub struct RequestMetrics {
@@ -29,11 +32,61 @@ pub struct ProviderStats {
     pub cache_hits: u64,
     pub avg_response_time: Duration,
     pub last_request_time: Option<Instant>,
+    /// Durations of the most recent successful, uncached requests, oldest first
+    pub recent_durations: VecDeque<Duration>,
+    /// Times a request to this provider was hedged by another provider
+    pub hedged_requests: u64,
+    /// Times this provider answered first as the hedge for another provider
+    pub hedge_wins: u64,
+}
+
+impl ProviderStats {
+    fn record(&mut self, success: bool, cache_hit: bool, duration: Duration) {
+        self.total_requests += 1;
+        if success {
+            self.successful_requests += 1;
+        } else {
+            self.failed_requests += 1;
+        }
+
+        if cache_hit {
+            self.cache_hits += 1;
+        }
+
+        self.total_duration += duration;
+        self.avg_response_time = Duration::from_nanos(
+            (self.total_duration.as_nanos() / self.total_requests as u128) as u64,
+        );
+        self.last_request_time = Some(Instant::now());
+
+        if success && !cache_hit {
+            if self.recent_durations.len() == LATENCY_SAMPLE_SIZE {
+                self.recent_durations.pop_front();
+            }
+            self.recent_durations.push_back(duration);
+        }
+    }
+
+    /// The given percentile (0-100) of recent successful request durations,
+    /// using the nearest-rank method. `None` until a request has succeeded.
+    pub fn latency_percentile(&self, percentile: f64) -> Option<Duration> {
+        if self.recent_durations.is_empty() {
+            return None;
+        }
+
+        let mut durations: Vec<Duration> = self.recent_durations.iter().copied().collect();
+        durations.sort();
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * durations.len() as f64).ceil();
+        let index = (rank as usize).clamp(1, durations.len()) - 1;
+        Some(durations[index])
+    }
 }
 
 pub struct MetricsCollector {
     enabled: bool,
     stats: Arc<RwLock<HashMap<String, ProviderStats>>>,
+    // Per authenticated client, keyed by client id
+    client_stats: Arc<RwLock<HashMap<String, ProviderStats>>>,
 }
 
 impl Default for MetricsCollector {
@@ -44,7 +97,12 @@ impl Default for MetricsCollector {
 
 impl MetricsCollector {
     pub fn new() -> Self {
-        let enabled = CONFIG.metrics.enabled;
+        Self::with_config(&CONFIG.metrics)
+    }
+
+    /// Create a collector from explicit settings rather than the global config
+    pub fn with_config(config: &MetricsConfig) -> Self {
+        let enabled = config.enabled;
 
         if enabled {
             Self::register_metrics();
@@ -54,6 +112,7 @@ impl MetricsCollector {
         Self {
             enabled,
             stats: Arc::new(RwLock::new(HashMap::new())),
+            client_stats: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -71,6 +130,18 @@ impl MetricsCollector {
             "omnisearch_requests_failed_total",
             "Total number of failed requests by provider"
         );
+        describe_counter!(
+            "omnisearch_client_requests_total",
+            "Total number of tool calls by authenticated client"
+        );
+        describe_counter!(
+            "omnisearch_hedged_requests_total",
+            "Total number of hedge requests sent because a provider was slow, by slow provider and hedge"
+        );
+        describe_counter!(
+            "omnisearch_hedge_wins_total",
+            "Total number of hedge requests that answered before the provider they hedged"
+        );
         describe_counter!(
             "omnisearch_cache_hits_total",
             "Total number of cache hits by provider"
@@ -135,29 +206,75 @@ impl MetricsCollector {
         // Update internal stats
         let mut stats = self.stats.write().await;
         let provider_stats = stats.entry(provider.clone()).or_default();
+        provider_stats.record(metrics.success, metrics.cache_hit, metrics.duration);
 
-        provider_stats.total_requests += 1;
-        if metrics.success {
-            provider_stats.successful_requests += 1;
-        } else {
-            provider_stats.failed_requests += 1;
+        debug!(
+            "Recorded metrics for provider {}: success={}, duration={:?}",
+            provider, metrics.success, metrics.duration
+        );
+    }
+
+    /// Record a tool call made by an authenticated client
+    pub async fn record_client_request(
+        &self,
+        client_id: &str,
+        tool: &str,
+        success: bool,
+        duration: Duration,
+    ) {
+        if !self.enabled {
+            return;
         }
 
-        if metrics.cache_hit {
-            provider_stats.cache_hits += 1;
+        let status = if success { "success" } else { "failure" };
+        counter!(
+            "omnisearch_client_requests_total",
+            "client" => client_id.to_string(),
+            "tool" => tool.to_string(),
+            "status" => status
+        )
+        .increment(1);
+
+        let mut stats = self.client_stats.write().await;
+        stats
+            .entry(client_id.to_string())
+            .or_default()
+            .record(success, false, duration);
+    }
+
+    /// Record that a slow request to `provider` was hedged with a request to `hedge`
+    pub async fn record_hedge(&self, provider: &str, hedge: &str, hedge_won: bool) {
+        if !self.enabled {
+            return;
         }
 
-        provider_stats.total_duration += metrics.duration;
-        provider_stats.avg_response_time = Duration::from_nanos(
-            (provider_stats.total_duration.as_nanos() / provider_stats.total_requests as u128)
-                as u64,
-        );
-        provider_stats.last_request_time = Some(Instant::now());
+        counter!(
+            "omnisearch_hedged_requests_total",
+            "provider" => provider.to_string(),
+            "hedge" => hedge.to_string()
+        )
+        .increment(1);
+        if hedge_won {
+            counter!("omnisearch_hedge_wins_total", "provider" => hedge.to_string()).increment(1);
+        }
 
-        debug!(
-            "Recorded metrics for provider {}: success={}, duration={:?}",
-            provider, metrics.success, metrics.duration
-        );
+        let mut stats = self.stats.write().await;
+        stats
+            .entry(provider.to_string())
+            .or_default()
+            .hedged_requests += 1;
+        if hedge_won {
+            stats.entry(hedge.to_string()).or_default().hedge_wins += 1;
+        }
+    }
+
+    pub async fn get_client_stats(&self, client_id: &str) -> Option<ProviderStats> {
+        if !self.enabled {
+            return None;
+        }
+
+        let stats = self.client_stats.read().await;
+        stats.get(client_id).cloned()
     }
 
     pub async fn record_cache_size(&self, size: usize) {
@@ -303,6 +420,27 @@ pub async fn get_provider_stats(provider: &str) -> Option<ProviderStats> {
     METRICS_COLLECTOR.get_provider_stats(provider).await
 }
 
+pub async fn record_hedge_metrics(provider: &str, hedge: &str, hedge_won: bool) {
+    METRICS_COLLECTOR
+        .record_hedge(provider, hedge, hedge_won)
+        .await;
+}
+
+pub async fn record_client_request_metrics(
+    client_id: &str,
+    tool: &str,
+    success: bool,
+    duration: Duration,
+) {
+    METRICS_COLLECTOR
+        .record_client_request(client_id, tool, success, duration)
+        .await;
+}
+
+pub async fn get_client_stats(client_id: &str) -> Option<ProviderStats> {
+    METRICS_COLLECTOR.get_client_stats(client_id).await
+}
+
 pub async fn get_all_provider_stats() -> HashMap<String, ProviderStats> {
     METRICS_COLLECTOR.get_all_stats().await
 }
@@ -374,6 +512,80 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_client_metrics_collection() {
+        let collector = MetricsCollector::new();
+
+        collector
+            .record_client_request("team-a", "search_tavily", true, Duration::from_millis(50))
+            .await;
+        collector
+            .record_client_request("team-a", "search_exa", false, Duration::from_millis(150))
+            .await;
+
+        if collector.is_enabled() {
+            let stats = collector.get_client_stats("team-a").await.unwrap();
+            assert_eq!(stats.total_requests, 2);
+            assert_eq!(stats.successful_requests, 1);
+            assert_eq!(stats.failed_requests, 1);
+            assert_eq!(stats.avg_response_time, Duration::from_millis(100));
+            assert!(collector.get_client_stats("team-b").await.is_none());
+        }
+    }
+
+    #[test]
+    fn test_latency_percentile() {
+        let mut stats = ProviderStats::default();
+        assert!(stats.latency_percentile(95.0).is_none());
+
+        for millis in 1..=20 {
+            stats.record(true, false, Duration::from_millis(millis));
+        }
+        stats.record(false, false, Duration::from_secs(30));
+        stats.record(true, true, Duration::from_secs(30));
+
+        assert_eq!(
+            stats.latency_percentile(50.0),
+            Some(Duration::from_millis(10))
+        );
+        assert_eq!(
+            stats.latency_percentile(95.0),
+            Some(Duration::from_millis(19))
+        );
+        assert_eq!(
+            stats.latency_percentile(100.0),
+            Some(Duration::from_millis(20))
+        );
+
+        for _ in 0..LATENCY_SAMPLE_SIZE {
+            stats.record(true, false, Duration::from_millis(5));
+        }
+        assert_eq!(stats.recent_durations.len(), LATENCY_SAMPLE_SIZE);
+        assert_eq!(
+            stats.latency_percentile(100.0),
+            Some(Duration::from_millis(5))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hedge_metrics() {
+        let collector = MetricsCollector::new();
+
+        collector
+            .record_hedge("slow_provider", "fast_provider", true)
+            .await;
+        collector
+            .record_hedge("slow_provider", "fast_provider", false)
+            .await;
+
+        if collector.is_enabled() {
+            let slow = collector.get_provider_stats("slow_provider").await.unwrap();
+            assert_eq!(slow.hedged_requests, 2);
+            let fast = collector.get_provider_stats("fast_provider").await.unwrap();
+            assert_eq!(fast.hedge_wins, 1);
+        }
+    }
+
     #[tokio::test]
     async fn test_metrics_middleware() {
         let collector = Arc::new(MetricsCollector::new());
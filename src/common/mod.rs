@@ -1,5 +1,8 @@
 pub mod cache;
+pub mod canonical_url;
+pub mod capabilities;
 pub mod circuit_breaker;
+pub mod dates;
 pub mod health;
 pub mod http;
 pub mod macros;
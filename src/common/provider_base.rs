@@ -11,7 +11,7 @@ pub trait ApiKeyProvider {
     ) -> Result<(), ProviderError> {
         if api_key.is_none() {
             Err(ProviderError::new(
-                ErrorType::ApiError,
+                ErrorType::Authentication,
                 format!("Missing API key for {}", provider_name),
                 provider_name.to_string(),
                 None,
@@ -33,7 +33,7 @@ pub trait MultiCredentialProvider {
         for (i, credential) in credentials.iter().enumerate() {
             if credential.is_none() {
                 return Err(ProviderError::new(
-                    ErrorType::ApiError,
+                    ErrorType::Authentication,
                     error_messages[i].to_string(),
                     provider_name.to_string(),
                     None,
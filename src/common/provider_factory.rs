@@ -1,7 +1,8 @@
 //! Provider factory for creating and managing providers
 
 use crate::common::types::SearchProvider;
-use crate::config::CONFIG;
+use crate::config::{Config, CONFIG};
+use reqwest::Client;
 
 /// Provider factory for creating and managing providers
 pub struct ProviderFactory;
@@ -9,56 +10,97 @@ pub struct ProviderFactory;
 impl ProviderFactory {
     /// Create all available search providers based on configuration
     pub fn create_search_providers() -> Vec<Box<dyn SearchProvider>> {
+        Self::create_search_providers_from(&CONFIG, None)
+    }
+
+    /// Create the search providers that `config` has credentials for.
+    ///
+    /// Each provider gets its own HTTP client unless `client` is given, in which
+    /// case they all share it.
+    pub fn create_search_providers_from(
+        config: &Config,
+        client: Option<&Client>,
+    ) -> Vec<Box<dyn SearchProvider>> {
+        let providers_config = &config.providers;
         let mut providers: Vec<Box<dyn SearchProvider>> = Vec::new();
 
+        macro_rules! provider {
+            ($provider:ty, $config:expr) => {
+                match client {
+                    Some(client) => <$provider>::with_client($config.clone(), client.clone()),
+                    None => <$provider>::with_config($config.clone()),
+                }
+            };
+        }
+
         // Tavily provider
-        if CONFIG.providers.tavily.api_key.is_some() {
-            providers.push(Box::new(
-                crate::providers::search::TavilySearchProvider::new(),
-            ));
+        if providers_config.tavily.api_key.is_some() {
+            providers.push(Box::new(provider!(
+                crate::providers::search::TavilySearchProvider,
+                providers_config.tavily
+            )));
         }
 
         // Google Custom Search provider
-        if CONFIG.providers.google.api_key.is_some()
-            && CONFIG.providers.google.search_engine_id.is_some()
+        if providers_config.google.api_key.is_some()
+            && providers_config.google.search_engine_id.is_some()
         {
-            providers.push(Box::new(
-                crate::providers::google::GoogleCustomSearchProvider::new(),
-            ));
+            providers.push(Box::new(provider!(
+                crate::providers::google::GoogleCustomSearchProvider,
+                providers_config.google
+            )));
         }
 
         // Reddit provider
-        if CONFIG.providers.reddit.client_id.is_some()
-            && CONFIG.providers.reddit.client_secret.is_some()
-            && CONFIG.providers.reddit.user_agent.is_some()
+        if providers_config.reddit.client_id.is_some()
+            && providers_config.reddit.client_secret.is_some()
+            && providers_config.reddit.user_agent.is_some()
         {
-            providers.push(Box::new(
-                crate::providers::reddit::RedditSearchProvider::new(),
-            ));
+            providers.push(Box::new(provider!(
+                crate::providers::reddit::RedditSearchProvider,
+                providers_config.reddit
+            )));
         }
 
         // DuckDuckGo provider (no API key required)
-        providers.push(Box::new(
-            crate::providers::duckduckgo::DuckDuckGoSearchProvider::new(),
-        ));
+        providers.push(Box::new(provider!(
+            crate::providers::duckduckgo::DuckDuckGoSearchProvider,
+            providers_config.duckduckgo
+        )));
 
         // Baidu provider
-        if CONFIG.providers.baidu.api_key.is_some() {
-            providers.push(Box::new(crate::providers::baidu::BaiduSearchProvider::new()));
+        if providers_config.baidu.api_key.is_some() {
+            providers.push(Box::new(provider!(
+                crate::providers::baidu::BaiduSearchProvider,
+                providers_config.baidu
+            )));
         }
 
         // Bright Data provider
-        if CONFIG.providers.brightdata.username.is_some()
-            && CONFIG.providers.brightdata.password.is_some()
+        if providers_config.brightdata.username.is_some()
+            && providers_config.brightdata.password.is_some()
         {
-            providers.push(Box::new(
-                crate::providers::brightdata::BrightDataSearchProvider::new(),
-            ));
+            providers.push(Box::new(provider!(
+                crate::providers::brightdata::BrightDataSearchProvider,
+                providers_config.brightdata
+            )));
         }
 
         // Exa provider
-        if CONFIG.providers.exa.api_key.is_some() {
-            providers.push(Box::new(crate::providers::exa::ExaSearchProvider::new()));
+        if providers_config.exa.api_key.is_some() {
+            providers.push(Box::new(provider!(
+                crate::providers::exa::ExaSearchProvider,
+                providers_config.exa
+            )));
+        }
+
+        // Brave provider
+        #[cfg(feature = "brave")]
+        if providers_config.brave.api_key.is_some() {
+            providers.push(Box::new(provider!(
+                crate::providers::brave::BraveSearchProvider,
+                providers_config.brave
+            )));
         }
 
         providers
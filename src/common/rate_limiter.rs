@@ -7,7 +7,10 @@ use std::{collections::HashMap, num::NonZeroU32, sync::Arc, time::Duration};
 use tokio::sync::RwLock;
 use tracing::{debug, warn};
 
-use crate::config::CONFIG;
+use crate::config::{Config, CONFIG};
+
+// Limiters for authenticated clients share the map with providers under this prefix
+const CLIENT_LIMITER_PREFIX: &str = "client:";
 
 pub type ProviderRateLimiter =
     GovernorLimiter<NotKeyed, InMemoryState, governor::clock::DefaultClock>;
@@ -16,6 +19,7 @@ pub type ProviderRateLimiter =
 pub struct RateLimiterManager {
     limiters: Arc<RwLock<HashMap<String, Arc<ProviderRateLimiter>>>>,
     enabled: bool,
+    config: Arc<Config>,
 }
 
 impl Default for RateLimiterManager {
@@ -26,11 +30,15 @@ impl Default for RateLimiterManager {
 
 impl RateLimiterManager {
     pub fn new() -> Self {
-        let enabled = CONFIG.rate_limiting.enabled;
+        Self::with_config(&CONFIG)
+    }
 
+    /// Create a manager whose limits come from `config` rather than the global config
+    pub fn with_config(config: &Config) -> Self {
         Self {
             limiters: Arc::new(RwLock::new(HashMap::new())),
-            enabled,
+            enabled: config.rate_limiting.enabled,
+            config: Arc::new(config.clone()),
         }
     }
 
@@ -66,19 +74,26 @@ impl RateLimiterManager {
     }
 
     fn get_provider_rate_limit(&self, provider: &str) -> u32 {
+        let providers = &self.config.providers;
         match provider {
-            "tavily" => CONFIG.providers.tavily.rate_limit,
-            "google" => CONFIG.providers.google.rate_limit,
-            "reddit" => CONFIG.providers.reddit.rate_limit,
-            "duckduckgo" => CONFIG.providers.duckduckgo.rate_limit,
-            "baidu" => CONFIG.providers.baidu.rate_limit,
-            "exa" => CONFIG.providers.exa.rate_limit,
-            "brave" => CONFIG.providers.brave.rate_limit,
-            "kagi" => CONFIG.providers.kagi.rate_limit,
-            "perplexity" => CONFIG.providers.perplexity.rate_limit,
-            "jina" => CONFIG.providers.jina.rate_limit,
-            "firecrawl" => CONFIG.providers.firecrawl.rate_limit,
-            "brightdata" => CONFIG.providers.brightdata.rate_limit,
+            "tavily" => providers.tavily.rate_limit,
+            "google" => providers.google.rate_limit,
+            "reddit" => providers.reddit.rate_limit,
+            "duckduckgo" => providers.duckduckgo.rate_limit,
+            "baidu" => providers.baidu.rate_limit,
+            "exa" => providers.exa.rate_limit,
+            "brave" => providers.brave.rate_limit,
+            "kagi" => providers.kagi.rate_limit,
+            "perplexity" => providers.perplexity.rate_limit,
+            "jina" => providers.jina.rate_limit,
+            "firecrawl" => providers.firecrawl.rate_limit,
+            "brightdata" => providers.brightdata.rate_limit,
+            key if key.starts_with(CLIENT_LIMITER_PREFIX) => {
+                self.config
+                    .rate_limiting
+                    .requests_per_minute
+                    .min(u32::MAX as u64) as u32
+            }
             _ => {
                 warn!("Unknown provider '{}', using default rate limit", provider);
                 60 // Default rate limit
@@ -105,6 +120,13 @@ impl RateLimiterManager {
         }
     }
 
+    /// Check the per-client limit (`rate_limiting.requests_per_minute`) for an authenticated client
+    pub async fn check_client_rate_limit(&self, client_id: &str) -> Result<()> {
+        self.check_rate_limit(&format!("{}{}", CLIENT_LIMITER_PREFIX, client_id))
+            .await
+            .map_err(|_| eyre!("Rate limit exceeded for client: {}", client_id))
+    }
+
     pub async fn wait_for_rate_limit(&self, provider: &str) -> Result<()> {
         if !self.enabled {
             return Ok(());
@@ -161,6 +183,12 @@ pub async fn check_rate_limit(provider: &str) -> Result<()> {
     RATE_LIMITER_MANAGER.check_rate_limit(provider).await
 }
 
+pub async fn check_client_rate_limit(client_id: &str) -> Result<()> {
+    RATE_LIMITER_MANAGER
+        .check_client_rate_limit(client_id)
+        .await
+}
+
 pub async fn wait_for_rate_limit(provider: &str) -> Result<()> {
     RATE_LIMITER_MANAGER.wait_for_rate_limit(provider).await
 }
@@ -226,6 +254,7 @@ mod tests {
         let manager = RateLimiterManager {
             limiters: Arc::new(RwLock::new(HashMap::new())),
             enabled: false,
+            config: Arc::new(Config::default()),
         };
 
         // When disabled, all operations should pass
@@ -235,4 +264,28 @@ mod tests {
         let stats = manager.get_limiter_stats("any_provider").await.unwrap();
         assert!(stats.is_none());
     }
+
+    #[tokio::test]
+    async fn test_client_rate_limit_is_per_client() {
+        let manager = RateLimiterManager::new();
+        let per_minute = CONFIG.rate_limiting.requests_per_minute;
+
+        for _ in 0..per_minute {
+            assert!(manager.check_client_rate_limit("busy-client").await.is_ok());
+        }
+
+        if manager.enabled {
+            let err = manager
+                .check_client_rate_limit("busy-client")
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("busy-client"));
+        }
+
+        // Other clients have their own budget
+        assert!(manager
+            .check_client_rate_limit("quiet-client")
+            .await
+            .is_ok());
+    }
 }
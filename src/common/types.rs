@@ -1,44 +1,492 @@
 use eyre;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use thiserror::Error;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct SearchResult {
+    /// Title of the page
     pub title: String,
+    /// URL of the page
     pub url: String,
+    /// Short excerpt of the page relevant to the query
     pub snippet: String,
+    /// Relevance score reported by the provider, if any
     #[serde(skip_serializing_if = "Option::is_none")]
     pub score: Option<f64>,
+    /// Name of the provider that returned the result
     pub source_provider: String,
+    /// Other providers that returned the same page, when results were merged
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub also_found_by: Vec<String>,
+    /// When the page was published, as an RFC 3339 timestamp
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<String>,
+    /// Author of the page or post
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Language of the page, such as `en` or `zh-CN`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// URL of an image representing the page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail_url: Option<String>,
+    /// Name of the site or community the page belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site_name: Option<String>,
+    /// Further details only one provider reports, such as a Reddit post's comment count
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+    /// Details only news, image and video results have
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<ResultDetails>,
+}
+
+impl SearchResult {
+    /// Every provider that returned this page, `source_provider` first
+    pub fn providers(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.source_provider.as_str())
+            .chain(self.also_found_by.iter().map(String::as_str))
+    }
+
+    /// Record the providers of a duplicate of this result, taking any details
+    /// this result is missing from the duplicate
+    pub fn merge_providers_from(&mut self, duplicate: &SearchResult) {
+        for provider in duplicate.providers() {
+            if self.providers().all(|known| known != provider) {
+                self.also_found_by.push(provider.to_string());
+            }
+        }
+        for (field, value) in [
+            (&mut self.published_at, &duplicate.published_at),
+            (&mut self.author, &duplicate.author),
+            (&mut self.language, &duplicate.language),
+            (&mut self.thumbnail_url, &duplicate.thumbnail_url),
+            (&mut self.site_name, &duplicate.site_name),
+        ] {
+            if field.is_none() {
+                field.clone_from(value);
+            }
+        }
+        for (key, value) in &duplicate.extra {
+            self.extra
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+        if self.details.is_none() {
+            self.details.clone_from(&duplicate.details);
+        }
+    }
+}
+
+/// What kind of result a search result is, with the details only that kind has
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResultDetails {
+    News(NewsResult),
+    Image(ImageResult),
+    Video(VideoResult),
+}
+
+/// A news article; its date is the result's `published_at`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct NewsResult {
+    /// The outlet that published the article
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// An image; the result's `url` is the image itself and its `thumbnail_url` a
+/// smaller copy
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ImageResult {
+    /// Width in pixels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    /// Height in pixels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// The page the image appears on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_url: Option<String>,
+}
+
+/// A video
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct VideoResult {
+    /// Length in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<u64>,
+    /// The channel or account that published the video
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct BaseSearchParams {
+    /// The search query
     pub query: String,
+    /// Maximum number of results to return (1-100)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u32>,
+    /// Only return results from these domains
     #[serde(skip_serializing_if = "Option::is_none")]
     pub include_domains: Option<Vec<String>>,
+    /// Never return results from these domains
     #[serde(skip_serializing_if = "Option::is_none")]
     pub exclude_domains: Option<Vec<String>>,
+    /// Number of results to skip, to fetch a later page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
+    /// The `next_page_token` of a previous search, to fetch the page after it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_token: Option<String>,
+    /// Only return pages published within this time range
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_range: Option<TimeRange>,
+    /// Favour results from this country, as an ISO 3166-1 code such as `us`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    /// Favour results in this language, as an ISO 639-1 code such as `en`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// How strictly to filter adult content
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safe_search: Option<SafeSearch>,
+    /// The kind of results to return, web pages unless set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vertical: Option<SearchVertical>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl BaseSearchParams {
+    /// The filters these parameters set
+    pub fn filters(&self) -> Vec<SearchFilter> {
+        [
+            (self.time_range.is_some(), SearchFilter::TimeRange),
+            (self.country.is_some(), SearchFilter::Country),
+            (self.language.is_some(), SearchFilter::Language),
+            (self.safe_search.is_some(), SearchFilter::SafeSearch),
+            (
+                self.vertical.is_some_and(|v| v != SearchVertical::Web),
+                SearchFilter::Vertical,
+            ),
+            (
+                self.include_domains.as_ref().is_some_and(|d| !d.is_empty()),
+                SearchFilter::IncludeDomains,
+            ),
+            (
+                self.exclude_domains.as_ref().is_some_and(|d| !d.is_empty()),
+                SearchFilter::ExcludeDomains,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(set, filter)| set.then_some(filter))
+        .collect()
+    }
+
+    /// Results to skip for providers that page by offset: the offset a
+    /// `page_token` holds, otherwise `offset`
+    pub fn start_offset(&self) -> u32 {
+        self.page_token
+            .as_deref()
+            .and_then(|token| token.parse().ok())
+            .or(self.offset)
+            .unwrap_or(0)
+    }
+
+    /// The offset of the page after `results`, or `None` if they were the last.
+    ///
+    /// A page shorter than the limit, capped at `max_limit`, is taken to be the last.
+    pub fn next_offset(&self, results: &[SearchResult], max_limit: u32) -> Option<u32> {
+        let limit = self.limit.unwrap_or(1).min(max_limit) as usize;
+        if results.is_empty() || results.len() < limit {
+            return None;
+        }
+        Some(self.start_offset() + results.len() as u32)
+    }
+}
+
+/// How recently a page must have been published
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeRange {
+    Day,
+    Week,
+    Month,
+    Year,
+    /// Between two dates, each written `YYYY-MM-DD`; either may be left open
+    Custom {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        start: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        end: Option<String>,
+    },
+}
+
+impl TimeRange {
+    /// Length of a preset range in days, or `None` for a custom one
+    pub fn days(&self) -> Option<u32> {
+        match self {
+            TimeRange::Day => Some(1),
+            TimeRange::Week => Some(7),
+            TimeRange::Month => Some(30),
+            TimeRange::Year => Some(365),
+            TimeRange::Custom { .. } => None,
+        }
+    }
+
+    /// The name of a preset range, as most providers spell it
+    pub fn preset_name(&self) -> Option<&'static str> {
+        match self {
+            TimeRange::Day => Some("day"),
+            TimeRange::Week => Some("week"),
+            TimeRange::Month => Some("month"),
+            TimeRange::Year => Some("year"),
+            TimeRange::Custom { .. } => None,
+        }
+    }
+}
+
+/// The built-in merging strategies, selectable per request or as a default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// Score each result by the sum of `1 / (k + rank)` over the providers that returned it
+    #[default]
+    ReciprocalRankFusion,
+    /// Take each provider's next result in turn
+    RoundRobin,
+    /// Reciprocal rank fusion scaled by a per-provider trust weight
+    Weighted,
+    /// Rescale each provider's scores to 0-1 and sum them
+    ScoreNormalization,
+}
+
+/// The kind of results a search returns
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchVertical {
+    /// Web pages
+    #[default]
+    Web,
+    /// News articles
+    News,
+    /// Images
+    Images,
+    /// Videos
+    Videos,
+    /// Papers and other academic research
+    Academic,
+}
+
+impl SearchVertical {
+    /// The kind of result a provider needs to return for this vertical
+    pub fn result_type(&self) -> ResultType {
+        match self {
+            SearchVertical::Web => ResultType::Web,
+            SearchVertical::News => ResultType::News,
+            SearchVertical::Images => ResultType::Image,
+            SearchVertical::Videos => ResultType::Video,
+            SearchVertical::Academic => ResultType::Academic,
+        }
+    }
+}
+
+/// How strictly to filter adult content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SafeSearch {
+    Off,
+    Moderate,
+    Strict,
+}
+
+/// A search filter that a provider may not be able to apply
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchFilter {
+    TimeRange,
+    Country,
+    Language,
+    SafeSearch,
+    Vertical,
+    IncludeDomains,
+    ExcludeDomains,
+}
+
+impl SearchFilter {
+    /// The filter's parameter name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchFilter::TimeRange => "time_range",
+            SearchFilter::Country => "country",
+            SearchFilter::Language => "language",
+            SearchFilter::SafeSearch => "safe_search",
+            SearchFilter::Vertical => "vertical",
+            SearchFilter::IncludeDomains => "include_domains",
+            SearchFilter::ExcludeDomains => "exclude_domains",
+        }
+    }
+}
+
+/// What a search provider can do, for routing, tool schemas and filtering
+/// results the provider could not filter itself
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProviderCapabilities {
+    /// Filters the provider applies itself, apart from `time_range`, which
+    /// `freshness` describes, and `vertical`, which `result_types` does
+    pub filters: Vec<SearchFilter>,
+    /// Most results one search can return
+    pub max_limit: u32,
+    /// How later pages of results are fetched
+    pub pagination: Pagination,
+    /// How finely results can be limited to a publication date
+    pub freshness: Freshness,
+    /// ISO 639-1 codes of the languages results come in, empty for any language
+    pub languages: Vec<String>,
+    /// The kinds of result the provider can return
+    pub result_types: Vec<ResultType>,
+    /// What searches cost
+    pub cost: CostClass,
+    /// Whether searches need an API key or other credentials
+    pub requires_api_key: bool,
+}
+
+impl Default for ProviderCapabilities {
+    /// A provider that applies no filters and only returns the first page
+    fn default() -> Self {
+        Self {
+            filters: Vec::new(),
+            max_limit: 100,
+            pagination: Pagination::None,
+            freshness: Freshness::None,
+            languages: Vec::new(),
+            result_types: vec![ResultType::Web],
+            cost: CostClass::Paid,
+            requires_api_key: true,
+        }
+    }
+}
+
+impl ProviderCapabilities {
+    /// Whether the provider applies `filter` itself, for some value of it
+    pub fn supports(&self, filter: SearchFilter) -> bool {
+        match filter {
+            SearchFilter::TimeRange => self.freshness != Freshness::None,
+            SearchFilter::Vertical => self.result_types.iter().any(|result_type| {
+                !matches!(result_type, ResultType::Web | ResultType::Discussion)
+            }),
+            filter => self.filters.contains(&filter),
+        }
+    }
+
+    /// Whether the provider can return results of this vertical
+    pub fn serves(&self, vertical: SearchVertical) -> bool {
+        vertical == SearchVertical::Web || self.result_types.contains(&vertical.result_type())
+    }
+
+    /// Filters set in `params` that the provider cannot apply
+    pub fn unsupported_filters(&self, params: &BaseSearchParams) -> Vec<SearchFilter> {
+        let custom_range = matches!(params.time_range, Some(TimeRange::Custom { .. }));
+        params
+            .filters()
+            .into_iter()
+            .filter(|filter| match filter {
+                SearchFilter::TimeRange if custom_range => self.freshness != Freshness::DateRange,
+                SearchFilter::Vertical => !self.serves(params.vertical.unwrap_or_default()),
+                filter => !self.supports(*filter),
+            })
+            .collect()
+    }
+}
+
+/// How a provider fetches later pages of results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Pagination {
+    /// Only the first page is available
+    None,
+    /// By the number of results to skip
+    Offset,
+    /// By a cursor the provider returns with each page
+    Cursor,
+}
+
+/// How finely a provider can limit results to a publication date
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Freshness {
+    /// Not at all
+    None,
+    /// To the last day, week, month or year
+    Presets,
+    /// To the presets or any range of dates
+    DateRange,
+}
+
+/// A kind of search result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultType {
+    Web,
+    News,
+    Image,
+    Video,
+    Academic,
+    Discussion,
+}
+
+/// What a provider charges for searches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CostClass {
+    /// No charge
+    Free,
+    /// A free allowance, then paid
+    FreeTier,
+    /// Every search is paid for
+    Paid,
+}
+
+/// Arguments accepted by content processing tools
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProcessingParams {
+    /// URLs to fetch and process
+    pub urls: Vec<String>,
+    /// Extraction depth, e.g. "basic" or "advanced"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extract_depth: Option<String>,
+}
+
+/// Arguments accepted by content enhancement tools
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EnhancementParams {
+    /// The content to enhance
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProcessingResult {
+    /// The processed content, e.g. extracted text or a summary
     pub content: String,
+    /// Content extracted from each URL, when the provider reports it separately
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raw_contents: Option<Vec<RawContent>>,
     pub metadata: ProcessingMetadata,
+    /// Name of the provider that processed the content
     pub source_provider: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RawContent {
     pub url: String,
     pub content: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProcessingMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
@@ -58,23 +506,28 @@ pub struct ProcessingMetadata {
     pub extract_depth: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EnhancementResult {
+    /// The content as submitted
     pub original_content: String,
+    /// The content after enhancement
     pub enhanced_content: String,
+    /// What the provider changed or added
     pub enhancements: Vec<Enhancement>,
+    /// Sources the enhancement relied on
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sources: Option<Vec<EnhancementSource>>,
+    /// Name of the provider that enhanced the content
     pub source_provider: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Enhancement {
     pub r#type: String,
     pub description: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EnhancementSource {
     pub title: String,
     pub url: String,
@@ -86,6 +539,42 @@ pub trait SearchProvider: Send + Sync {
     async fn search(&self, params: BaseSearchParams) -> Result<Vec<SearchResult>, ProviderError>;
     fn name(&self) -> &'static str;
     fn description(&self) -> &'static str;
+
+    /// A `page_token` for the results after `results`, or `None` on the last page.
+    ///
+    /// The default is the offset of the next page, as read back by
+    /// [`BaseSearchParams::start_offset`], unless the provider has no pagination.
+    /// A page shorter than the limit, or than the provider's `max_limit`, is
+    /// taken to be the last.
+    fn next_page_token(
+        &self,
+        params: &BaseSearchParams,
+        results: &[SearchResult],
+    ) -> Option<String> {
+        let capabilities = self.capabilities();
+        if capabilities.pagination == Pagination::None {
+            return None;
+        }
+        params
+            .next_offset(results, capabilities.max_limit)
+            .map(|offset| offset.to_string())
+    }
+
+    /// What this provider can do.
+    ///
+    /// The default describes a provider that applies no filters and only returns
+    /// the first page.
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities::default()
+    }
+
+    /// Filters set in `params` that this provider cannot apply, and so leaves
+    /// out of its search.
+    ///
+    /// The default is the filters its [`capabilities`](Self::capabilities) do not cover.
+    fn unsupported_filters(&self, params: &BaseSearchParams) -> Vec<SearchFilter> {
+        self.capabilities().unsupported_filters(params)
+    }
 }
 
 #[async_trait::async_trait]
@@ -107,7 +596,7 @@ pub trait EnhancementProvider: Send + Sync {
 }
 
 // Error types
-#[derive(Error, Debug, PartialEq)]
+#[derive(Error, Debug, Clone, PartialEq)]
 pub enum ErrorType {
     #[error("API Error")]
     ApiError,
@@ -117,6 +606,37 @@ pub enum ErrorType {
     InvalidInput,
     #[error("Provider Error")]
     ProviderError,
+    #[error("Timeout")]
+    Timeout,
+    #[error("Network Error")]
+    Network,
+    #[error("Authentication Failed")]
+    Authentication,
+    #[error("Quota Exhausted")]
+    QuotaExhausted,
+    #[error("Parse Error")]
+    ParseError,
+    #[error("Circuit Open")]
+    CircuitOpen,
+    #[error("Unavailable")]
+    Unavailable,
+}
+
+impl ErrorType {
+    /// Whether the same request may succeed if tried again later.
+    ///
+    /// Bad keys, spent quotas and malformed requests fail the same way every
+    /// time; timeouts, dropped connections, rate limits and server errors may not.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ErrorType::RateLimit
+                | ErrorType::ProviderError
+                | ErrorType::Timeout
+                | ErrorType::Network
+                | ErrorType::Unavailable
+        )
+    }
 }
 
 #[derive(Error, Debug)]
@@ -125,6 +645,10 @@ pub struct ProviderError {
     pub error_type: ErrorType,
     pub message: String,
     pub provider: String,
+    /// Status of the HTTP response the error came from
+    pub http_status: Option<u16>,
+    /// How long the provider asked us to wait before trying again
+    pub retry_after: Option<Duration>,
     #[source]
     pub source: Option<eyre::Error>,
 }
@@ -140,7 +664,26 @@ impl ProviderError {
             error_type,
             message,
             provider,
+            http_status: None,
+            retry_after: None,
             source,
         }
     }
+
+    /// Record the HTTP status of the response the error came from
+    pub fn with_http_status(mut self, http_status: u16) -> Self {
+        self.http_status = Some(http_status);
+        self
+    }
+
+    /// Record how long the provider asked us to wait before trying again
+    pub fn with_retry_after(mut self, retry_after: Option<Duration>) -> Self {
+        self.retry_after = retry_after;
+        self
+    }
+
+    /// Whether the same request may succeed if tried again later
+    pub fn is_retryable(&self) -> bool {
+        self.error_type.is_retryable()
+    }
 }
@@ -5,7 +5,10 @@ use std::collections::HashSet;
 use tracing::{debug, warn};
 use validator::{Validate, ValidationError};
 
-use crate::common::types::BaseSearchParams;
+use crate::common::dates::is_calendar_date;
+use crate::common::types::{
+    BaseSearchParams, Pagination, SafeSearch, SearchProvider, SearchVertical, TimeRange,
+};
 
 // Validation constants
 const MAX_QUERY_LENGTH: usize = 1000;
@@ -13,6 +16,8 @@ const MAX_QUERY_LENGTH: usize = 1000;
 const MAX_RESULTS_LIMIT: usize = 100;
 #[allow(dead_code)]
 const MIN_RESULTS_LIMIT: usize = 1;
+// The furthest `offset`, or offset held in a `page_token`, a search may start at
+const MAX_OFFSET: u32 = 1000;
 const MAX_DOMAIN_COUNT: usize = 50;
 const MAX_DOMAIN_LENGTH: usize = 253; // DNS limit
 
@@ -25,6 +30,13 @@ lazy_static::lazy_static! {
         r"^(?:[a-zA-Z0-9](?:[a-zA-Z0-9-]{0,61}[a-zA-Z0-9])?\.)*[a-zA-Z0-9](?:[a-zA-Z0-9-]{0,61}[a-zA-Z0-9])?$"
     ).unwrap();
 
+    static ref COUNTRY_REGEX: Regex = Regex::new(r"^[A-Za-z]{2}$").unwrap();
+
+    // A language subtag, optionally with a script or region, such as `zh-Hant` or `pt-BR`
+    static ref LANGUAGE_REGEX: Regex = Regex::new(
+        r"^[A-Za-z]{2,3}(?:-[A-Za-z]{4})?(?:-(?:[A-Za-z]{2}|[0-9]{3}))?$"
+    ).unwrap();
+
     static ref MALICIOUS_PATTERNS: Vec<Regex> = vec![
         Regex::new(r"(?i)<script\b[^>]*>.*?</script>").unwrap(), // XSS
         Regex::new(r"(?i)javascript:").unwrap(), // JavaScript URLs
@@ -65,6 +77,29 @@ pub struct ValidatedSearchParams {
 
     #[validate(custom(function = "validate_urls"))]
     pub urls: Option<Vec<String>>,
+
+    #[validate(range(max = MAX_OFFSET, message = "Offset must be at most 1000"))]
+    pub offset: Option<u32>,
+
+    #[validate(length(
+        min = 1,
+        max = 1024,
+        message = "Page token must be between 1 and 1024 characters"
+    ))]
+    pub page_token: Option<String>,
+
+    #[validate(custom(function = "validate_time_range"))]
+    pub time_range: Option<TimeRange>,
+
+    #[validate(custom(function = "validate_country"))]
+    pub country: Option<String>,
+
+    #[validate(custom(function = "validate_language"))]
+    pub language: Option<String>,
+
+    pub safe_search: Option<SafeSearch>,
+
+    pub vertical: Option<SearchVertical>,
 }
 
 impl ValidatedSearchParams {
@@ -75,6 +110,13 @@ impl ValidatedSearchParams {
             include_domains: params.include_domains,
             exclude_domains: params.exclude_domains,
             urls: None, // BaseSearchParams doesn't have URLs
+            offset: params.offset,
+            page_token: params.page_token,
+            time_range: params.time_range,
+            country: params.country,
+            language: params.language,
+            safe_search: params.safe_search,
+            vertical: params.vertical,
         };
 
         validated
@@ -90,6 +132,13 @@ impl ValidatedSearchParams {
             limit: self.limit,
             include_domains: self.include_domains.clone(),
             exclude_domains: self.exclude_domains.clone(),
+            offset: self.offset,
+            page_token: self.page_token.clone(),
+            time_range: self.time_range.clone(),
+            country: self.country.clone(),
+            language: self.language.clone(),
+            safe_search: self.safe_search,
+            vertical: self.vertical,
         }
     }
 }
@@ -196,6 +245,41 @@ fn validate_urls(urls: &[String]) -> std::result::Result<(), ValidationError> {
     Ok(())
 }
 
+fn validate_time_range(time_range: &TimeRange) -> std::result::Result<(), ValidationError> {
+    let TimeRange::Custom { start, end } = time_range else {
+        return Ok(());
+    };
+    if start.is_none() && end.is_none() {
+        return Err(ValidationError::new("empty_time_range"));
+    }
+    for date in [start, end].into_iter().flatten() {
+        if !is_calendar_date(date) {
+            return Err(ValidationError::new("invalid_date"));
+        }
+    }
+    // Dates written `YYYY-MM-DD` sort in date order
+    if let (Some(start), Some(end)) = (start, end) {
+        if start > end {
+            return Err(ValidationError::new("time_range_reversed"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_country(country: &str) -> std::result::Result<(), ValidationError> {
+    if !COUNTRY_REGEX.is_match(country) {
+        return Err(ValidationError::new("invalid_country"));
+    }
+    Ok(())
+}
+
+fn validate_language(language: &str) -> std::result::Result<(), ValidationError> {
+    if !LANGUAGE_REGEX.is_match(language) {
+        return Err(ValidationError::new("invalid_language"));
+    }
+    Ok(())
+}
+
 // Helper functions
 fn has_excessive_repetition(text: &str) -> bool {
     // Check for repeated characters (more than 10 in a row)
@@ -324,6 +408,11 @@ fn format_validation_errors(errors: &validator::ValidationErrors) -> String {
                 "duplicate_url" => "Duplicate URLs not allowed",
                 "suspicious_url" => "Suspicious URL detected",
                 "url_too_long" => "URL too long",
+                "empty_time_range" => "Custom time range needs a start or end date",
+                "invalid_date" => "Dates must be written YYYY-MM-DD",
+                "time_range_reversed" => "Time range starts after it ends",
+                "invalid_country" => "Country must be a two-letter ISO 3166-1 code",
+                "invalid_language" => "Language must be an ISO 639-1 code such as en or pt-BR",
                 _ => "Validation error",
             };
             formatted.push(format!("{}: {}", field, message));
@@ -338,6 +427,29 @@ pub fn validate_search_params(params: &BaseSearchParams) -> Result<ValidatedSear
     ValidatedSearchParams::from_base_params(params.clone())
 }
 
+/// Check a `page_token` against how `provider` pages.
+///
+/// A provider that pages by offset only accepts a token holding an offset, up to
+/// the same limit as `offset`. One that does not page accepts no token at all.
+pub fn validate_page_token(provider: &dyn SearchProvider, params: &BaseSearchParams) -> Result<()> {
+    let Some(token) = params.page_token.as_deref() else {
+        return Ok(());
+    };
+    match provider.capabilities().pagination {
+        Pagination::Offset => {
+            let offset: u32 = token
+                .parse()
+                .map_err(|_| eyre!("Invalid page token for {}", provider.name()))?;
+            if offset > MAX_OFFSET {
+                return Err(eyre!("Page token offset must be at most {}", MAX_OFFSET));
+            }
+            Ok(())
+        }
+        Pagination::Cursor => Ok(()),
+        Pagination::None => Err(eyre!("{} does not page its results", provider.name())),
+    }
+}
+
 pub fn sanitize_query(query: &str) -> String {
     // Remove or replace potentially problematic characters
     query
@@ -400,6 +512,13 @@ mod tests {
             limit: Some(10),
             include_domains: Some(vec!["github.com".to_string()]),
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         };
 
         let result = validate_search_params(&params);
@@ -413,6 +532,13 @@ mod tests {
             limit: Some(10),
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         };
 
         let result = validate_search_params(&params);
@@ -427,6 +553,13 @@ mod tests {
             limit: Some(10),
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         };
 
         let result = validate_search_params(&params);
@@ -440,6 +573,13 @@ mod tests {
             limit: Some(10),
             include_domains: Some(vec!["invalid..domain".to_string()]),
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         };
 
         let result = validate_search_params(&params);
@@ -454,6 +594,13 @@ mod tests {
             limit: Some(10),
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         };
 
         let result = validate_search_params(&params);
@@ -467,12 +614,121 @@ mod tests {
             limit: Some(101), // Over maximum
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         };
 
         let result = validate_search_params(&params);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_paging_validation() {
+        let params = BaseSearchParams {
+            query: "test".to_string(),
+            offset: Some(20),
+            page_token: Some("tavily=20".to_string()),
+            ..Default::default()
+        };
+        let validated = validate_search_params(&params).unwrap();
+        assert_eq!(validated.to_base_params().offset, Some(20));
+        assert_eq!(
+            validated.to_base_params().page_token.as_deref(),
+            Some("tavily=20")
+        );
+
+        let params = BaseSearchParams {
+            query: "test".to_string(),
+            offset: Some(5_000),
+            ..Default::default()
+        };
+        assert!(validate_search_params(&params).is_err());
+    }
+
+    struct Paged(Pagination);
+
+    #[async_trait::async_trait]
+    impl SearchProvider for Paged {
+        async fn search(
+            &self,
+            _params: BaseSearchParams,
+        ) -> Result<Vec<crate::common::types::SearchResult>, crate::common::types::ProviderError>
+        {
+            Ok(Vec::new())
+        }
+
+        fn name(&self) -> &'static str {
+            "paged"
+        }
+
+        fn description(&self) -> &'static str {
+            "Paged test provider"
+        }
+
+        fn capabilities(&self) -> crate::common::types::ProviderCapabilities {
+            crate::common::types::ProviderCapabilities {
+                pagination: self.0,
+                ..Default::default()
+            }
+        }
+    }
+
+    #[test]
+    fn test_page_token_validation() {
+        let params = |token: &str| BaseSearchParams {
+            query: "test".to_string(),
+            page_token: Some(token.to_string()),
+            ..Default::default()
+        };
+
+        // An offset token gets the same limit as `offset`
+        let offset = Paged(Pagination::Offset);
+        assert!(validate_page_token(&offset, &params("1000")).is_ok());
+        assert!(validate_page_token(&offset, &params("1001")).is_err());
+        assert!(validate_page_token(&offset, &params("t3_abc")).is_err());
+        assert!(validate_page_token(&offset, &BaseSearchParams::default()).is_ok());
+
+        assert!(validate_page_token(&Paged(Pagination::Cursor), &params("t3_abc")).is_ok());
+        assert!(validate_page_token(&Paged(Pagination::None), &params("10")).is_err());
+    }
+
+    #[test]
+    fn test_filter_validation() {
+        let params = |time_range, country: &str, language: &str| BaseSearchParams {
+            query: "test".to_string(),
+            time_range: Some(time_range),
+            country: Some(country.to_string()),
+            language: Some(language.to_string()),
+            ..Default::default()
+        };
+        let custom = |start: Option<&str>, end: Option<&str>| TimeRange::Custom {
+            start: start.map(str::to_string),
+            end: end.map(str::to_string),
+        };
+
+        assert!(validate_search_params(&params(TimeRange::Week, "us", "en")).is_ok());
+        assert!(validate_search_params(&params(TimeRange::Day, "TW", "zh-Hant")).is_ok());
+        assert!(
+            validate_search_params(&params(custom(Some("2024-01-01"), None), "br", "pt-BR"))
+                .is_ok()
+        );
+
+        for invalid in [
+            params(custom(None, None), "us", "en"),
+            params(custom(Some("2024-02-30"), None), "us", "en"),
+            params(custom(Some("2024-06-01"), Some("2024-01-01")), "us", "en"),
+            params(TimeRange::Week, "usa", "en"),
+            params(TimeRange::Week, "us", "english"),
+        ] {
+            assert!(validate_search_params(&invalid).is_err());
+        }
+    }
+
     #[test]
     fn test_sanitize_query() {
         let dirty_query = "test\0query\x01with\x7fcontrol";
@@ -1,11 +1,12 @@
+use crate::common::types::MergeStrategy;
 use eyre::{eyre, Result};
 use figment::{
-    providers::{Env, Format, Toml, Yaml},
+    providers::{Env, Format, Serialized, Toml, Yaml},
     Figment,
 };
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::{path::Path, time::Duration};
+use std::{collections::HashMap, path::Path, time::Duration};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -16,6 +17,12 @@ pub struct Config {
     pub logging: LoggingConfig,
     pub providers: ProvidersConfig,
     pub circuit_breaker: CircuitBreakerConfig,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub prompts: PromptsConfig,
+    #[serde(default)]
+    pub search: SearchConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +64,9 @@ pub struct MetricsConfig {
 pub struct LoggingConfig {
     pub level: String,
     pub json_format: bool,
+    /// Append local logs to this file instead of writing them to stderr
+    #[serde(default)]
+    pub file: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +77,94 @@ pub struct CircuitBreakerConfig {
     pub half_open_max_calls: u32,
 }
 
+/// Authentication for the HTTP transport
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    pub enabled: bool,
+    /// Static bearer tokens, each mapped to a client id
+    pub tokens: Vec<StaticTokenConfig>,
+    /// Optional JWT validation against a JWKS
+    pub jwt: Option<JwtConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaticTokenConfig {
+    pub client_id: String,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwtConfig {
+    /// Path to a local JWKS file
+    pub jwks_path: Option<String>,
+    /// URL of a JWKS document, fetched at startup and refreshed as keys rotate
+    pub jwks_url: Option<String>,
+    /// How often to reload the JWKS, in seconds
+    #[serde(default = "default_jwks_refresh_seconds")]
+    pub jwks_refresh_seconds: u64,
+    /// Signing algorithms to accept, such as `RS256`. Keys that name their own
+    /// `alg` must use one of these; keys that do not are used with all of them.
+    #[serde(default)]
+    pub algorithms: Vec<String>,
+    /// Required `iss` claim
+    pub issuer: Option<String>,
+    /// Required `aud` claim
+    pub audience: Option<String>,
+    /// Claim used as the client id
+    #[serde(default = "default_client_id_claim")]
+    pub client_id_claim: String,
+}
+
+fn default_client_id_claim() -> String {
+    "sub".to_string()
+}
+
+fn default_jwks_refresh_seconds() -> u64 {
+    3600
+}
+
+/// MCP prompt templates
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PromptsConfig {
+    /// Directory of extra `.toml`/`.yaml` prompt files, added to the built-in prompts
+    pub directory: Option<String>,
+}
+
+/// How results from several providers are combined
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchConfig {
+    /// Strategy used when a request does not choose one
+    pub merge_strategy: MergeStrategy,
+    /// Trust weights for the `weighted` strategy, by provider name; unlisted providers weigh 1.0
+    pub provider_weights: HashMap<String, f64>,
+    pub hedging: HedgingConfig,
+}
+
+/// Hedged requests: when a provider is slower than usual, the same search is
+/// sent to the next provider and whichever answers first is used
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HedgingConfig {
+    pub enabled: bool,
+    /// Hedge once a provider has taken longer than this percentile (0-100) of its recent latencies
+    pub percentile: f64,
+    /// Successful requests a provider needs on record before it is hedged
+    pub min_samples: usize,
+}
+
+impl Default for HedgingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            percentile: 95.0,
+            min_samples: 20,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProvidersConfig {
     pub tavily: ProviderConfig,
@@ -76,7 +174,7 @@ pub struct ProvidersConfig {
     pub baidu: ProviderConfig,
     pub brightdata: BrightDataProviderConfig,
     pub exa: ProviderConfig,
-    pub brave: ProviderConfig,
+    pub brave: BraveProviderConfig,
     pub kagi: ProviderConfig,
     pub perplexity: ProviderConfig,
     pub jina: ProviderConfig,
@@ -121,6 +219,18 @@ pub struct BrightDataProviderConfig {
     pub base_url: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BraveProviderConfig {
+    pub enabled: bool,
+    pub api_key: Option<String>,
+    pub rate_limit: u32,
+    pub timeout_seconds: u64,
+    pub base_url: Option<String>,
+    /// Goggle to rerank web and news results with, as a URL or an inline definition
+    #[serde(default)]
+    pub goggles: Option<String>,
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -147,6 +257,7 @@ impl Default for Config {
             logging: LoggingConfig {
                 level: "info".to_string(),
                 json_format: false,
+                file: None,
             },
             circuit_breaker: CircuitBreakerConfig {
                 enabled: true,
@@ -155,6 +266,9 @@ impl Default for Config {
                 half_open_max_calls: 3,
             },
             providers: ProvidersConfig::default(),
+            auth: AuthConfig::default(),
+            prompts: PromptsConfig::default(),
+            search: SearchConfig::default(),
         }
     }
 }
@@ -213,12 +327,13 @@ impl Default for ProvidersConfig {
                 timeout_seconds: 30,
                 base_url: Some("https://api.exa.ai".to_string()),
             },
-            brave: ProviderConfig {
+            brave: BraveProviderConfig {
                 enabled: true,
                 api_key: std::env::var("BRAVE_API_KEY").ok(),
                 rate_limit: 100,
                 timeout_seconds: 30,
                 base_url: Some("https://api.search.brave.com/res/v1".to_string()),
+                goggles: std::env::var("BRAVE_GOGGLES").ok(),
             },
             kagi: ProviderConfig {
                 enabled: true,
@@ -256,20 +371,20 @@ impl Default for ProvidersConfig {
 
 impl Config {
     pub fn load() -> Result<Self> {
-        let config: Config = Figment::new()
+        let config: Config = Figment::from(Serialized::defaults(Config::default()))
             .merge(Toml::file("config.toml"))
             .merge(Yaml::file("config.yaml"))
             .merge(Yaml::file("config.yml"))
-            .merge(Env::prefixed("OMNISEARCH_"))
+            .merge(Env::prefixed("OMNISEARCH_").split("__"))
             .extract()?;
 
         Ok(config)
     }
 
     pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let config: Config = Figment::new()
+        let config: Config = Figment::from(Serialized::defaults(Config::default()))
             .merge(Toml::file(path.as_ref()))
-            .merge(Env::prefixed("OMNISEARCH_"))
+            .merge(Env::prefixed("OMNISEARCH_").split("__"))
             .extract()?;
 
         Ok(config)
@@ -341,6 +456,12 @@ pub fn validate_config() -> Result<()> {
         missing_providers.push("exa (missing EXA_API_KEY)");
     }
 
+    if config.providers.brave.enabled && config.providers.brave.api_key.is_some() {
+        available_providers.push("brave");
+    } else if config.providers.brave.enabled {
+        missing_providers.push("brave (missing BRAVE_API_KEY)");
+    }
+
     // Log results
     if !available_providers.is_empty() {
         tracing::info!("Available providers: {}", available_providers.join(", "));
@@ -13,7 +13,7 @@
 //!
 //! ## Library Usage
 //!
-//! ```rust
+//! ```rust,no_run
 //! use omnisearch_mcp::{OmnisearchClient, SearchRequest};
 //!
 //! #[tokio::main]
@@ -26,9 +26,9 @@
 //!         .limit(10)
 //!         .include_domains(&["github.com", "docs.rs"]);
 //!
-//!     let results = client.search(request).await?;
+//!     let response = client.search(request).await?;
 //!
-//!     for result in results {
+//!     for result in response.results {
 //!         println!("{}: {}", result.title, result.url);
 //!     }
 //!
@@ -57,7 +57,9 @@ pub mod providers;
 pub mod server;
 
 // Re-export common types for library users
-pub use common::types::{BaseSearchParams, ErrorType, ProviderError, SearchProvider, SearchResult};
+pub use common::types::{
+    BaseSearchParams, ErrorType, ProviderCapabilities, ProviderError, SearchProvider, SearchResult,
+};
 
 // Re-export configuration functions
 pub use config::{validate_config, Config, CONFIG};
@@ -75,7 +77,11 @@ pub use server::{register_tools, setup_handlers};
 
 // High-level client API for library usage
 mod client;
-pub use client::{OmnisearchClient, SearchRequest, SearchResponse};
+pub use client::{
+    MergeStrategy, OmnisearchClient, OmnisearchClientBuilder, ProviderEvent, ProviderResults,
+    ReciprocalRankFusion, ResultMerger, RoundRobin, ScoreNormalization, SearchCategory,
+    SearchRequest, SearchResponse, WeightedTrust, DEFAULT_RRF_K,
+};
 
 /// The current version of the omnisearch-mcp crate
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -87,9 +93,7 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 ///
 /// # Example
 ///
-/// ```rust
-/// use omnisearch_mcp;
-///
+/// ```rust,no_run
 /// #[tokio::main]
 /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
 ///     let providers = omnisearch_mcp::initialize().await?;
@@ -98,8 +102,8 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 /// }
 /// ```
 pub async fn initialize() -> Result<Vec<Box<dyn SearchProvider>>, ProviderError> {
-    // Initialize tracing for library users
-    tracing_subscriber::fmt::init();
+    // Initialize tracing for library users, unless the application already has
+    let _ = tracing_subscriber::fmt::try_init();
 
     // Validate configuration
     validate_config().map_err(|e| {
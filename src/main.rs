@@ -1,15 +1,34 @@
 use async_trait::async_trait;
 use eyre::Result;
-use omnisearch_mcp::{config::validate_config, providers::initialize_providers};
-use rust_mcp_sdk::schema::{
-    schema_utils::CallToolError, CallToolRequest, CallToolResult, Implementation, InitializeResult,
-    ListToolsRequest, ListToolsResult, RpcError, ServerCapabilities, ServerCapabilitiesTools,
-    LATEST_PROTOCOL_VERSION,
+use omnisearch_mcp::{
+    config::{validate_config, CONFIG},
+    providers::initialize_providers,
+    server::{
+        auth::client_for_session,
+        batch::{parse_batch_args, run_batch},
+        call_tool_with_context, list_tools,
+        logging::{init_logging, session_span, MCP_LOGGING},
+        prompts::{get_prompt, list_prompts, load_configured_prompts},
+        requests::{ToolCallContext, IN_FLIGHT_REQUESTS},
+        resources::{
+            list_resource_templates, list_resources, read_resource, subscribe_resource,
+            unsubscribe_resource,
+        },
+        search_client,
+        transport::{parse_transport_args, serve_http, serve_stdio, TransportMode},
+    },
 };
-use rust_mcp_sdk::{
-    mcp_server::{server_runtime, ServerHandler, ServerRuntime},
-    McpServer, StdioTransport, TransportOptions,
+use rust_mcp_sdk::schema::{
+    schema_utils::CallToolError, CallToolRequest, CallToolResult, GetPromptRequest,
+    GetPromptResult, Implementation, InitializeResult, ListPromptsRequest, ListPromptsResult,
+    ListResourceTemplatesRequest, ListResourceTemplatesResult, ListResourcesRequest,
+    ListResourcesResult, ListToolsRequest, ListToolsResult, ReadResourceRequest,
+    ReadResourceResult, RpcError, ServerCapabilities, ServerCapabilitiesPrompts,
+    ServerCapabilitiesResources, ServerCapabilitiesTools, SetLevelRequest, SubscribeRequest,
+    UnsubscribeRequest, LATEST_PROTOCOL_VERSION,
 };
+use rust_mcp_sdk::{mcp_server::ServerHandler, McpServer};
+use tracing::Instrument;
 
 struct OmnisearchServerHandler;
 
@@ -20,32 +39,131 @@ impl ServerHandler for OmnisearchServerHandler {
         _request: ListToolsRequest,
         _runtime: &dyn McpServer,
     ) -> std::result::Result<ListToolsResult, RpcError> {
-        // Initialize our providers when listing tools
-        validate_config().map_err(|e| RpcError::internal_error().with_message(e.to_string()))?;
-        initialize_providers();
-
         Ok(ListToolsResult {
             meta: None,
             next_cursor: None,
-            tools: vec![],
+            tools: list_tools(),
         })
     }
 
     async fn handle_call_tool_request(
         &self,
-        _request: CallToolRequest,
-        _runtime: &dyn McpServer,
+        request: CallToolRequest,
+        runtime: &dyn McpServer,
     ) -> std::result::Result<CallToolResult, CallToolError> {
-        Err(CallToolError::new(
-            RpcError::method_not_found().with_message("Tool not found".to_string()),
-        ))
+        let client = runtime
+            .session_id()
+            .and_then(|session_id| client_for_session(&session_id));
+        let session_id = runtime.session_id().unwrap_or_default();
+        let in_flight = IN_FLIGHT_REQUESTS.take(&session_id, &request.params);
+        let context = ToolCallContext::new(runtime, in_flight);
+        let params = request.params;
+        // Anything logged while the tool runs is only forwarded to this session
+        call_tool_with_context(&params.name, params.arguments, client.as_ref(), &context)
+            .instrument(session_span(&session_id))
+            .await
+    }
+
+    async fn handle_list_prompts_request(
+        &self,
+        _request: ListPromptsRequest,
+        _runtime: &dyn McpServer,
+    ) -> std::result::Result<ListPromptsResult, RpcError> {
+        Ok(ListPromptsResult {
+            meta: None,
+            next_cursor: None,
+            prompts: list_prompts(),
+        })
+    }
+
+    async fn handle_get_prompt_request(
+        &self,
+        request: GetPromptRequest,
+        _runtime: &dyn McpServer,
+    ) -> std::result::Result<GetPromptResult, RpcError> {
+        get_prompt(&request.params.name, request.params.arguments)
+    }
+
+    async fn handle_set_level_request(
+        &self,
+        request: SetLevelRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<rust_mcp_sdk::schema::Result, RpcError> {
+        let session_id = runtime.session_id().unwrap_or_default();
+        MCP_LOGGING.set_level(&session_id, request.params.level);
+        Ok(rust_mcp_sdk::schema::Result::default())
+    }
+
+    async fn handle_list_resources_request(
+        &self,
+        _request: ListResourcesRequest,
+        _runtime: &dyn McpServer,
+    ) -> std::result::Result<ListResourcesResult, RpcError> {
+        Ok(ListResourcesResult {
+            meta: None,
+            next_cursor: None,
+            resources: list_resources(),
+        })
+    }
+
+    async fn handle_list_resource_templates_request(
+        &self,
+        _request: ListResourceTemplatesRequest,
+        _runtime: &dyn McpServer,
+    ) -> std::result::Result<ListResourceTemplatesResult, RpcError> {
+        Ok(ListResourceTemplatesResult {
+            meta: None,
+            next_cursor: None,
+            resource_templates: list_resource_templates(),
+        })
+    }
+
+    async fn handle_read_resource_request(
+        &self,
+        request: ReadResourceRequest,
+        _runtime: &dyn McpServer,
+    ) -> std::result::Result<ReadResourceResult, RpcError> {
+        read_resource(&request.params.uri).await
+    }
+
+    async fn handle_subscribe_request(
+        &self,
+        request: SubscribeRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<rust_mcp_sdk::schema::Result, RpcError> {
+        let session_id = runtime.session_id().unwrap_or_default();
+        subscribe_resource(&session_id, &request.params.uri).await?;
+        Ok(rust_mcp_sdk::schema::Result::default())
+    }
+
+    async fn handle_unsubscribe_request(
+        &self,
+        request: UnsubscribeRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<rust_mcp_sdk::schema::Result, RpcError> {
+        let session_id = runtime.session_id().unwrap_or_default();
+        unsubscribe_resource(&session_id, &request.params.uri);
+        Ok(rust_mcp_sdk::schema::Result::default())
     }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt::init();
+    // Log to stderr (or the configured file) so stdout stays free for JSON-RPC
+    init_logging(&CONFIG.logging)?;
+
+    let transport_mode = parse_transport_args(std::env::args()).map_err(|e| eyre::eyre!(e))?;
+
+    // Validate configuration and register providers before serving any requests
+    validate_config()?;
+    initialize_providers();
+
+    // `--batch` runs a file of searches and exits instead of serving MCP
+    if let Some(batch) = parse_batch_args(std::env::args()).map_err(|e| eyre::eyre!(e))? {
+        return run_batch(&search_client(), &batch).await;
+    }
+
+    load_configured_prompts(&CONFIG.prompts)?;
 
     // Define server details and capabilities
     let server_details = InitializeResult {
@@ -56,6 +174,12 @@ async fn main() -> Result<()> {
         },
         capabilities: ServerCapabilities {
             tools: Some(ServerCapabilitiesTools { list_changed: None }),
+            logging: Some(serde_json::Map::new()),
+            prompts: Some(ServerCapabilitiesPrompts { list_changed: None }),
+            resources: Some(ServerCapabilitiesResources {
+                list_changed: None,
+                subscribe: Some(true),
+            }),
             ..Default::default()
         },
         meta: None,
@@ -63,27 +187,11 @@ async fn main() -> Result<()> {
         protocol_version: LATEST_PROTOCOL_VERSION.to_string(),
     };
 
-    // Create std transport with default options
-    let transport = StdioTransport::new(TransportOptions::default())
-        .map_err(|e| eyre::eyre!("Failed to create transport: {}", e))?;
-
     // Instantiate our custom handler for handling MCP messages
     let handler = OmnisearchServerHandler {};
 
-    // Create a MCP server
-    let server: ServerRuntime = server_runtime::create_server(server_details, transport, handler);
-
-    println!("Omnisearch MCP server running on stdio");
-
-    // Start the server
-    if let Err(start_error) = server.start().await {
-        eprintln!(
-            "{}",
-            start_error
-                .rpc_error_message()
-                .unwrap_or(&start_error.to_string())
-        );
+    match transport_mode {
+        TransportMode::Stdio => serve_stdio(server_details, handler).await,
+        TransportMode::Http => serve_http(server_details, handler, &CONFIG).await,
     }
-
-    Ok(())
 }
@@ -1,7 +1,11 @@
+use crate::common::dates::normalize_date;
+use crate::common::http::{handle_http_error, request_error, retry_after};
 use crate::common::types::{
-    BaseSearchParams, ErrorType, ProviderError, SearchProvider, SearchResult,
+    BaseSearchParams, CostClass, ErrorType, Freshness, NewsResult, Pagination,
+    ProviderCapabilities, ProviderError, ResultDetails, ResultType, SearchFilter, SearchProvider,
+    SearchResult, SearchVertical,
 };
-use crate::config::CONFIG;
+use crate::config::{ProviderConfig, CONFIG};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -17,10 +21,17 @@ struct BaiduResult {
     title: String,
     link: String,
     snippet: String,
+    position: Option<u32>,
+    // Either a date or a relative time such as "3天前"
+    date: Option<String>,
+    thumbnail: Option<String>,
+    // The publisher, for news results
+    source: Option<String>,
 }
 
 pub struct BaiduSearchProvider {
     client: Client,
+    config: ProviderConfig,
 }
 
 impl Default for BaiduSearchProvider {
@@ -31,14 +42,21 @@ impl Default for BaiduSearchProvider {
 
 impl BaiduSearchProvider {
     pub fn new() -> Self {
+        Self::with_config(CONFIG.providers.baidu.clone())
+    }
+
+    /// Create the provider from explicit settings rather than the global config
+    pub fn with_config(config: ProviderConfig) -> Self {
         let client = Client::builder()
-            .timeout(Duration::from_millis(
-                CONFIG.providers.baidu.timeout_seconds * 1000,
-            ))
+            .timeout(Duration::from_millis(config.timeout_seconds * 1000))
             .build()
             .expect("Failed to create HTTP client");
+        Self::with_client(config, client)
+    }
 
-        Self { client }
+    /// Create the provider from explicit settings, sharing an existing HTTP client
+    pub fn with_client(config: ProviderConfig, client: Client) -> Self {
+        Self { client, config }
     }
 }
 
@@ -52,19 +70,46 @@ impl SearchProvider for BaiduSearchProvider {
         "Search the web using Baidu Search via SerpApi. Provides search results from China's leading search engine. Requires SerpApi API key."
     }
 
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            filters: vec![SearchFilter::Language],
+            max_limit: 50,
+            pagination: Pagination::Offset,
+            freshness: Freshness::None,
+            languages: vec!["zh".to_string()],
+            result_types: vec![ResultType::Web, ResultType::News],
+            cost: CostClass::Paid,
+            requires_api_key: true,
+        }
+    }
+
+    // Baidu can only be told which Chinese script to return
+    fn unsupported_filters(&self, params: &BaseSearchParams) -> Vec<SearchFilter> {
+        let mut unsupported = self.capabilities().unsupported_filters(params);
+        if params.language.is_some() && chinese_script(params).is_none() {
+            unsupported.push(SearchFilter::Language);
+            unsupported.sort();
+        }
+        unsupported
+    }
+
     async fn search(&self, params: BaseSearchParams) -> Result<Vec<SearchResult>, ProviderError> {
-        let api_key = CONFIG.providers.baidu.api_key.as_ref().ok_or_else(|| {
+        let api_key = self.config.api_key.as_ref().ok_or_else(|| {
             ProviderError::new(
-                ErrorType::ApiError,
+                ErrorType::Authentication,
                 "Missing SerpApi API key".to_string(),
                 self.name().to_string(),
                 None,
             )
         })?;
 
+        // SerpApi serves Baidu News as a separate engine
+        let news = params.vertical == Some(SearchVertical::News);
+        let engine = if news { "baidu_news" } else { "baidu" };
+
         // Prepare query parameters
         let mut query_params = vec![
-            ("engine", "baidu".to_string()),
+            ("engine", engine.to_string()),
             ("api_key", api_key.clone()),
             ("q", params.query.clone()),
         ];
@@ -74,14 +119,22 @@ impl SearchProvider for BaiduSearchProvider {
             query_params.push(("num", limit_str));
         }
 
+        if let Some(ct) = chinese_script(&params) {
+            query_params.push(("ct", ct.to_string()));
+        }
+
+        // SerpApi's `pn` is the offset of the first result
+        let offset = params.start_offset();
+        if offset > 0 {
+            query_params.push(("pn", offset.to_string()));
+        }
+
         // Make the request
         let response = self
             .client
             .get(format!(
                 "{}/search",
-                CONFIG
-                    .providers
-                    .baidu
+                self.config
                     .base_url
                     .as_deref()
                     .unwrap_or("https://serpapi.com")
@@ -89,90 +142,74 @@ impl SearchProvider for BaiduSearchProvider {
             .query(&query_params)
             .send()
             .await
-            .map_err(|e| {
-                ProviderError::new(
-                    ErrorType::ApiError,
-                    format!("Failed to send request: {}", e),
-                    self.name().to_string(),
-                    Some(e.into()),
-                )
-            })?;
+            .map_err(|e| request_error(e, self.name()))?;
 
         // Check for errors
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = retry_after(response.headers());
             let error_message = match response.text().await {
                 Ok(text) => text,
                 Err(_) => status.to_string(),
             };
 
-            match status.as_u16() {
-                401 => {
-                    return Err(ProviderError::new(
-                        ErrorType::ApiError,
-                        "Invalid SerpApi API key".to_string(),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-                403 => {
-                    return Err(ProviderError::new(
-                        ErrorType::ApiError,
-                        "SerpApi API access forbidden".to_string(),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-                429 => {
-                    return Err(ProviderError::new(
-                        ErrorType::RateLimit,
-                        "SerpApi rate limit exceeded".to_string(),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-                500 => {
-                    return Err(ProviderError::new(
-                        ErrorType::ProviderError,
-                        "SerpApi internal error".to_string(),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-                _ => {
-                    return Err(ProviderError::new(
-                        ErrorType::ApiError,
-                        format!("Unexpected error: {}", error_message),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-            }
+            let error = handle_http_error(
+                status,
+                error_message,
+                self.name(),
+                "SerpApi rate limit exceeded",
+                "Invalid SerpApi API key",
+                "SerpApi API access forbidden",
+                "SerpApi internal error",
+            );
+            return Err(error.with_retry_after(retry_after));
         }
 
         // Parse the response
-        let data: BaiduSearchResponse = response.json().await.map_err(|e| {
-            ProviderError::new(
-                ErrorType::ApiError,
-                format!("Failed to parse response: {}", e),
-                self.name().to_string(),
-                Some(e.into()),
-            )
-        })?;
+        let data: BaiduSearchResponse = response
+            .json()
+            .await
+            .map_err(|e| request_error(e, self.name()))?;
 
         // Convert to SearchResult format
         let results = data
             .organic_results
             .into_iter()
-            .map(|result| SearchResult {
-                title: result.title,
-                url: result.link,
-                snippet: result.snippet,
-                score: None,
-                source_provider: self.name().to_string(),
+            .map(|result| {
+                let published_at = result.date.as_deref().and_then(normalize_date);
+                let mut extra = serde_json::Map::new();
+                if let Some(position) = result.position {
+                    extra.insert("position".to_string(), position.into());
+                }
+                if let Some(date) = result.date.filter(|_| published_at.is_none()) {
+                    extra.insert("date".to_string(), date.into());
+                }
+                SearchResult {
+                    title: result.title,
+                    url: result.link,
+                    snippet: result.snippet,
+                    score: None,
+                    source_provider: self.name().to_string(),
+                    published_at,
+                    thumbnail_url: result.thumbnail,
+                    extra,
+                    details: news.then_some(ResultDetails::News(NewsResult {
+                        publisher: result.source,
+                    })),
+                    ..Default::default()
+                }
             })
             .collect();
 
         Ok(results)
     }
 }
+
+// SerpApi's `ct`: 2 for Simplified Chinese, 3 for Traditional
+fn chinese_script(params: &BaseSearchParams) -> Option<u8> {
+    match params.language.as_deref()?.to_lowercase().as_str() {
+        "zh" | "zh-cn" | "zh-sg" | "zh-hans" => Some(2),
+        "zh-tw" | "zh-hk" | "zh-mo" | "zh-hant" => Some(3),
+        _ => None,
+    }
+}
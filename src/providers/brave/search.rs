@@ -0,0 +1,408 @@
+use crate::common::dates::normalize_date;
+use crate::common::http::{create_http_client, handle_http_error, request_error, retry_after};
+use crate::common::types::{
+    BaseSearchParams, CostClass, ErrorType, Freshness, ImageResult, NewsResult, Pagination,
+    ProviderCapabilities, ProviderError, ResultDetails, ResultType, SafeSearch, SearchFilter,
+    SearchProvider, SearchResult, SearchVertical, TimeRange, VideoResult,
+};
+use crate::config::{BraveProviderConfig, CONFIG};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+// Brave returns at most `MAX_COUNT` results a page, on pages numbered up to
+// `MAX_PAGE`. Image search has no pages, only up to `MAX_IMAGE_COUNT` results
+const MAX_COUNT: u32 = 20;
+const MAX_PAGE: u32 = 9;
+const MAX_IMAGE_COUNT: u32 = 100;
+
+// Web results come under `web`; the other endpoints list them at the top level
+#[derive(Debug, Serialize, Deserialize)]
+struct BraveSearchResponse {
+    web: Option<BraveWebResults>,
+    #[serde(default)]
+    results: Vec<BraveResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BraveWebResults {
+    #[serde(default)]
+    results: Vec<BraveResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BraveResult {
+    title: String,
+    url: String,
+    #[serde(default)]
+    description: String,
+    // A timestamp without an offset, such as "2024-03-05T10:30:00"
+    page_age: Option<String>,
+    // How long ago the page was published, such as "3 days ago"
+    age: Option<String>,
+    language: Option<String>,
+    profile: Option<BraveProfile>,
+    meta_url: Option<BraveMetaUrl>,
+    thumbnail: Option<BraveThumbnail>,
+    extra_snippets: Option<Vec<String>>,
+    // Only for video searches
+    video: Option<BraveVideo>,
+    // Only for image searches
+    properties: Option<BraveImageProperties>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BraveProfile {
+    name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BraveMetaUrl {
+    hostname: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BraveThumbnail {
+    src: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BraveVideo {
+    // "MM:SS" or "HH:MM:SS"
+    duration: Option<String>,
+    creator: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BraveImageProperties {
+    url: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+pub struct BraveSearchProvider {
+    client: Client,
+    config: BraveProviderConfig,
+}
+
+impl Default for BraveSearchProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BraveSearchProvider {
+    pub fn new() -> Self {
+        Self::with_config(CONFIG.providers.brave.clone())
+    }
+
+    /// Create the provider from explicit settings rather than the global config
+    pub fn with_config(config: BraveProviderConfig) -> Self {
+        let client = create_http_client(config.timeout_seconds * 1000);
+        Self::with_client(config, client)
+    }
+
+    /// Create the provider from explicit settings, sharing an existing HTTP client
+    pub fn with_client(config: BraveProviderConfig, client: Client) -> Self {
+        Self { client, config }
+    }
+}
+
+#[async_trait]
+impl SearchProvider for BraveSearchProvider {
+    fn name(&self) -> &'static str {
+        "brave"
+    }
+
+    fn description(&self) -> &'static str {
+        "Search the web using Brave Search API. Independent index with web, news, image and video results, and supports Goggles for custom reranking. Requires Brave Search API key."
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            filters: vec![
+                SearchFilter::Country,
+                SearchFilter::Language,
+                SearchFilter::SafeSearch,
+            ],
+            max_limit: MAX_COUNT,
+            pagination: Pagination::Offset,
+            freshness: Freshness::DateRange,
+            languages: Vec::new(),
+            result_types: vec![
+                ResultType::Web,
+                ResultType::News,
+                ResultType::Image,
+                ResultType::Video,
+            ],
+            cost: CostClass::FreeTier,
+            requires_api_key: true,
+        }
+    }
+
+    // Image search cannot be limited by date
+    fn unsupported_filters(&self, params: &BaseSearchParams) -> Vec<SearchFilter> {
+        let mut unsupported = self.capabilities().unsupported_filters(params);
+        if params.time_range.is_some() && params.vertical == Some(SearchVertical::Images) {
+            unsupported.insert(0, SearchFilter::TimeRange);
+        }
+        unsupported
+    }
+
+    // Pages end at the last result Brave can return
+    fn next_page_token(
+        &self,
+        params: &BaseSearchParams,
+        results: &[SearchResult],
+    ) -> Option<String> {
+        let vertical = params.vertical.unwrap_or_default();
+        params
+            .next_offset(results, MAX_COUNT)
+            .filter(|&offset| offset < last_result(vertical))
+            .map(|offset| offset.to_string())
+    }
+
+    async fn search(&self, params: BaseSearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+        let api_key = self.config.api_key.as_ref().ok_or_else(|| {
+            ProviderError::new(
+                ErrorType::Authentication,
+                "Missing Brave Search API key".to_string(),
+                self.name().to_string(),
+                None,
+            )
+        })?;
+
+        let vertical = params.vertical.unwrap_or_default();
+        let endpoint = match vertical {
+            SearchVertical::News => "news",
+            SearchVertical::Images => "images",
+            SearchVertical::Videos => "videos",
+            SearchVertical::Web | SearchVertical::Academic => "web",
+        };
+
+        let limit = params.limit.unwrap_or(5).max(1);
+        let offset = params.start_offset();
+        if offset >= last_result(vertical) {
+            return Err(ProviderError::new(
+                ErrorType::InvalidInput,
+                format!(
+                    "Brave Search cannot return results past the first {}",
+                    last_result(vertical)
+                ),
+                self.name().to_string(),
+                None,
+            ));
+        }
+        let (count, page, skip) = page_for(vertical, offset, limit);
+
+        let mut query_params = vec![("q", params.query.clone()), ("count", count.to_string())];
+        if page > 0 {
+            query_params.push(("offset", page.to_string()));
+        }
+
+        if vertical != SearchVertical::Images {
+            if let Some(freshness) = params.time_range.as_ref().map(freshness) {
+                query_params.push(("freshness", freshness));
+            }
+        }
+        if let Some(country) = &params.country {
+            query_params.push(("country", country.to_uppercase()));
+        }
+        if let Some(language) = &params.language {
+            query_params.push(("search_lang", search_lang(language)));
+        }
+        if let Some(safe_search) = params.safe_search {
+            let safe = match (safe_search, vertical) {
+                (SafeSearch::Off, _) => "off",
+                // Image search is either filtered or not
+                (SafeSearch::Moderate, SearchVertical::Images) => "strict",
+                (SafeSearch::Moderate, _) => "moderate",
+                (SafeSearch::Strict, _) => "strict",
+            };
+            query_params.push(("safesearch", safe.to_string()));
+        }
+        if matches!(endpoint, "web" | "news") {
+            if let Some(goggles) = &self.config.goggles {
+                query_params.push(("goggles", goggles.clone()));
+            }
+        }
+
+        // Make the request
+        let response = self
+            .client
+            .get(format!(
+                "{}/{}/search",
+                self.config
+                    .base_url
+                    .as_deref()
+                    .unwrap_or("https://api.search.brave.com/res/v1"),
+                endpoint
+            ))
+            .header("X-Subscription-Token", api_key)
+            .header("Accept", "application/json")
+            .query(&query_params)
+            .send()
+            .await
+            .map_err(|e| request_error(e, self.name()))?;
+
+        // Check for errors
+        if !response.status().is_success() {
+            let status = response.status();
+            let retry_after = retry_after(response.headers());
+            let error_message = match response.text().await {
+                Ok(text) => text,
+                Err(_) => status.to_string(),
+            };
+
+            let error = handle_http_error(
+                status,
+                error_message,
+                self.name(),
+                "Brave Search rate limit exceeded",
+                "Invalid Brave Search API key",
+                "Brave Search API access forbidden",
+                "Brave Search API internal error",
+            );
+            return Err(error.with_retry_after(retry_after));
+        }
+
+        // Parse the response
+        let data: BraveSearchResponse = response
+            .json()
+            .await
+            .map_err(|e| request_error(e, self.name()))?;
+
+        // Convert to SearchResult format
+        let results = data
+            .web
+            .map(|web| web.results)
+            .unwrap_or(data.results)
+            .into_iter()
+            .skip(skip as usize)
+            .take(limit as usize)
+            .map(|result| {
+                let published_at = result.page_age.as_deref().and_then(|page_age| {
+                    normalize_date(page_age).or_else(|| normalize_date(&format!("{}Z", page_age)))
+                });
+                let mut extra = serde_json::Map::new();
+                if let Some(age) = result.age.filter(|_| published_at.is_none()) {
+                    extra.insert("age".to_string(), age.into());
+                }
+                if let Some(snippets) = result.extra_snippets.filter(|s| !s.is_empty()) {
+                    extra.insert("extra_snippets".to_string(), snippets.into());
+                }
+                let site_name = result
+                    .profile
+                    .and_then(|profile| profile.name)
+                    .or_else(|| result.meta_url.and_then(|meta_url| meta_url.hostname));
+                let (url, details) = match vertical {
+                    SearchVertical::News => (
+                        result.url,
+                        Some(ResultDetails::News(NewsResult {
+                            publisher: site_name.clone(),
+                        })),
+                    ),
+                    // The result's own URL is the page the image is on
+                    SearchVertical::Images => {
+                        let properties = result.properties;
+                        let image_url = properties.as_ref().and_then(|p| p.url.clone());
+                        let details = ResultDetails::Image(ImageResult {
+                            width: properties.as_ref().and_then(|p| p.width),
+                            height: properties.as_ref().and_then(|p| p.height),
+                            page_url: Some(result.url.clone()),
+                        });
+                        (image_url.unwrap_or(result.url), Some(details))
+                    }
+                    SearchVertical::Videos => {
+                        let video = result.video;
+                        let details = ResultDetails::Video(VideoResult {
+                            duration_seconds: video
+                                .as_ref()
+                                .and_then(|v| v.duration.as_deref())
+                                .and_then(duration_seconds),
+                            channel: video.and_then(|v| v.creator),
+                        });
+                        (result.url, Some(details))
+                    }
+                    SearchVertical::Web | SearchVertical::Academic => (result.url, None),
+                };
+                SearchResult {
+                    title: result.title,
+                    url,
+                    snippet: result.description,
+                    score: None,
+                    source_provider: self.name().to_string(),
+                    published_at,
+                    language: result.language,
+                    thumbnail_url: result.thumbnail.and_then(|thumbnail| thumbnail.src),
+                    site_name,
+                    extra,
+                    details,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        Ok(results)
+    }
+}
+
+// One past the last result Brave can return for a vertical
+fn last_result(vertical: SearchVertical) -> u32 {
+    match vertical {
+        SearchVertical::Images => MAX_IMAGE_COUNT,
+        _ => MAX_COUNT * (MAX_PAGE + 1),
+    }
+}
+
+// Brave's `count` and `offset` for the results from `offset` on, and how many
+// results of that page come before `offset`. Brave's `offset` counts pages of
+// `count` results, so this is the smallest page size that holds all `limit`
+// results in one page, or else the largest page the first result is on. Image
+// search has no pages, so the results before `offset` are fetched too
+fn page_for(vertical: SearchVertical, offset: u32, limit: u32) -> (u32, u32, u32) {
+    if vertical == SearchVertical::Images {
+        return ((offset + limit).min(MAX_IMAGE_COUNT), 0, offset);
+    }
+    let (count, page) = (limit..=MAX_COUNT)
+        .map(|count| (count, offset / count))
+        .find(|&(count, page)| page <= MAX_PAGE && (offset + limit - 1) / count == page)
+        .unwrap_or((MAX_COUNT, offset / MAX_COUNT));
+    (count, page, offset - count * page)
+}
+
+// Brave's `freshness`: a two-letter preset, or two dates joined by `to`
+fn freshness(time_range: &TimeRange) -> String {
+    match time_range {
+        TimeRange::Custom { start, end } => {
+            let today = time::OffsetDateTime::now_utc().date().to_string();
+            format!(
+                "{}to{}",
+                start.as_deref().unwrap_or("1970-01-01"),
+                end.as_deref().unwrap_or(&today)
+            )
+        }
+        TimeRange::Day => "pd".to_string(),
+        TimeRange::Week => "pw".to_string(),
+        TimeRange::Month => "pm".to_string(),
+        TimeRange::Year => "py".to_string(),
+    }
+}
+
+// Brave's `search_lang` keeps the region only for the variants it lists
+fn search_lang(language: &str) -> String {
+    let language = language.to_lowercase();
+    match language.as_str() {
+        "en-gb" | "pt-br" | "pt-pt" | "zh-hans" | "zh-hant" => language,
+        "zh-cn" | "zh-sg" => "zh-hans".to_string(),
+        "zh-tw" | "zh-hk" | "zh-mo" => "zh-hant".to_string(),
+        _ => language.split('-').next().unwrap_or_default().to_string(),
+    }
+}
+
+// A video's "MM:SS" or "HH:MM:SS" length in seconds
+fn duration_seconds(duration: &str) -> Option<u64> {
+    duration.split(':').try_fold(0, |seconds, part| {
+        Some(seconds * 60 + part.trim().parse::<u64>().ok()?)
+    })
+}
@@ -1,7 +1,9 @@
+use crate::common::http::{handle_http_error, request_error, retry_after};
 use crate::common::types::{
-    BaseSearchParams, ErrorType, ProviderError, SearchProvider, SearchResult,
+    BaseSearchParams, CostClass, ErrorType, Freshness, Pagination, ProviderCapabilities,
+    ProviderError, ResultType, SafeSearch, SearchFilter, SearchProvider, SearchResult, TimeRange,
 };
-use crate::config::CONFIG;
+use crate::config::{BrightDataProviderConfig, CONFIG};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -17,10 +19,13 @@ struct BrightDataResult {
     title: String,
     url: String,
     description: String,
+    rank: Option<u32>,
+    image: Option<String>,
 }
 
 pub struct BrightDataSearchProvider {
     client: Client,
+    config: BrightDataProviderConfig,
 }
 
 impl Default for BrightDataSearchProvider {
@@ -31,14 +36,21 @@ impl Default for BrightDataSearchProvider {
 
 impl BrightDataSearchProvider {
     pub fn new() -> Self {
+        Self::with_config(CONFIG.providers.brightdata.clone())
+    }
+
+    /// Create the provider from explicit settings rather than the global config
+    pub fn with_config(config: BrightDataProviderConfig) -> Self {
         let client = Client::builder()
-            .timeout(Duration::from_millis(
-                CONFIG.providers.brightdata.timeout_seconds * 1000,
-            ))
+            .timeout(Duration::from_millis(config.timeout_seconds * 1000))
             .build()
             .expect("Failed to create HTTP client");
+        Self::with_client(config, client)
+    }
 
-        Self { client }
+    /// Create the provider from explicit settings, sharing an existing HTTP client
+    pub fn with_client(config: BrightDataProviderConfig, client: Client) -> Self {
+        Self { client, config }
     }
 }
 
@@ -52,39 +64,92 @@ impl SearchProvider for BrightDataSearchProvider {
         "Search the web using Bright Data SERP API. Provides high-quality search results with advanced filtering options. Requires Bright Data API credentials."
     }
 
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            filters: vec![
+                SearchFilter::Country,
+                SearchFilter::Language,
+                SearchFilter::SafeSearch,
+                SearchFilter::IncludeDomains,
+                SearchFilter::ExcludeDomains,
+            ],
+            max_limit: 100,
+            pagination: Pagination::Offset,
+            freshness: Freshness::DateRange,
+            languages: Vec::new(),
+            result_types: vec![ResultType::Web],
+            cost: CostClass::Paid,
+            requires_api_key: true,
+        }
+    }
+
     async fn search(&self, params: BaseSearchParams) -> Result<Vec<SearchResult>, ProviderError> {
-        let username = CONFIG
-            .providers
-            .brightdata
-            .username
-            .as_ref()
-            .ok_or_else(|| {
-                ProviderError::new(
-                    ErrorType::ApiError,
-                    "Missing Bright Data username".to_string(),
-                    self.name().to_string(),
-                    None,
-                )
-            })?;
-
-        let password = CONFIG
-            .providers
-            .brightdata
-            .password
-            .as_ref()
-            .ok_or_else(|| {
-                ProviderError::new(
-                    ErrorType::ApiError,
-                    "Missing Bright Data password".to_string(),
-                    self.name().to_string(),
-                    None,
-                )
-            })?;
+        let username = self.config.username.as_ref().ok_or_else(|| {
+            ProviderError::new(
+                ErrorType::Authentication,
+                "Missing Bright Data username".to_string(),
+                self.name().to_string(),
+                None,
+            )
+        })?;
+
+        let password = self.config.password.as_ref().ok_or_else(|| {
+            ProviderError::new(
+                ErrorType::Authentication,
+                "Missing Bright Data password".to_string(),
+                self.name().to_string(),
+                None,
+            )
+        })?;
 
         // Prepare query parameters
         let limit_str = params.limit.unwrap_or(5).to_string();
         let mut query_params = vec![("q", params.query.clone()), ("limit", limit_str)];
 
+        let offset = params.start_offset();
+        if offset > 0 {
+            query_params.push(("start", offset.to_string()));
+        }
+
+        // The SERP API takes Google's own parameters
+        match &params.time_range {
+            Some(TimeRange::Custom { start, end }) => {
+                // Google writes custom ranges as `cdr:1,cd_min:M/D/YYYY,cd_max:M/D/YYYY`
+                let day = |date: &Option<String>| {
+                    date.as_deref()
+                        .and_then(|date| {
+                            let mut parts = date.splitn(3, '-');
+                            let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+                            Some(format!("{}/{}/{}", month, day, year))
+                        })
+                        .unwrap_or_default()
+                };
+                query_params.push((
+                    "tbs",
+                    format!("cdr:1,cd_min:{},cd_max:{}", day(start), day(end)),
+                ));
+            }
+            Some(time_range) => {
+                if let Some(name) = time_range.preset_name() {
+                    query_params.push(("tbs", format!("qdr:{}", &name[..1])));
+                }
+            }
+            None => {}
+        }
+        if let Some(country) = &params.country {
+            query_params.push(("gl", country.to_lowercase()));
+        }
+        if let Some(language) = &params.language {
+            query_params.push(("hl", language.clone()));
+        }
+        if let Some(safe_search) = params.safe_search {
+            let safe = match safe_search {
+                SafeSearch::Off => "off",
+                SafeSearch::Moderate | SafeSearch::Strict => "active",
+            };
+            query_params.push(("safe", safe.to_string()));
+        }
+
         if let Some(include_domains) = &params.include_domains {
             if !include_domains.is_empty() {
                 let include_domains_str = include_domains.join(",");
@@ -104,9 +169,7 @@ impl SearchProvider for BrightDataSearchProvider {
             .client
             .get(format!(
                 "{}/search",
-                CONFIG
-                    .providers
-                    .brightdata
+                self.config
                     .base_url
                     .as_deref()
                     .unwrap_or("https://api.brightdata.com/serp")
@@ -115,87 +178,54 @@ impl SearchProvider for BrightDataSearchProvider {
             .query(&query_params)
             .send()
             .await
-            .map_err(|e| {
-                ProviderError::new(
-                    ErrorType::ApiError,
-                    format!("Failed to send request: {}", e),
-                    self.name().to_string(),
-                    Some(e.into()),
-                )
-            })?;
+            .map_err(|e| request_error(e, self.name()))?;
 
         // Check for errors
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = retry_after(response.headers());
             let error_message = match response.text().await {
                 Ok(text) => text,
                 Err(_) => status.to_string(),
             };
 
-            match status.as_u16() {
-                401 => {
-                    return Err(ProviderError::new(
-                        ErrorType::ApiError,
-                        "Invalid Bright Data credentials".to_string(),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-                403 => {
-                    return Err(ProviderError::new(
-                        ErrorType::ApiError,
-                        "Bright Data API access forbidden".to_string(),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-                429 => {
-                    return Err(ProviderError::new(
-                        ErrorType::RateLimit,
-                        "Bright Data rate limit exceeded".to_string(),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-                500 => {
-                    return Err(ProviderError::new(
-                        ErrorType::ProviderError,
-                        "Bright Data API internal error".to_string(),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-                _ => {
-                    return Err(ProviderError::new(
-                        ErrorType::ApiError,
-                        format!("Unexpected error: {}", error_message),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-            }
+            let error = handle_http_error(
+                status,
+                error_message,
+                self.name(),
+                "Bright Data rate limit exceeded",
+                "Invalid Bright Data credentials",
+                "Bright Data API access forbidden",
+                "Bright Data API internal error",
+            );
+            return Err(error.with_retry_after(retry_after));
         }
 
         // Parse the response
-        let data: BrightDataSearchResponse = response.json().await.map_err(|e| {
-            ProviderError::new(
-                ErrorType::ApiError,
-                format!("Failed to parse response: {}", e),
-                self.name().to_string(),
-                Some(e.into()),
-            )
-        })?;
+        let data: BrightDataSearchResponse = response
+            .json()
+            .await
+            .map_err(|e| request_error(e, self.name()))?;
 
         // Convert to SearchResult format
         let results = data
             .results
             .into_iter()
-            .map(|result| SearchResult {
-                title: result.title,
-                url: result.url,
-                snippet: result.description,
-                score: None,
-                source_provider: self.name().to_string(),
+            .map(|result| {
+                let mut extra = serde_json::Map::new();
+                if let Some(rank) = result.rank {
+                    extra.insert("rank".to_string(), rank.into());
+                }
+                SearchResult {
+                    title: result.title,
+                    url: result.url,
+                    snippet: result.description,
+                    score: None,
+                    source_provider: self.name().to_string(),
+                    thumbnail_url: result.image,
+                    extra,
+                    ..Default::default()
+                }
             })
             .collect();
 
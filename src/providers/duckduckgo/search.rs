@@ -1,8 +1,9 @@
-use crate::common::http::{create_http_client, handle_http_error};
+use crate::common::http::{create_http_client, handle_http_error, request_error, retry_after};
 use crate::common::types::{
-    BaseSearchParams, ErrorType, ProviderError, SearchProvider, SearchResult,
+    BaseSearchParams, CostClass, Freshness, Pagination, ProviderCapabilities, ProviderError,
+    ResultType, SafeSearch, SearchFilter, SearchProvider, SearchResult, TimeRange,
 };
-use crate::config::CONFIG;
+use crate::config::{ProviderConfig, CONFIG};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -21,6 +22,7 @@ struct DuckDuckGoResult {
 
 pub struct DuckDuckGoSearchProvider {
     client: Client,
+    config: ProviderConfig,
 }
 
 impl Default for DuckDuckGoSearchProvider {
@@ -31,8 +33,18 @@ impl Default for DuckDuckGoSearchProvider {
 
 impl DuckDuckGoSearchProvider {
     pub fn new() -> Self {
-        let client = create_http_client(CONFIG.providers.duckduckgo.timeout_seconds * 1000);
-        Self { client }
+        Self::with_config(CONFIG.providers.duckduckgo.clone())
+    }
+
+    /// Create the provider from explicit settings rather than the global config
+    pub fn with_config(config: ProviderConfig) -> Self {
+        let client = create_http_client(config.timeout_seconds * 1000);
+        Self::with_client(config, client)
+    }
+
+    /// Create the provider from explicit settings, sharing an existing HTTP client
+    pub fn with_client(config: ProviderConfig, client: Client) -> Self {
+        Self { client, config }
     }
 }
 
@@ -46,25 +58,72 @@ impl SearchProvider for DuckDuckGoSearchProvider {
         "Search the web using DuckDuckGo search API. Provides privacy-focused search results without tracking. No API key required."
     }
 
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            filters: vec![
+                SearchFilter::Country,
+                SearchFilter::Language,
+                SearchFilter::SafeSearch,
+            ],
+            max_limit: 50,
+            pagination: Pagination::Offset,
+            freshness: Freshness::DateRange,
+            languages: Vec::new(),
+            result_types: vec![ResultType::Web],
+            cost: CostClass::Free,
+            requires_api_key: false,
+        }
+    }
+
+    // Date ranges need both ends
+    fn unsupported_filters(&self, params: &BaseSearchParams) -> Vec<SearchFilter> {
+        let mut unsupported = self.capabilities().unsupported_filters(params);
+        if params.time_range.is_some() && date_filter(params).is_none() {
+            unsupported.insert(0, SearchFilter::TimeRange);
+        }
+        unsupported
+    }
+
     async fn search(&self, params: BaseSearchParams) -> Result<Vec<SearchResult>, ProviderError> {
         // Prepare query parameters
         let limit_str = params.limit.unwrap_or(5).to_string();
-        let query_params = vec![
-            ("q", params.query.as_str()),
-            ("kl", "us-en"),            // Set locale to US English
-            ("s", "0"),                 // Start at first result
-            ("dc", limit_str.as_str()), // Number of results
-            ("o", "json"),              // Output format
+        let offset_str = params.start_offset().to_string();
+        // Regions pair a country with a language, US English unless asked otherwise
+        let region = format!(
+            "{}-{}",
+            params.country.as_deref().unwrap_or("us").to_lowercase(),
+            params
+                .language
+                .as_deref()
+                .and_then(|language| language.split('-').next())
+                .unwrap_or("en")
+                .to_lowercase()
+        );
+        let mut query_params = vec![
+            ("q", params.query.clone()),
+            ("kl", region),
+            ("s", offset_str), // Offset of the first result
+            ("dc", limit_str), // Number of results
+            ("o", "json".to_string()),
         ];
+        if let Some(df) = date_filter(&params) {
+            query_params.push(("df", df));
+        }
+        if let Some(safe_search) = params.safe_search {
+            let kp = match safe_search {
+                SafeSearch::Off => "-2",
+                SafeSearch::Moderate => "-1",
+                SafeSearch::Strict => "1",
+            };
+            query_params.push(("kp", kp.to_string()));
+        }
 
         // Make the request
         let response = self
             .client
             .get(format!(
                 "{}/search",
-                CONFIG
-                    .providers
-                    .duckduckgo
+                self.config
                     .base_url
                     .as_deref()
                     .unwrap_or("https://api.duckduckgo.com")
@@ -72,18 +131,12 @@ impl SearchProvider for DuckDuckGoSearchProvider {
             .query(&query_params)
             .send()
             .await
-            .map_err(|e| {
-                ProviderError::new(
-                    ErrorType::ApiError,
-                    format!("Failed to send request: {}", e),
-                    self.name().to_string(),
-                    Some(e.into()),
-                )
-            })?;
+            .map_err(|e| request_error(e, self.name()))?;
 
         // Check for errors
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = retry_after(response.headers());
             let error_message = match response.text().await {
                 Ok(text) => text,
                 Err(_) => status.to_string(),
@@ -98,18 +151,14 @@ impl SearchProvider for DuckDuckGoSearchProvider {
                 "DuckDuckGo API access forbidden",
                 "DuckDuckGo API internal error",
             );
-            return Err(error);
+            return Err(error.with_retry_after(retry_after));
         }
 
         // Parse the response
-        let data: DuckDuckGoSearchResponse = response.json().await.map_err(|e| {
-            ProviderError::new(
-                ErrorType::ApiError,
-                format!("Failed to parse response: {}", e),
-                self.name().to_string(),
-                Some(e.into()),
-            )
-        })?;
+        let data: DuckDuckGoSearchResponse = response
+            .json()
+            .await
+            .map_err(|e| request_error(e, self.name()))?;
 
         // Convert to SearchResult format
         let results = data
@@ -121,9 +170,25 @@ impl SearchProvider for DuckDuckGoSearchProvider {
                 snippet: result.snippet,
                 score: None,
                 source_provider: self.name().to_string(),
+                ..Default::default()
             })
             .collect();
 
         Ok(results)
     }
 }
+
+// DuckDuckGo's `df`: a letter for the preset ranges, or two dates
+fn date_filter(params: &BaseSearchParams) -> Option<String> {
+    match params.time_range.as_ref()? {
+        TimeRange::Custom {
+            start: Some(start),
+            end: Some(end),
+        } => Some(format!("{}..{}", start, end)),
+        TimeRange::Custom { .. } => None,
+        time_range => time_range
+            .preset_name()
+            .and_then(|name| name.get(..1))
+            .map(str::to_string),
+    }
+}
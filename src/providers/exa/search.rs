@@ -1,8 +1,11 @@
-use crate::common::http::{create_http_client, handle_http_error};
+use crate::common::dates::{days_ago, normalize_date};
+use crate::common::http::{create_http_client, handle_http_error, request_error, retry_after};
 use crate::common::types::{
-    BaseSearchParams, ErrorType, ProviderError, SearchProvider, SearchResult,
+    BaseSearchParams, CostClass, ErrorType, Freshness, NewsResult, Pagination,
+    ProviderCapabilities, ProviderError, ResultDetails, ResultType, SearchFilter, SearchProvider,
+    SearchResult, SearchVertical, TimeRange,
 };
-use crate::config::CONFIG;
+use crate::config::{ProviderConfig, CONFIG};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -18,10 +21,16 @@ struct ExaResult {
     url: String,
     text: String,
     score: f64,
+    id: Option<String>,
+    #[serde(rename = "publishedDate")]
+    published_date: Option<String>,
+    author: Option<String>,
+    image: Option<String>,
 }
 
 pub struct ExaSearchProvider {
     client: Client,
+    config: ProviderConfig,
 }
 
 impl Default for ExaSearchProvider {
@@ -32,8 +41,18 @@ impl Default for ExaSearchProvider {
 
 impl ExaSearchProvider {
     pub fn new() -> Self {
-        let client = create_http_client(CONFIG.providers.exa.timeout_seconds * 1000);
-        Self { client }
+        Self::with_config(CONFIG.providers.exa.clone())
+    }
+
+    /// Create the provider from explicit settings rather than the global config
+    pub fn with_config(config: ProviderConfig) -> Self {
+        let client = create_http_client(config.timeout_seconds * 1000);
+        Self::with_client(config, client)
+    }
+
+    /// Create the provider from explicit settings, sharing an existing HTTP client
+    pub fn with_client(config: ProviderConfig, client: Client) -> Self {
+        Self { client, config }
     }
 }
 
@@ -47,10 +66,28 @@ impl SearchProvider for ExaSearchProvider {
         "Search the web using Exa Search API. Provides high-quality search results with relevance scores. Requires Exa API key."
     }
 
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            filters: vec![
+                SearchFilter::Country,
+                SearchFilter::IncludeDomains,
+                SearchFilter::ExcludeDomains,
+            ],
+            max_limit: 100,
+            // Exa's search has no cursor or offset, only the first page
+            pagination: Pagination::None,
+            freshness: Freshness::DateRange,
+            languages: Vec::new(),
+            result_types: vec![ResultType::Web, ResultType::News, ResultType::Academic],
+            cost: CostClass::FreeTier,
+            requires_api_key: true,
+        }
+    }
+
     async fn search(&self, params: BaseSearchParams) -> Result<Vec<SearchResult>, ProviderError> {
-        let api_key = CONFIG.providers.exa.api_key.as_ref().ok_or_else(|| {
+        let api_key = self.config.api_key.as_ref().ok_or_else(|| {
             ProviderError::new(
-                ErrorType::ApiError,
+                ErrorType::Authentication,
                 "Missing Exa API key".to_string(),
                 self.name().to_string(),
                 None,
@@ -65,6 +102,40 @@ impl SearchProvider for ExaSearchProvider {
             serde_json::Value::Number(params.limit.unwrap_or(5).into()),
         );
 
+        // Exa narrows its index to one kind of page with `category`
+        let news = params.vertical == Some(SearchVertical::News);
+        let category = match params.vertical {
+            Some(SearchVertical::News) => Some("news"),
+            Some(SearchVertical::Academic) => Some("research paper"),
+            _ => None,
+        };
+        if let Some(category) = category {
+            request_body.insert(
+                "category".to_string(),
+                serde_json::Value::String(category.to_string()),
+            );
+        }
+
+        let (start, end) = match &params.time_range {
+            Some(TimeRange::Custom { start, end }) => (
+                start.as_ref().map(|date| format!("{}T00:00:00.000Z", date)),
+                end.as_ref().map(|date| format!("{}T23:59:59.999Z", date)),
+            ),
+            Some(time_range) => (time_range.days().and_then(days_ago), None),
+            None => (None, None),
+        };
+        for (key, date) in [("startPublishedDate", start), ("endPublishedDate", end)] {
+            if let Some(date) = date {
+                request_body.insert(key.to_string(), serde_json::Value::String(date));
+            }
+        }
+        if let Some(country) = &params.country {
+            request_body.insert(
+                "userLocation".to_string(),
+                serde_json::Value::String(country.to_uppercase()),
+            );
+        }
+
         if let Some(include_domains) = params.include_domains {
             request_body.insert(
                 "include_domains".to_string(),
@@ -94,9 +165,7 @@ impl SearchProvider for ExaSearchProvider {
             .client
             .post(format!(
                 "{}/search",
-                CONFIG
-                    .providers
-                    .exa
+                self.config
                     .base_url
                     .as_deref()
                     .unwrap_or("https://api.exa.ai")
@@ -106,18 +175,12 @@ impl SearchProvider for ExaSearchProvider {
             .json(&request_body)
             .send()
             .await
-            .map_err(|e| {
-                ProviderError::new(
-                    ErrorType::ApiError,
-                    format!("Failed to send request: {}", e),
-                    self.name().to_string(),
-                    Some(e.into()),
-                )
-            })?;
+            .map_err(|e| request_error(e, self.name()))?;
 
         // Check for errors
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = retry_after(response.headers());
             let error_message = match response.text().await {
                 Ok(text) => text,
                 Err(_) => status.to_string(),
@@ -132,29 +195,37 @@ impl SearchProvider for ExaSearchProvider {
                 "Exa API access forbidden",
                 "Exa API internal error",
             );
-            return Err(error);
+            return Err(error.with_retry_after(retry_after));
         }
 
         // Parse the response
-        let data: ExaSearchResponse = response.json().await.map_err(|e| {
-            ProviderError::new(
-                ErrorType::ApiError,
-                format!("Failed to parse response: {}", e),
-                self.name().to_string(),
-                Some(e.into()),
-            )
-        })?;
+        let data: ExaSearchResponse = response
+            .json()
+            .await
+            .map_err(|e| request_error(e, self.name()))?;
 
         // Convert to SearchResult format
         let results = data
             .results
             .into_iter()
-            .map(|result| SearchResult {
-                title: result.title,
-                url: result.url,
-                snippet: result.text,
-                score: Some(result.score),
-                source_provider: self.name().to_string(),
+            .map(|result| {
+                let mut extra = serde_json::Map::new();
+                if let Some(id) = result.id {
+                    extra.insert("id".to_string(), id.into());
+                }
+                SearchResult {
+                    title: result.title,
+                    url: result.url,
+                    snippet: result.text,
+                    score: Some(result.score),
+                    source_provider: self.name().to_string(),
+                    published_at: result.published_date.as_deref().and_then(normalize_date),
+                    author: result.author.filter(|author| !author.is_empty()),
+                    thumbnail_url: result.image,
+                    extra,
+                    details: news.then(|| ResultDetails::News(NewsResult::default())),
+                    ..Default::default()
+                }
             })
             .collect();
 
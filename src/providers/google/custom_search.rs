@@ -1,12 +1,20 @@
-use crate::common::http::{create_http_client, handle_http_error};
+use crate::common::dates::normalize_date;
+use crate::common::http::{create_http_client, handle_http_error, request_error, retry_after};
 use crate::common::types::{
-    BaseSearchParams, ErrorType, ProviderError, SearchProvider, SearchResult,
+    BaseSearchParams, CostClass, ErrorType, Freshness, ImageResult, Pagination,
+    ProviderCapabilities, ProviderError, ResultDetails, ResultType, SafeSearch, SearchFilter,
+    SearchProvider, SearchResult, SearchVertical, TimeRange,
 };
-use crate::config::CONFIG;
+use crate::config::{GoogleProviderConfig, CONFIG};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
+// Custom Search returns at most `MAX_NUM` results a request, and none past
+// the `LAST_RESULT`th
+const MAX_NUM: u32 = 10;
+const LAST_RESULT: u32 = 100;
+
 #[derive(Debug, Serialize, Deserialize)]
 struct GoogleCustomSearchResponse {
     items: Option<Vec<GoogleResult>>,
@@ -16,11 +24,56 @@ struct GoogleCustomSearchResponse {
 struct GoogleResult {
     title: String,
     link: String,
+    #[serde(default)]
     snippet: String,
+    #[serde(rename = "displayLink")]
+    display_link: Option<String>,
+    mime: Option<String>,
+    #[serde(default)]
+    pagemap: GooglePagemap,
+    // Only for image searches
+    image: Option<GoogleImage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleImage {
+    context_link: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    thumbnail_link: Option<String>,
+}
+
+// Structured data Google extracted from the page
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GooglePagemap {
+    #[serde(default)]
+    cse_thumbnail: Vec<GoogleThumbnail>,
+    #[serde(default)]
+    metatags: Vec<serde_json::Map<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GoogleThumbnail {
+    src: String,
+}
+
+impl GooglePagemap {
+    // The first of the named meta tags the page has
+    fn metatag(&self, names: &[&str]) -> Option<String> {
+        names.iter().find_map(|name| {
+            self.metatags
+                .iter()
+                .find_map(|tags| tags.get(*name)?.as_str())
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        })
+    }
 }
 
 pub struct GoogleCustomSearchProvider {
     client: Client,
+    config: GoogleProviderConfig,
 }
 
 impl Default for GoogleCustomSearchProvider {
@@ -31,8 +84,18 @@ impl Default for GoogleCustomSearchProvider {
 
 impl GoogleCustomSearchProvider {
     pub fn new() -> Self {
-        let client = create_http_client(CONFIG.providers.google.timeout_seconds * 1000);
-        Self { client }
+        Self::with_config(CONFIG.providers.google.clone())
+    }
+
+    /// Create the provider from explicit settings rather than the global config
+    pub fn with_config(config: GoogleProviderConfig) -> Self {
+        let client = create_http_client(config.timeout_seconds * 1000);
+        Self::with_client(config, client)
+    }
+
+    /// Create the provider from explicit settings, sharing an existing HTTP client
+    pub fn with_client(config: GoogleProviderConfig, client: Client) -> Self {
+        Self { client, config }
     }
 }
 
@@ -46,39 +109,121 @@ impl SearchProvider for GoogleCustomSearchProvider {
         "Search the web using Google Custom Search API. Provides reliable web search results with snippets. Requires a Google API key and Custom Search Engine ID."
     }
 
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            filters: vec![
+                SearchFilter::Country,
+                SearchFilter::Language,
+                SearchFilter::SafeSearch,
+                SearchFilter::IncludeDomains,
+            ],
+            max_limit: MAX_NUM,
+            pagination: Pagination::Offset,
+            freshness: Freshness::DateRange,
+            languages: Vec::new(),
+            result_types: vec![ResultType::Web, ResultType::Image],
+            cost: CostClass::FreeTier,
+            requires_api_key: true,
+        }
+    }
+
+    // Pages end at the last result Custom Search can return
+    fn next_page_token(
+        &self,
+        params: &BaseSearchParams,
+        results: &[SearchResult],
+    ) -> Option<String> {
+        params
+            .next_offset(results, MAX_NUM)
+            .filter(|&offset| offset < LAST_RESULT)
+            .map(|offset| offset.to_string())
+    }
+
     async fn search(&self, params: BaseSearchParams) -> Result<Vec<SearchResult>, ProviderError> {
-        let api_key = CONFIG.providers.google.api_key.as_ref().ok_or_else(|| {
+        let api_key = self.config.api_key.as_ref().ok_or_else(|| {
             ProviderError::new(
-                ErrorType::ApiError,
+                ErrorType::Authentication,
                 "Missing Google API key".to_string(),
                 self.name().to_string(),
                 None,
             )
         })?;
 
-        let search_engine_id = CONFIG
-            .providers
-            .google
-            .search_engine_id
-            .as_ref()
-            .ok_or_else(|| {
-                ProviderError::new(
-                    ErrorType::ApiError,
-                    "Missing Google Custom Search Engine ID".to_string(),
-                    self.name().to_string(),
-                    None,
-                )
-            })?;
+        let search_engine_id = self.config.search_engine_id.as_ref().ok_or_else(|| {
+            ProviderError::new(
+                ErrorType::Authentication,
+                "Missing Google Custom Search Engine ID".to_string(),
+                self.name().to_string(),
+                None,
+            )
+        })?;
+
+        let offset = params.start_offset();
+        if offset >= LAST_RESULT {
+            return Err(ProviderError::new(
+                ErrorType::InvalidInput,
+                format!(
+                    "Google Custom Search cannot return results past the first {}",
+                    LAST_RESULT
+                ),
+                self.name().to_string(),
+                None,
+            ));
+        }
+        let num = params
+            .limit
+            .unwrap_or(5)
+            .min(MAX_NUM)
+            .min(LAST_RESULT - offset);
 
         // Prepare query parameters
-        let limit_str = params.limit.unwrap_or(5).to_string();
         let mut query_params = vec![
             ("key", api_key.clone()),
             ("cx", search_engine_id.clone()),
             ("q", params.query.clone()),
-            ("num", limit_str),
+            ("num", num.to_string()),
         ];
 
+        if params.vertical == Some(SearchVertical::Images) {
+            query_params.push(("searchType", "image".to_string()));
+        }
+
+        // `start` is the 1-based index of the first result
+        if offset > 0 {
+            query_params.push(("start", (offset + 1).to_string()));
+        }
+
+        match &params.time_range {
+            Some(TimeRange::Custom { start, end }) => {
+                // Restricts to, but does not sort by, the dates
+                let day = |date: &Option<String>, open: &str| {
+                    date.as_deref().unwrap_or(open).replace('-', "")
+                };
+                query_params.push((
+                    "sort",
+                    format!("date:r:{}:{}", day(start, "19700101"), day(end, "99991231")),
+                ));
+            }
+            Some(time_range) => {
+                let days = time_range.days().unwrap_or(1);
+                query_params.push(("dateRestrict", format!("d{}", days)));
+            }
+            None => {}
+        }
+        if let Some(country) = &params.country {
+            query_params.push(("gl", country.to_lowercase()));
+        }
+        if let Some(language) = &params.language {
+            query_params.push(("lr", format!("lang_{}", language)));
+        }
+        if let Some(safe_search) = params.safe_search {
+            let safe = match safe_search {
+                SafeSearch::Off => "off",
+                SafeSearch::Moderate | SafeSearch::Strict => "active",
+            };
+            query_params.push(("safe", safe.to_string()));
+        }
+
         if let Some(include_domains) = &params.include_domains {
             if !include_domains.is_empty() {
                 let site_filter = include_domains
@@ -102,18 +247,12 @@ impl SearchProvider for GoogleCustomSearchProvider {
             .query(&query_params)
             .send()
             .await
-            .map_err(|e| {
-                ProviderError::new(
-                    ErrorType::ApiError,
-                    format!("Failed to send request: {}", e),
-                    self.name().to_string(),
-                    Some(e.into()),
-                )
-            })?;
+            .map_err(|e| request_error(e, self.name()))?;
 
         // Check for errors
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = retry_after(response.headers());
             let error_message = match response.text().await {
                 Ok(text) => text,
                 Err(_) => status.to_string(),
@@ -128,30 +267,63 @@ impl SearchProvider for GoogleCustomSearchProvider {
                 "API key does not have access to this endpoint",
                 "Google Custom Search API internal error",
             );
-            return Err(error);
+            return Err(error.with_retry_after(retry_after));
         }
 
         // Parse the response
-        let data: GoogleCustomSearchResponse = response.json().await.map_err(|e| {
-            ProviderError::new(
-                ErrorType::ApiError,
-                format!("Failed to parse response: {}", e),
-                self.name().to_string(),
-                Some(e.into()),
-            )
-        })?;
+        let data: GoogleCustomSearchResponse = response
+            .json()
+            .await
+            .map_err(|e| request_error(e, self.name()))?;
 
         // Convert to SearchResult format
         let results = data
             .items
             .unwrap_or_default()
             .into_iter()
-            .map(|result| SearchResult {
-                title: result.title,
-                url: result.link,
-                snippet: result.snippet,
-                score: None,
-                source_provider: self.name().to_string(),
+            .map(|result| {
+                let pagemap = &result.pagemap;
+                let mut extra = serde_json::Map::new();
+                if let Some(mime) = result.mime {
+                    extra.insert("mime".to_string(), mime.into());
+                }
+                SearchResult {
+                    published_at: pagemap
+                        .metatag(&["article:published_time", "og:article:published_time"])
+                        .as_deref()
+                        .and_then(normalize_date),
+                    author: pagemap.metatag(&["author", "article:author"]),
+                    // Open Graph locales are written `en_US`
+                    language: pagemap
+                        .metatag(&["og:locale"])
+                        .map(|locale| locale.replace('_', "-")),
+                    thumbnail_url: result
+                        .image
+                        .as_ref()
+                        .and_then(|image| image.thumbnail_link.clone())
+                        .or_else(|| {
+                            pagemap
+                                .cse_thumbnail
+                                .first()
+                                .map(|thumbnail| thumbnail.src.clone())
+                        })
+                        .or_else(|| pagemap.metatag(&["og:image"])),
+                    site_name: pagemap.metatag(&["og:site_name"]).or(result.display_link),
+                    title: result.title,
+                    url: result.link,
+                    snippet: result.snippet,
+                    score: None,
+                    source_provider: self.name().to_string(),
+                    extra,
+                    details: result.image.map(|image| {
+                        ResultDetails::Image(ImageResult {
+                            width: image.width,
+                            height: image.height,
+                            page_url: image.context_link,
+                        })
+                    }),
+                    ..Default::default()
+                }
             })
             .collect();
 
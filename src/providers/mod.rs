@@ -2,11 +2,15 @@ use crate::{common::provider_factory::ProviderFactory, config::CONFIG};
 
 #[cfg(feature = "server")]
 use crate::server::register_search_provider;
+#[cfg(feature = "server")]
+use tracing::info;
 
 use crate::common::types::SearchProvider;
 
 // Import search providers
 pub mod baidu;
+#[cfg(feature = "brave")]
+pub mod brave;
 pub mod brightdata;
 pub mod duckduckgo;
 pub mod exa;
@@ -70,14 +74,14 @@ pub fn initialize_providers() {
     }
 
     // Log available providers
-    println!("Available providers:");
+    info!("Available providers:");
 
     let search_providers = &*crate::server::tools::AVAILABLE_PROVIDERS
         .search
         .read()
         .unwrap();
     if !search_providers.is_empty() {
-        println!(
+        info!(
             "- Search: {}",
             search_providers
                 .iter()
@@ -86,7 +90,7 @@ pub fn initialize_providers() {
                 .join(", ")
         );
     } else {
-        println!("- Search: None available (missing API keys)");
+        info!("- Search: None available (missing API keys)");
     }
 
     let ai_response_providers = &*crate::server::tools::AVAILABLE_PROVIDERS
@@ -94,7 +98,7 @@ pub fn initialize_providers() {
         .read()
         .unwrap();
     if !ai_response_providers.is_empty() {
-        println!(
+        info!(
             "- AI Response: {}",
             ai_response_providers
                 .iter()
@@ -103,7 +107,7 @@ pub fn initialize_providers() {
                 .join(", ")
         );
     } else {
-        println!("- AI Response: None available (missing API keys)");
+        info!("- AI Response: None available (missing API keys)");
     }
 
     let processing_providers = &*crate::server::tools::AVAILABLE_PROVIDERS
@@ -111,7 +115,7 @@ pub fn initialize_providers() {
         .read()
         .unwrap();
     if !processing_providers.is_empty() {
-        println!(
+        info!(
             "- Processing: {}",
             processing_providers
                 .iter()
@@ -120,7 +124,7 @@ pub fn initialize_providers() {
                 .join(", ")
         );
     } else {
-        println!("- Processing: None available (missing API keys)");
+        info!("- Processing: None available (missing API keys)");
     }
 
     let enhancement_providers = &*crate::server::tools::AVAILABLE_PROVIDERS
@@ -128,7 +132,7 @@ pub fn initialize_providers() {
         .read()
         .unwrap();
     if !enhancement_providers.is_empty() {
-        println!(
+        info!(
             "- Enhancement: {}",
             enhancement_providers
                 .iter()
@@ -137,6 +141,6 @@ pub fn initialize_providers() {
                 .join(", ")
         );
     } else {
-        println!("- Enhancement: None available (missing API keys)");
+        info!("- Enhancement: None available (missing API keys)");
     }
 }
@@ -1,7 +1,10 @@
+use crate::common::dates::from_unix_timestamp;
+use crate::common::http::{handle_http_error, request_error, retry_after};
 use crate::common::types::{
-    BaseSearchParams, ErrorType, ProviderError, SearchProvider, SearchResult,
+    BaseSearchParams, CostClass, ErrorType, Freshness, Pagination, ProviderCapabilities,
+    ProviderError, ResultType, SafeSearch, SearchFilter, SearchProvider, SearchResult,
 };
-use crate::config::CONFIG;
+use crate::config::{RedditProviderConfig, CONFIG};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -29,10 +32,17 @@ struct RedditPost {
     selftext: String,
     permalink: String,
     score: Option<i64>,
+    subreddit: Option<String>,
+    author: Option<String>,
+    num_comments: Option<u64>,
+    created_utc: Option<f64>,
+    // "self", "default" or "nsfw" when the post has no thumbnail image
+    thumbnail: Option<String>,
 }
 
 pub struct RedditSearchProvider {
     client: Client,
+    config: RedditProviderConfig,
 }
 
 impl Default for RedditSearchProvider {
@@ -43,14 +53,21 @@ impl Default for RedditSearchProvider {
 
 impl RedditSearchProvider {
     pub fn new() -> Self {
+        Self::with_config(CONFIG.providers.reddit.clone())
+    }
+
+    /// Create the provider from explicit settings rather than the global config
+    pub fn with_config(config: RedditProviderConfig) -> Self {
         let client = Client::builder()
-            .timeout(Duration::from_millis(
-                CONFIG.providers.reddit.timeout_seconds * 1000,
-            ))
+            .timeout(Duration::from_millis(config.timeout_seconds * 1000))
             .build()
             .expect("Failed to create HTTP client");
+        Self::with_client(config, client)
+    }
 
-        Self { client }
+    /// Create the provider from explicit settings, sharing an existing HTTP client
+    pub fn with_client(config: RedditProviderConfig, client: Client) -> Self {
+        Self { client, config }
     }
 }
 
@@ -60,41 +77,61 @@ impl SearchProvider for RedditSearchProvider {
         "reddit"
     }
 
+    fn next_page_token(
+        &self,
+        params: &BaseSearchParams,
+        results: &[SearchResult],
+    ) -> Option<String> {
+        if results.len() < params.limit.unwrap_or(5) as usize {
+            return None;
+        }
+        results.last().and_then(|result| post_fullname(&result.url))
+    }
+
     fn description(&self) -> &'static str {
         "Search Reddit posts using OAuth2 authentication. Provides access to discussions and content from Reddit communities. Requires Reddit API credentials (client ID, client secret, user agent)."
     }
 
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            filters: vec![SearchFilter::SafeSearch],
+            max_limit: 100,
+            pagination: Pagination::Cursor,
+            // Reddit only has preset time ranges
+            freshness: Freshness::Presets,
+            languages: Vec::new(),
+            result_types: vec![ResultType::Discussion],
+            cost: CostClass::Free,
+            requires_api_key: true,
+        }
+    }
+
     async fn search(&self, params: BaseSearchParams) -> Result<Vec<SearchResult>, ProviderError> {
         // Note: For simplicity, we're not implementing full OAuth2 flow here
         // In a real implementation, you would need to properly authenticate with Reddit's API
         // using the client credentials flow
 
-        let client_id = CONFIG.providers.reddit.client_id.as_ref().ok_or_else(|| {
+        let client_id = self.config.client_id.as_ref().ok_or_else(|| {
             ProviderError::new(
-                ErrorType::ApiError,
+                ErrorType::Authentication,
                 "Missing Reddit client ID".to_string(),
                 self.name().to_string(),
                 None,
             )
         })?;
 
-        let client_secret = CONFIG
-            .providers
-            .reddit
-            .client_secret
-            .as_ref()
-            .ok_or_else(|| {
-                ProviderError::new(
-                    ErrorType::ApiError,
-                    "Missing Reddit client secret".to_string(),
-                    self.name().to_string(),
-                    None,
-                )
-            })?;
-
-        let user_agent = CONFIG.providers.reddit.user_agent.as_ref().ok_or_else(|| {
+        let client_secret = self.config.client_secret.as_ref().ok_or_else(|| {
             ProviderError::new(
-                ErrorType::ApiError,
+                ErrorType::Authentication,
+                "Missing Reddit client secret".to_string(),
+                self.name().to_string(),
+                None,
+            )
+        })?;
+
+        let user_agent = self.config.user_agent.as_ref().ok_or_else(|| {
+            ProviderError::new(
+                ErrorType::Authentication,
                 "Missing Reddit user agent".to_string(),
                 self.name().to_string(),
                 None,
@@ -102,13 +139,36 @@ impl SearchProvider for RedditSearchProvider {
         })?;
 
         // Prepare query parameters
-        let limit_str = params.limit.unwrap_or(5).to_string();
-        let query_params = vec![
+        // Reddit pages with `after`, the fullname of the last post already
+        // seen. A bare offset is served by fetching the posts before it too.
+        let skip = match params.page_token {
+            Some(_) => 0,
+            None => params.offset.unwrap_or(0),
+        };
+        let limit_str = (skip + params.limit.unwrap_or(5)).to_string();
+        let mut query_params = vec![
             ("q", params.query.as_str()),
             ("limit", limit_str.as_str()),
             ("sort", "relevance"),
             ("type", "link"),
         ];
+        if let Some(after) = params.page_token.as_deref() {
+            query_params.push(("after", after));
+        }
+        if let Some(t) = params
+            .time_range
+            .as_ref()
+            .and_then(|time_range| time_range.preset_name())
+        {
+            query_params.push(("t", t));
+        }
+        if let Some(safe_search) = params.safe_search {
+            let include_over_18 = match safe_search {
+                SafeSearch::Off => "on",
+                SafeSearch::Moderate | SafeSearch::Strict => "off",
+            };
+            query_params.push(("include_over_18", include_over_18));
+        }
 
         // Make the request
         let response = self
@@ -119,87 +179,58 @@ impl SearchProvider for RedditSearchProvider {
             .query(&query_params)
             .send()
             .await
-            .map_err(|e| {
-                ProviderError::new(
-                    ErrorType::ApiError,
-                    format!("Failed to send request: {}", e),
-                    self.name().to_string(),
-                    Some(e.into()),
-                )
-            })?;
+            .map_err(|e| request_error(e, self.name()))?;
 
         // Check for errors
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = retry_after(response.headers());
             let error_message = match response.text().await {
                 Ok(text) => text,
                 Err(_) => status.to_string(),
             };
 
-            match status.as_u16() {
-                401 => {
-                    return Err(ProviderError::new(
-                        ErrorType::ApiError,
-                        "Invalid Reddit API credentials".to_string(),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-                403 => {
-                    return Err(ProviderError::new(
-                        ErrorType::ApiError,
-                        "Reddit API access forbidden".to_string(),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-                429 => {
-                    return Err(ProviderError::new(
-                        ErrorType::RateLimit,
-                        "Reddit API rate limit exceeded".to_string(),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-                500 => {
-                    return Err(ProviderError::new(
-                        ErrorType::ProviderError,
-                        "Reddit API internal error".to_string(),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-                _ => {
-                    return Err(ProviderError::new(
-                        ErrorType::ApiError,
-                        format!("Unexpected error: {}", error_message),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-            }
+            let error = handle_http_error(
+                status,
+                error_message,
+                self.name(),
+                "Reddit API rate limit exceeded",
+                "Invalid Reddit API credentials",
+                "Reddit API access forbidden",
+                "Reddit API internal error",
+            );
+            return Err(error.with_retry_after(retry_after));
         }
 
         // Parse the response
-        let data: RedditSearchResponse = response.json().await.map_err(|e| {
-            ProviderError::new(
-                ErrorType::ApiError,
-                format!("Failed to parse response: {}", e),
-                self.name().to_string(),
-                Some(e.into()),
-            )
-        })?;
+        let data: RedditSearchResponse = response
+            .json()
+            .await
+            .map_err(|e| request_error(e, self.name()))?;
 
         // Convert to SearchResult format
         let results = data
             .data
             .children
             .into_iter()
+            .skip(skip as usize)
             .map(|post_wrapper| {
                 let post = post_wrapper.data;
+                let url = format!("https://reddit.com{}", post.permalink);
+                let mut extra = serde_json::Map::new();
+                if let Some(subreddit) = &post.subreddit {
+                    extra.insert("subreddit".to_string(), subreddit.clone().into());
+                }
+                if let Some(num_comments) = post.num_comments {
+                    extra.insert("num_comments".to_string(), num_comments.into());
+                }
+                // Link posts point away from Reddit; self posts link to themselves
+                if !post.url.is_empty() && !post.url.ends_with(&post.permalink) {
+                    extra.insert("linked_url".to_string(), post.url.into());
+                }
                 SearchResult {
                     title: post.title,
-                    url: format!("https://reddit.com{}", post.permalink),
+                    url,
                     snippet: if post.selftext.is_empty() {
                         "No text content available".to_string()
                     } else {
@@ -207,6 +238,12 @@ impl SearchProvider for RedditSearchProvider {
                     },
                     score: post.score.map(|s| s as f64),
                     source_provider: self.name().to_string(),
+                    published_at: post.created_utc.and_then(from_unix_timestamp),
+                    author: post.author.filter(|author| author != "[deleted]"),
+                    thumbnail_url: post.thumbnail.filter(|url| url.starts_with("http")),
+                    site_name: post.subreddit.map(|subreddit| format!("r/{}", subreddit)),
+                    extra,
+                    ..Default::default()
                 }
             })
             .collect();
@@ -214,3 +251,13 @@ impl SearchProvider for RedditSearchProvider {
         Ok(results)
     }
 }
+
+// The fullname (`t3_<id>`) of the post at a `/r/<subreddit>/comments/<id>/...` URL
+fn post_fullname(url: &str) -> Option<String> {
+    let mut segments = url.split('/');
+    segments.find(|segment| *segment == "comments")?;
+    segments
+        .next()
+        .filter(|id| !id.is_empty())
+        .map(|id| format!("t3_{}", id))
+}
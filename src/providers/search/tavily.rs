@@ -1,12 +1,19 @@
+use crate::common::dates::normalize_date;
+use crate::common::http::{handle_http_error, request_error, retry_after};
 use crate::common::types::{
-    BaseSearchParams, ErrorType, ProviderError, SearchProvider, SearchResult,
+    BaseSearchParams, CostClass, ErrorType, Freshness, NewsResult, Pagination,
+    ProviderCapabilities, ProviderError, ResultDetails, ResultType, SearchFilter, SearchProvider,
+    SearchResult, SearchVertical, TimeRange,
 };
-use crate::config::CONFIG;
+use crate::config::{ProviderConfig, CONFIG};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
+// The most results one Tavily search returns
+const MAX_RESULTS: u32 = 20;
+
 #[derive(Debug, Serialize, Deserialize)]
 struct TavilySearchResponse {
     results: Vec<TavilyResult>,
@@ -19,10 +26,15 @@ struct TavilyResult {
     url: String,
     content: String,
     score: f64,
+    // Only for the news topic
+    published_date: Option<String>,
+    // Only when asked for with `include_raw_content`
+    raw_content: Option<String>,
 }
 
 pub struct TavilySearchProvider {
     client: Client,
+    config: ProviderConfig,
 }
 
 impl Default for TavilySearchProvider {
@@ -33,14 +45,21 @@ impl Default for TavilySearchProvider {
 
 impl TavilySearchProvider {
     pub fn new() -> Self {
+        Self::with_config(CONFIG.providers.tavily.clone())
+    }
+
+    /// Create the provider from explicit settings rather than the global config
+    pub fn with_config(config: ProviderConfig) -> Self {
         let client = Client::builder()
-            .timeout(Duration::from_millis(
-                CONFIG.providers.tavily.timeout_seconds * 1000,
-            ))
+            .timeout(Duration::from_millis(config.timeout_seconds * 1000))
             .build()
             .expect("Failed to create HTTP client");
+        Self::with_client(config, client)
+    }
 
-        Self { client }
+    /// Create the provider from explicit settings, sharing an existing HTTP client
+    pub fn with_client(config: ProviderConfig, client: Client) -> Self {
+        Self { client, config }
     }
 }
 
@@ -54,32 +73,89 @@ impl SearchProvider for TavilySearchProvider {
         "Search the web using Tavily Search API. Best for factual queries requiring reliable sources and citations. Supports domain filtering through API parameters (include_domains/exclude_domains). Provides high-quality results for technical, scientific, and academic topics. Use when you need verified information with strong citation support."
     }
 
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            filters: vec![SearchFilter::IncludeDomains, SearchFilter::ExcludeDomains],
+            max_limit: MAX_RESULTS,
+            pagination: Pagination::Offset,
+            freshness: Freshness::DateRange,
+            languages: Vec::new(),
+            result_types: vec![ResultType::Web, ResultType::News],
+            cost: CostClass::FreeTier,
+            requires_api_key: true,
+        }
+    }
+
+    // Pages end at the last result a search can return
+    fn next_page_token(
+        &self,
+        params: &BaseSearchParams,
+        results: &[SearchResult],
+    ) -> Option<String> {
+        params
+            .next_offset(results, MAX_RESULTS)
+            .filter(|&offset| offset < MAX_RESULTS)
+            .map(|offset| offset.to_string())
+    }
+
     async fn search(&self, params: BaseSearchParams) -> Result<Vec<SearchResult>, ProviderError> {
-        let api_key = CONFIG.providers.tavily.api_key.as_ref().ok_or_else(|| {
+        let api_key = self.config.api_key.as_ref().ok_or_else(|| {
             ProviderError::new(
-                ErrorType::ApiError,
+                ErrorType::Authentication,
                 "Missing API key".to_string(),
                 self.name().to_string(),
                 None,
             )
         })?;
 
+        // Tavily has no paging, so later pages are fetched along with the ones
+        // before them and cut out of the response. Nothing past its first
+        // `MAX_RESULTS` results can be reached this way
+        let offset = params.start_offset();
+        if offset >= MAX_RESULTS {
+            return Ok(Vec::new());
+        }
+        let max_results = (offset + params.limit.unwrap_or(5)).min(MAX_RESULTS);
+
         // Prepare request body
         let mut request_body = serde_json::Map::new();
         request_body.insert("query".to_string(), serde_json::Value::String(params.query));
         request_body.insert(
             "max_results".to_string(),
-            serde_json::Value::Number(params.limit.unwrap_or(5).into()),
+            serde_json::Value::Number(max_results.into()),
         );
         request_body.insert(
             "search_depth".to_string(),
             serde_json::Value::String("basic".to_string()),
         );
+        let news = params.vertical == Some(SearchVertical::News);
+        let topic = if news { "news" } else { "general" };
         request_body.insert(
             "topic".to_string(),
-            serde_json::Value::String("general".to_string()),
+            serde_json::Value::String(topic.to_string()),
         );
 
+        // `time_range` and the dates apply to both topics
+        match &params.time_range {
+            Some(TimeRange::Custom { start, end }) => {
+                for (key, date) in [("start_date", start), ("end_date", end)] {
+                    if let Some(date) = date {
+                        request_body
+                            .insert(key.to_string(), serde_json::Value::String(date.clone()));
+                    }
+                }
+            }
+            Some(time_range) => {
+                if let Some(name) = time_range.preset_name() {
+                    request_body.insert(
+                        "time_range".to_string(),
+                        serde_json::Value::String(name.to_string()),
+                    );
+                }
+            }
+            None => {}
+        }
+
         if let Some(include_domains) = params.include_domains {
             request_body.insert(
                 "include_domains".to_string(),
@@ -109,9 +185,7 @@ impl SearchProvider for TavilySearchProvider {
             .client
             .post(format!(
                 "{}/search",
-                CONFIG
-                    .providers
-                    .tavily
+                self.config
                     .base_url
                     .as_ref()
                     .unwrap_or(&"https://api.tavily.com".to_string())
@@ -121,87 +195,57 @@ impl SearchProvider for TavilySearchProvider {
             .json(&request_body)
             .send()
             .await
-            .map_err(|e| {
-                ProviderError::new(
-                    ErrorType::ApiError,
-                    format!("Failed to send request: {}", e),
-                    self.name().to_string(),
-                    Some(e.into()),
-                )
-            })?;
+            .map_err(|e| request_error(e, self.name()))?;
 
         // Check for errors
         if !response.status().is_success() {
             let status = response.status();
+            let retry_after = retry_after(response.headers());
             let error_message = match response.text().await {
                 Ok(text) => text,
                 Err(_) => status.to_string(),
             };
 
-            match status.as_u16() {
-                401 => {
-                    return Err(ProviderError::new(
-                        ErrorType::ApiError,
-                        "Invalid API key".to_string(),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-                403 => {
-                    return Err(ProviderError::new(
-                        ErrorType::ApiError,
-                        "API key does not have access to this endpoint".to_string(),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-                429 => {
-                    return Err(ProviderError::new(
-                        ErrorType::RateLimit,
-                        "Rate limit exceeded".to_string(),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-                500 => {
-                    return Err(ProviderError::new(
-                        ErrorType::ProviderError,
-                        "Tavily API internal error".to_string(),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-                _ => {
-                    return Err(ProviderError::new(
-                        ErrorType::ApiError,
-                        format!("Unexpected error: {}", error_message),
-                        self.name().to_string(),
-                        None,
-                    ))
-                }
-            }
+            let error = handle_http_error(
+                status,
+                error_message,
+                self.name(),
+                "Rate limit exceeded",
+                "Invalid API key",
+                "API key does not have access to this endpoint",
+                "Tavily API internal error",
+            );
+            return Err(error.with_retry_after(retry_after));
         }
 
         // Parse the response
-        let data: TavilySearchResponse = response.json().await.map_err(|e| {
-            ProviderError::new(
-                ErrorType::ApiError,
-                format!("Failed to parse response: {}", e),
-                self.name().to_string(),
-                Some(e.into()),
-            )
-        })?;
+        let data: TavilySearchResponse = response
+            .json()
+            .await
+            .map_err(|e| request_error(e, self.name()))?;
 
         // Convert to SearchResult format
         let results = data
             .results
             .into_iter()
-            .map(|result| SearchResult {
-                title: result.title,
-                url: result.url,
-                snippet: result.content,
-                score: Some(result.score),
-                source_provider: self.name().to_string(),
+            .skip(offset as usize)
+            .map(|result| {
+                let mut extra = serde_json::Map::new();
+                if let Some(raw_content) = result.raw_content {
+                    extra.insert("raw_content".to_string(), raw_content.into());
+                }
+                SearchResult {
+                    title: result.title,
+                    url: result.url,
+                    snippet: result.content,
+                    score: Some(result.score),
+                    source_provider: self.name().to_string(),
+                    published_at: result.published_date.as_deref().and_then(normalize_date),
+                    extra,
+                    // Tavily does not name the publisher
+                    details: news.then(|| ResultDetails::News(NewsResult::default())),
+                    ..Default::default()
+                }
             })
             .collect();
 
@@ -0,0 +1,480 @@
+use crate::config::{AuthConfig, JwtConfig};
+use eyre::{eyre, Result};
+use jsonwebtoken::{
+    decode, decode_header,
+    jwk::{Jwk, JwkSet},
+    Algorithm, DecodingKey, Validation,
+};
+use once_cell::sync::Lazy;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tracing::{debug, info, warn};
+
+/// The authenticated caller behind an HTTP request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientIdentity {
+    pub client_id: String,
+    pub method: AuthMethod,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthMethod {
+    StaticToken,
+    Jwt,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum AuthError {
+    #[error("Missing bearer token")]
+    MissingToken,
+    #[error("Invalid bearer token: {0}")]
+    InvalidToken(String),
+    #[error("Session belongs to another client")]
+    SessionMismatch,
+}
+
+/// Validates bearer tokens for the HTTP transport.
+///
+/// Static tokens are checked first; anything else is treated as a JWT when a
+/// JWKS has been configured.
+pub struct Authenticator {
+    static_tokens: HashMap<String, String>,
+    jwt: Option<JwtValidator>,
+}
+
+impl Authenticator {
+    /// Create an authenticator from `token -> client_id` pairs and an optional JWT validator
+    pub fn new(static_tokens: HashMap<String, String>, jwt: Option<JwtValidator>) -> Self {
+        Self { static_tokens, jwt }
+    }
+
+    /// Build the authenticator described by `[auth]`, or `None` when auth is disabled
+    pub async fn from_config(config: &AuthConfig) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let static_tokens: HashMap<String, String> = config
+            .tokens
+            .iter()
+            .map(|t| (t.token.clone(), t.client_id.clone()))
+            .collect();
+
+        let jwt = match &config.jwt {
+            Some(jwt_config) => Some(JwtValidator::from_config(jwt_config).await?),
+            None => None,
+        };
+
+        if static_tokens.is_empty() && jwt.is_none() {
+            return Err(eyre!(
+                "Authentication is enabled but no tokens or JWKS are configured"
+            ));
+        }
+
+        info!(
+            "HTTP authentication enabled ({} static tokens, JWT {})",
+            static_tokens.len(),
+            if jwt.is_some() { "enabled" } else { "disabled" }
+        );
+
+        Ok(Some(Self::new(static_tokens, jwt)))
+    }
+
+    /// Authenticate the value of an `Authorization` header
+    pub async fn authenticate_header(
+        &self,
+        header: Option<&str>,
+    ) -> Result<ClientIdentity, AuthError> {
+        let token = header
+            .and_then(|h| {
+                h.strip_prefix("Bearer ")
+                    .or_else(|| h.strip_prefix("bearer "))
+            })
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .ok_or(AuthError::MissingToken)?;
+
+        self.authenticate(token).await
+    }
+
+    /// Authenticate a raw bearer token
+    pub async fn authenticate(&self, token: &str) -> Result<ClientIdentity, AuthError> {
+        if let Some(client_id) = self.static_tokens.get(token) {
+            return Ok(ClientIdentity {
+                client_id: client_id.clone(),
+                method: AuthMethod::StaticToken,
+            });
+        }
+
+        match &self.jwt {
+            Some(jwt) => jwt.validate(token).await,
+            None => Err(AuthError::InvalidToken("unknown token".to_string())),
+        }
+    }
+}
+
+/// The shortest time between JWKS reloads prompted by an unknown `kid`, so
+/// tokens naming made-up keys cannot hammer the JWKS endpoint
+const MIN_JWKS_RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Where a JWKS is loaded from
+#[derive(Debug, Clone)]
+enum JwksSource {
+    File(String),
+    Url(String),
+}
+
+impl JwksSource {
+    async fn load(&self) -> Result<JwkSet> {
+        match self {
+            JwksSource::File(path) => {
+                let contents = tokio::fs::read_to_string(path)
+                    .await
+                    .map_err(|e| eyre!("Failed to read JWKS file {}: {}", path, e))?;
+                serde_json::from_str(&contents)
+                    .map_err(|e| eyre!("Invalid JWKS file {}: {}", path, e))
+            }
+            JwksSource::Url(url) => reqwest::get(url)
+                .await
+                .map_err(|e| eyre!("Failed to fetch JWKS from {}: {}", url, e))?
+                .error_for_status()
+                .map_err(|e| eyre!("Failed to fetch JWKS from {}: {}", url, e))?
+                .json()
+                .await
+                .map_err(|e| eyre!("Invalid JWKS from {}: {}", url, e)),
+        }
+    }
+}
+
+struct LoadedKeys {
+    keys: JwkSet,
+    loaded_at: Instant,
+    // When a token last named a key we did not have
+    missing_key_reload_at: Option<Instant>,
+}
+
+/// Validates JWTs against the keys in a JWKS.
+///
+/// A JWKS loaded from a file or URL is reloaded every `jwks_refresh_seconds`,
+/// and sooner when a token names a key it does not have, so rotated keys are
+/// picked up without a restart.
+pub struct JwtValidator {
+    keys: RwLock<LoadedKeys>,
+    source: Option<JwksSource>,
+    refresh_interval: Duration,
+    reloading: tokio::sync::Mutex<()>,
+    algorithms: Vec<Algorithm>,
+    issuer: Option<String>,
+    audience: Option<String>,
+    client_id_claim: String,
+}
+
+impl JwtValidator {
+    /// Validate against a fixed set of keys, each used with the algorithm its `alg` names
+    pub fn new(
+        keys: JwkSet,
+        issuer: Option<String>,
+        audience: Option<String>,
+        client_id_claim: String,
+    ) -> Self {
+        Self {
+            keys: RwLock::new(LoadedKeys {
+                keys,
+                loaded_at: Instant::now(),
+                missing_key_reload_at: None,
+            }),
+            source: None,
+            refresh_interval: Duration::MAX,
+            reloading: tokio::sync::Mutex::new(()),
+            algorithms: Vec::new(),
+            issuer,
+            audience,
+            client_id_claim,
+        }
+    }
+
+    /// Load the JWKS from the configured file or URL
+    pub async fn from_config(config: &JwtConfig) -> Result<Self> {
+        let source = match (&config.jwks_path, &config.jwks_url) {
+            (Some(path), _) => JwksSource::File(path.clone()),
+            (None, Some(url)) => JwksSource::Url(url.clone()),
+            (None, None) => return Err(eyre!("JWT auth requires jwks_path or jwks_url")),
+        };
+        let algorithms = config
+            .algorithms
+            .iter()
+            .map(|name| {
+                Algorithm::from_str(name).map_err(|_| eyre!("Unknown JWT algorithm '{}'", name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let keys = source.load().await?;
+        debug!("Loaded {} JWKS keys", keys.keys.len());
+
+        let mut validator = Self::new(
+            keys,
+            config.issuer.clone(),
+            config.audience.clone(),
+            config.client_id_claim.clone(),
+        );
+        validator.source = Some(source);
+        validator.refresh_interval = Duration::from_secs(config.jwks_refresh_seconds);
+        validator.algorithms = algorithms;
+        Ok(validator)
+    }
+
+    pub async fn validate(&self, token: &str) -> Result<ClientIdentity, AuthError> {
+        let invalid = |e: &dyn std::fmt::Display| AuthError::InvalidToken(e.to_string());
+
+        let header = decode_header(token).map_err(|e| invalid(&e))?;
+
+        self.reload(false).await;
+        let jwk = match self.find_key(header.kid.as_deref()) {
+            Some(jwk) => Some(jwk),
+            None if header.kid.is_some() => {
+                self.reload(true).await;
+                self.find_key(header.kid.as_deref())
+            }
+            None => None,
+        }
+        .ok_or_else(|| AuthError::InvalidToken("no matching key".to_string()))?;
+
+        // The algorithms come from the key and configuration, never the token
+        let algorithms = self.key_algorithms(&jwk)?;
+        if !algorithms.contains(&header.alg) {
+            return Err(AuthError::InvalidToken("algorithm not allowed".to_string()));
+        }
+
+        let key = DecodingKey::from_jwk(&jwk).map_err(|e| invalid(&e))?;
+
+        let mut validation = Validation::new(algorithms[0]);
+        validation.algorithms = algorithms;
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        match &self.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+
+        let claims = decode::<Map<String, Value>>(token, &key, &validation)
+            .map_err(|e| invalid(&e))?
+            .claims;
+
+        let client_id = claims
+            .get(&self.client_id_claim)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                AuthError::InvalidToken(format!("missing '{}' claim", self.client_id_claim))
+            })?;
+
+        Ok(ClientIdentity {
+            client_id: client_id.to_string(),
+            method: AuthMethod::Jwt,
+        })
+    }
+
+    /// The algorithms tokens signed with `jwk` may use: the key's own `alg`,
+    /// provided it is configured, or else the configured algorithms
+    fn key_algorithms(&self, jwk: &Jwk) -> Result<Vec<Algorithm>, AuthError> {
+        let algorithms = match jwk.common.key_algorithm {
+            Some(key_algorithm) => {
+                let algorithm = Algorithm::from_str(&key_algorithm.to_string())
+                    .map_err(|_| AuthError::InvalidToken("key is not a signing key".to_string()))?;
+                if !self.algorithms.is_empty() && !self.algorithms.contains(&algorithm) {
+                    return Err(AuthError::InvalidToken(
+                        "key algorithm not allowed".to_string(),
+                    ));
+                }
+                vec![algorithm]
+            }
+            None => self.algorithms.clone(),
+        };
+        if algorithms.is_empty() {
+            return Err(AuthError::InvalidToken(
+                "no algorithm configured for key".to_string(),
+            ));
+        }
+        Ok(algorithms)
+    }
+
+    fn find_key(&self, kid: Option<&str>) -> Option<Jwk> {
+        let loaded = self.keys.read().unwrap();
+        match kid {
+            Some(kid) => loaded.keys.find(kid),
+            None if loaded.keys.keys.len() == 1 => loaded.keys.keys.first(),
+            None => None,
+        }
+        .cloned()
+    }
+
+    /// Reload the JWKS once it is `jwks_refresh_seconds` old or, with
+    /// `missing_key`, because a token named a key it lacks. Failures keep the
+    /// current keys until the next reload is due.
+    async fn reload(&self, missing_key: bool) {
+        let Some(source) = &self.source else {
+            return;
+        };
+        let due = |loaded: &LoadedKeys| {
+            if missing_key {
+                loaded
+                    .missing_key_reload_at
+                    .is_none_or(|at| at.elapsed() >= MIN_JWKS_RELOAD_INTERVAL)
+            } else {
+                loaded.loaded_at.elapsed() >= self.refresh_interval
+            }
+        };
+        if !due(&self.keys.read().unwrap()) {
+            return;
+        }
+
+        // Another request may have reloaded while this one waited
+        let _reloading = self.reloading.lock().await;
+        if !due(&self.keys.read().unwrap()) {
+            return;
+        }
+
+        let result = source.load().await;
+        let mut loaded = self.keys.write().unwrap();
+        match result {
+            Ok(keys) => {
+                debug!("Reloaded {} JWKS keys", keys.keys.len());
+                loaded.keys = keys;
+            }
+            Err(e) => warn!("Keeping the current JWKS: {}", e),
+        }
+        loaded.loaded_at = Instant::now();
+        if missing_key {
+            loaded.missing_key_reload_at = Some(Instant::now());
+        }
+    }
+}
+
+/// Which client owns each MCP session on the HTTP transport.
+///
+/// MCP handlers only see the session id, so the HTTP layer records the
+/// authenticated client here for handlers to look up.
+pub struct SessionClients {
+    sessions: RwLock<HashMap<String, ClientIdentity>>,
+}
+
+impl Default for SessionClients {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionClients {
+    pub fn new() -> Self {
+        Self {
+            sessions: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Record the owner of a session, refusing to hand it to a different client
+    pub fn bind(&self, session_id: &str, client: &ClientIdentity) -> Result<(), AuthError> {
+        let mut sessions = self.sessions.write().unwrap();
+        match sessions.get(session_id) {
+            Some(owner) if owner.client_id != client.client_id => Err(AuthError::SessionMismatch),
+            Some(_) => Ok(()),
+            None => {
+                sessions.insert(session_id.to_string(), client.clone());
+                Ok(())
+            }
+        }
+    }
+
+    pub fn get(&self, session_id: &str) -> Option<ClientIdentity> {
+        self.sessions.read().unwrap().get(session_id).cloned()
+    }
+
+    pub fn remove(&self, session_id: &str) {
+        self.sessions.write().unwrap().remove(session_id);
+    }
+}
+
+pub static SESSION_CLIENTS: Lazy<SessionClients> = Lazy::new(SessionClients::new);
+
+/// Look up the authenticated client that owns an MCP session
+pub fn client_for_session(session_id: &str) -> Option<ClientIdentity> {
+    SESSION_CLIENTS.get(session_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn static_authenticator() -> Authenticator {
+        let mut tokens = HashMap::new();
+        tokens.insert("secret-token".to_string(), "team-a".to_string());
+        Authenticator::new(tokens, None)
+    }
+
+    #[tokio::test]
+    async fn test_static_token_authentication() {
+        let auth = static_authenticator();
+
+        let identity = auth
+            .authenticate_header(Some("Bearer secret-token"))
+            .await
+            .unwrap();
+        assert_eq!(identity.client_id, "team-a");
+        assert_eq!(identity.method, AuthMethod::StaticToken);
+
+        assert_eq!(
+            auth.authenticate_header(None).await,
+            Err(AuthError::MissingToken)
+        );
+        assert_eq!(
+            auth.authenticate_header(Some("Basic abc")).await,
+            Err(AuthError::MissingToken)
+        );
+        assert!(matches!(
+            auth.authenticate_header(Some("Bearer wrong")).await,
+            Err(AuthError::InvalidToken(_))
+        ));
+    }
+
+    #[test]
+    fn test_session_binding() {
+        let clients = SessionClients::new();
+        let a = ClientIdentity {
+            client_id: "a".to_string(),
+            method: AuthMethod::StaticToken,
+        };
+        let b = ClientIdentity {
+            client_id: "b".to_string(),
+            method: AuthMethod::StaticToken,
+        };
+
+        assert!(clients.bind("session-1", &a).is_ok());
+        assert!(clients.bind("session-1", &a).is_ok());
+        assert_eq!(
+            clients.bind("session-1", &b),
+            Err(AuthError::SessionMismatch)
+        );
+        assert_eq!(clients.get("session-1"), Some(a));
+
+        clients.remove("session-1");
+        assert!(clients.get("session-1").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_auth_builds_nothing() {
+        let config = AuthConfig::default();
+        assert!(Authenticator::from_config(&config).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_enabled_auth_requires_credentials() {
+        let config = AuthConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(Authenticator::from_config(&config).await.is_err());
+    }
+}
@@ -0,0 +1,226 @@
+//! Command line batch mode: searches in as JSON lines, results out as JSON lines.
+
+use crate::client::OmnisearchClient;
+use crate::common::types::{ErrorType, ProviderError};
+use crate::server::tools::{
+    batch_search_items, OmnisearchParams, BATCH_SEARCH_TOOL_NAME, DEFAULT_BATCH_CONCURRENCY,
+};
+use eyre::{eyre, Result};
+use futures::StreamExt;
+use std::collections::BTreeMap;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+/// A batch run requested on the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchArgs {
+    /// File of searches, one JSON object per line, or `-` for stdin
+    pub input: String,
+    /// How many searches run at once
+    pub concurrency: usize,
+}
+
+/// Read batch mode from command line arguments.
+///
+/// Accepts `--batch <file>` and `--concurrency <n>`, also in `--flag=value`
+/// form. Returns `None` when `--batch` is not given.
+pub fn parse_batch_args<I>(args: I) -> Result<Option<BatchArgs>, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    let mut input = None;
+    let mut concurrency = DEFAULT_BATCH_CONCURRENCY;
+
+    while let Some(arg) = args.next() {
+        let (flag, value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+            None => (arg, None),
+        };
+        if flag != "--batch" && flag != "--concurrency" {
+            continue;
+        }
+        let value = match value {
+            Some(value) => value,
+            None => args
+                .next()
+                .ok_or_else(|| format!("{} requires a value", flag))?,
+        };
+        if flag == "--batch" {
+            input = Some(value);
+        } else {
+            concurrency = value.parse().ok().filter(|n| *n > 0).ok_or_else(|| {
+                format!(
+                    "Invalid concurrency '{}', expected a positive number",
+                    value
+                )
+            })?;
+        }
+    }
+
+    Ok(input.map(|input| BatchArgs { input, concurrency }))
+}
+
+/// Run the searches in `args.input`, writing one result per line to stdout.
+pub async fn run_batch(client: &OmnisearchClient, args: &BatchArgs) -> Result<()> {
+    let mut stdout = tokio::io::stdout();
+    if args.input == "-" {
+        let stdin = BufReader::new(tokio::io::stdin());
+        return run_batch_jsonl(client, stdin, &mut stdout, args.concurrency).await;
+    }
+
+    let file = tokio::fs::File::open(&args.input)
+        .await
+        .map_err(|e| eyre!("Failed to open batch file {}: {}", args.input, e))?;
+    run_batch_jsonl(client, BufReader::new(file), &mut stdout, args.concurrency).await
+}
+
+/// Run a batch of searches read as JSON lines, each with the arguments of the
+/// `omnisearch` tool.
+///
+/// Every non-empty input line gets one output line, in input order, holding
+/// the same item as in the `batch_search` tool's output. A line that is not a
+/// valid search becomes an error item; it does not stop the batch.
+pub async fn run_batch_jsonl<R, W>(
+    client: &OmnisearchClient,
+    input: R,
+    mut output: W,
+    concurrency: usize,
+) -> Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut searches = Vec::new();
+    let mut lines = input.lines();
+    let mut line_number = 0;
+    while let Some(line) = lines.next_line().await? {
+        line_number += 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        searches.push(
+            serde_json::from_str::<OmnisearchParams>(&line).map_err(|e| {
+                ProviderError::new(
+                    ErrorType::InvalidInput,
+                    format!("Invalid search on line {}: {}", line_number, e),
+                    BATCH_SEARCH_TOOL_NAME.to_string(),
+                    None,
+                )
+            }),
+        );
+    }
+
+    // Items finish out of order; each is written once those before it are
+    let finished = batch_search_items(client, searches, concurrency);
+    tokio::pin!(finished);
+    let mut pending = BTreeMap::new();
+    let mut next = 0;
+    while let Some((index, item)) = finished.next().await {
+        pending.insert(index, item);
+        while let Some(item) = pending.remove(&next) {
+            let mut line = serde_json::to_string(&item)?;
+            line.push('\n');
+            output.write_all(line.as_bytes()).await?;
+            next += 1;
+        }
+    }
+    output.flush().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::types::{BaseSearchParams, SearchProvider, SearchResult};
+    use serde_json::Value;
+    use std::sync::Arc;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_batch_flags() {
+        assert_eq!(
+            parse_batch_args(args(&["omnisearch-mcp", "--transport", "http"])).unwrap(),
+            None
+        );
+        assert_eq!(
+            parse_batch_args(args(&["omnisearch-mcp", "--batch", "queries.jsonl"])).unwrap(),
+            Some(BatchArgs {
+                input: "queries.jsonl".to_string(),
+                concurrency: DEFAULT_BATCH_CONCURRENCY,
+            })
+        );
+        assert_eq!(
+            parse_batch_args(args(&["omnisearch-mcp", "--concurrency=8", "--batch=-"])).unwrap(),
+            Some(BatchArgs {
+                input: "-".to_string(),
+                concurrency: 8,
+            })
+        );
+        assert!(parse_batch_args(args(&["omnisearch-mcp", "--batch"])).is_err());
+        assert!(parse_batch_args(args(&[
+            "omnisearch-mcp",
+            "--batch",
+            "-",
+            "--concurrency",
+            "0"
+        ]))
+        .is_err());
+    }
+
+    struct EchoProvider;
+
+    #[async_trait::async_trait]
+    impl SearchProvider for EchoProvider {
+        async fn search(
+            &self,
+            params: BaseSearchParams,
+        ) -> Result<Vec<SearchResult>, ProviderError> {
+            // Later queries answer first, so the output has to be put back in order
+            let delay = 50u64.saturating_sub(params.query.len() as u64 * 10);
+            tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+            Ok(vec![SearchResult {
+                title: params.query.clone(),
+                url: format!("https://example.com/{}", params.query),
+                snippet: String::new(),
+                score: None,
+                source_provider: "tavily".to_string(),
+                ..Default::default()
+            }])
+        }
+
+        fn name(&self) -> &'static str {
+            "tavily"
+        }
+
+        fn description(&self) -> &'static str {
+            "Echo test provider"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_batch_jsonl_keeps_input_order() {
+        let client = OmnisearchClient::from_providers([Arc::new(EchoProvider) as _]);
+        let input = "{\"query\": \"a\"}\n\n{\"query\": \"bb\"}\nnot json\n{\"query\": \"ccc\"}\n";
+        let mut output = Vec::new();
+        run_batch_jsonl(&client, input.as_bytes(), &mut output, 4)
+            .await
+            .unwrap();
+
+        let lines: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["response"]["results"][0]["title"], "a");
+        assert_eq!(lines[1]["query"], "bb");
+        assert!(lines[2]["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("line 4"));
+        assert_eq!(lines[3]["response"]["providers_used"][0], "tavily");
+    }
+}
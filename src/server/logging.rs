@@ -0,0 +1,394 @@
+use crate::config::LoggingConfig;
+use eyre::{eyre, Result};
+use once_cell::sync::Lazy;
+use rust_mcp_sdk::mcp_server::ServerRuntime;
+use rust_mcp_sdk::schema::{LoggingLevel, LoggingMessageNotificationParams};
+use rust_mcp_sdk::McpServer;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::future::Future;
+use std::sync::{Arc, Mutex, RwLock};
+use tokio::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{debug, Event, Level, Span, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{filter, EnvFilter, Layer};
+
+// Only this crate's events are forwarded to clients. Events from this module are
+// skipped so that a failure to forward a message cannot feed back into itself.
+const FORWARDED_TARGET: &str = "omnisearch_mcp";
+const LOGGING_TARGET: &str = module_path!();
+
+// Messages waiting to be forwarded; further messages are dropped while it is full
+const LOG_QUEUE_CAPACITY: usize = 1024;
+
+// The span field naming the session an event was logged for
+const SESSION_FIELD: &str = "mcp_session";
+
+/// Install the process-wide tracing subscriber.
+///
+/// Local logs go to stderr, or to `[logging] file` if set, so they never mix
+/// with the JSON-RPC stream on stdout. `RUST_LOG` overrides `[logging] level`.
+/// Events are also offered to `MCP_LOGGING`, which forwards them to clients that
+/// have asked for them with `logging/setLevel`, tagged with the session whose
+/// [`session_span`] they were logged in.
+pub fn init_logging(config: &LoggingConfig) -> Result<()> {
+    let local_filter = EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(&config.level))
+        .map_err(|e| eyre!("Invalid log level '{}': {}", config.level, e))?;
+
+    let local = match &config.file {
+        Some(path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| eyre!("Failed to open log file {}: {}", path, e))?;
+            let layer = tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(Mutex::new(file));
+            if config.json_format {
+                layer.json().boxed()
+            } else {
+                layer.boxed()
+            }
+        }
+        None => {
+            let layer = tracing_subscriber::fmt::layer().with_writer(std::io::stderr);
+            if config.json_format {
+                layer.json().boxed()
+            } else {
+                layer.boxed()
+            }
+        }
+    };
+
+    tracing_subscriber::registry()
+        .with(local.with_filter(local_filter))
+        .with(McpLoggingLayer.with_filter(filter::filter_fn(|metadata| {
+            metadata.target().starts_with(FORWARDED_TARGET)
+                && !metadata.target().starts_with(LOGGING_TARGET)
+        })))
+        .try_init()
+        .map_err(|e| eyre!("Failed to initialise logging: {}", e))
+}
+
+/// Map a tracing level onto the syslog-style MCP logging levels
+pub fn mcp_level(level: &Level) -> LoggingLevel {
+    match *level {
+        Level::ERROR => LoggingLevel::Error,
+        Level::WARN => LoggingLevel::Warning,
+        Level::INFO => LoggingLevel::Info,
+        Level::DEBUG | Level::TRACE => LoggingLevel::Debug,
+    }
+}
+
+// Position of an MCP logging level, least severe first
+fn severity(level: &LoggingLevel) -> u8 {
+    match level {
+        LoggingLevel::Debug => 0,
+        LoggingLevel::Info => 1,
+        LoggingLevel::Notice => 2,
+        LoggingLevel::Warning => 3,
+        LoggingLevel::Error => 4,
+        LoggingLevel::Critical => 5,
+        LoggingLevel::Alert => 6,
+        LoggingLevel::Emergency => 7,
+    }
+}
+
+/// A span for handling a request from `session_id`. Events logged inside it are
+/// only forwarded to that session.
+pub fn session_span(session_id: &str) -> Span {
+    tracing::info_span!(target: FORWARDED_TARGET, "session", mcp_session = session_id)
+}
+
+/// A log message, with the session it was logged for if it was logged while
+/// handling one
+#[derive(Debug, Clone)]
+pub struct SessionLogMessage {
+    pub session_id: Option<String>,
+    pub params: LoggingMessageNotificationParams,
+}
+
+/// The MCP sessions that have asked for log messages, and the queue of messages
+/// waiting to be sent to them
+pub struct McpLogging {
+    levels: RwLock<HashMap<String, LoggingLevel>>,
+    sender: mpsc::Sender<SessionLogMessage>,
+    receiver: Mutex<Option<mpsc::Receiver<SessionLogMessage>>>,
+}
+
+impl Default for McpLogging {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl McpLogging {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel(LOG_QUEUE_CAPACITY);
+        Self {
+            levels: RwLock::new(HashMap::new()),
+            sender,
+            receiver: Mutex::new(Some(receiver)),
+        }
+    }
+
+    /// Send a session log messages at `level` and above
+    pub fn set_level(&self, session_id: &str, level: LoggingLevel) {
+        self.levels
+            .write()
+            .unwrap()
+            .insert(session_id.to_string(), level);
+    }
+
+    pub fn remove_session(&self, session_id: &str) {
+        self.levels.write().unwrap().remove(session_id);
+    }
+
+    /// The sessions that want a message at `level`
+    pub fn sessions_for(&self, level: &LoggingLevel) -> Vec<String> {
+        self.levels
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(_, wanted)| severity(level) >= severity(wanted))
+            .map(|(session_id, _)| session_id.clone())
+            .collect()
+    }
+
+    /// The sessions `message` goes to.
+    ///
+    /// With a single session, as over stdio, it gets every message it wants.
+    /// Otherwise a message only goes to the session it was logged for, and
+    /// messages logged outside any session go to none.
+    pub fn recipients(&self, message: &SessionLogMessage, single_session: bool) -> Vec<String> {
+        let mut sessions = self.sessions_for(&message.params.level);
+        if !single_session {
+            sessions.retain(|session_id| message.session_id.as_ref() == Some(session_id));
+        }
+        sessions
+    }
+
+    fn wants(&self, level: &LoggingLevel) -> bool {
+        self.levels
+            .read()
+            .unwrap()
+            .values()
+            .any(|wanted| severity(level) >= severity(wanted))
+    }
+
+    /// Queue a message for the sessions that want it
+    pub fn publish(&self, message: SessionLogMessage) {
+        if self.wants(&message.params.level) {
+            // A full queue means clients are not keeping up; drop rather than block logging
+            let _ = self.sender.try_send(message);
+        }
+    }
+
+    /// Take the queue of published messages. Only the first caller gets it.
+    pub fn take_receiver(&self) -> Option<mpsc::Receiver<SessionLogMessage>> {
+        self.receiver.lock().unwrap().take()
+    }
+}
+
+// Global MCP logging state, fed by `McpLoggingLayer`
+pub static MCP_LOGGING: Lazy<McpLogging> = Lazy::new(McpLogging::new);
+
+/// A tracing layer that turns events into MCP `notifications/message` payloads
+pub struct McpLoggingLayer;
+
+// The session a `session_span` was opened for, kept in the span's extensions
+struct SessionTag(String);
+
+impl<S> Layer<S> for McpLoggingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = FieldVisitor(Map::new());
+        attrs.record(&mut fields);
+        if let (Some(Value::String(session_id)), Some(span)) =
+            (fields.0.remove(SESSION_FIELD), ctx.span(id))
+        {
+            span.extensions_mut().insert(SessionTag(session_id));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let level = mcp_level(event.metadata().level());
+        if !MCP_LOGGING.wants(&level) {
+            return;
+        }
+
+        let session_id = ctx.event_scope(event).and_then(|scope| {
+            scope
+                .from_root()
+                .find_map(|span| Some(span.extensions().get::<SessionTag>()?.0.clone()))
+        });
+        let mut fields = FieldVisitor(Map::new());
+        event.record(&mut fields);
+        MCP_LOGGING.publish(SessionLogMessage {
+            session_id,
+            params: LoggingMessageNotificationParams {
+                data: Value::Object(fields.0),
+                level,
+                logger: Some(event.metadata().target().to_string()),
+            },
+        });
+    }
+}
+
+// Collects an event's fields, including `message`, into a JSON object
+struct FieldVisitor(Map<String, Value>);
+
+impl Visit for FieldVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value).into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+}
+
+/// Forward queued log messages to the sessions that asked for them.
+///
+/// `runtime_for` resolves a session id to its runtime; sessions it cannot
+/// resolve are assumed closed and stop receiving messages. Unless the server
+/// has a `single_session`, each message only goes to the session it was
+/// logged for.
+pub async fn forward_log_messages<F, Fut>(single_session: bool, runtime_for: F)
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Option<Arc<ServerRuntime>>>,
+{
+    let Some(mut receiver) = MCP_LOGGING.take_receiver() else {
+        debug!("MCP log messages are already being forwarded");
+        return;
+    };
+
+    while let Some(message) = receiver.recv().await {
+        for session_id in MCP_LOGGING.recipients(&message, single_session) {
+            let Some(runtime) = runtime_for(session_id.clone()).await else {
+                MCP_LOGGING.remove_session(&session_id);
+                continue;
+            };
+            if let Err(e) = runtime.send_logging_message(message.params.clone()).await {
+                debug!(
+                    "Failed to send log message to session {}: {}",
+                    session_id, e
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(level: LoggingLevel) -> SessionLogMessage {
+        tagged(level, None)
+    }
+
+    fn tagged(level: LoggingLevel, session_id: Option<&str>) -> SessionLogMessage {
+        SessionLogMessage {
+            session_id: session_id.map(str::to_string),
+            params: LoggingMessageNotificationParams {
+                data: Value::String("test".to_string()),
+                level,
+                logger: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_sessions_receive_their_level_and_above() {
+        let logging = McpLogging::new();
+        logging.set_level("debug-session", LoggingLevel::Debug);
+        logging.set_level("warning-session", LoggingLevel::Warning);
+
+        let mut info = logging.sessions_for(&LoggingLevel::Info);
+        info.sort();
+        assert_eq!(info, vec!["debug-session"]);
+
+        let mut errors = logging.sessions_for(&LoggingLevel::Error);
+        errors.sort();
+        assert_eq!(errors, vec!["debug-session", "warning-session"]);
+
+        logging.remove_session("debug-session");
+        assert!(logging.sessions_for(&LoggingLevel::Info).is_empty());
+    }
+
+    #[test]
+    fn test_messages_are_only_queued_when_wanted() {
+        let logging = McpLogging::new();
+        let mut receiver = logging.take_receiver().unwrap();
+        assert!(logging.take_receiver().is_none());
+
+        logging.publish(message(LoggingLevel::Error));
+        assert!(receiver.try_recv().is_err());
+
+        logging.set_level("session", LoggingLevel::Warning);
+        logging.publish(message(LoggingLevel::Info));
+        logging.publish(message(LoggingLevel::Error));
+        assert!(matches!(
+            receiver.try_recv().unwrap().params.level,
+            LoggingLevel::Error
+        ));
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_messages_only_reach_their_own_session() {
+        let logging = McpLogging::new();
+        logging.set_level("alice", LoggingLevel::Debug);
+        logging.set_level("bob", LoggingLevel::Debug);
+
+        let from_alice = tagged(LoggingLevel::Warning, Some("alice"));
+        assert_eq!(logging.recipients(&from_alice, false), vec!["alice"]);
+        assert!(logging
+            .recipients(&message(LoggingLevel::Error), false)
+            .is_empty());
+
+        // A single stdio session gets everything it asked for
+        let logging = McpLogging::new();
+        logging.set_level("", LoggingLevel::Info);
+        assert_eq!(
+            logging.recipients(&message(LoggingLevel::Info), true),
+            vec![""]
+        );
+        assert!(logging
+            .recipients(&tagged(LoggingLevel::Debug, Some("")), true)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_mcp_level_mapping() {
+        assert!(matches!(mcp_level(&Level::WARN), LoggingLevel::Warning));
+        assert!(matches!(mcp_level(&Level::TRACE), LoggingLevel::Debug));
+    }
+}
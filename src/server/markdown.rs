@@ -0,0 +1,411 @@
+use crate::common::types::{
+    EnhancementResult, ProcessingResult, ResultDetails, SearchFilter, SearchResult,
+};
+use crate::server::tools::{BatchSearchToolOutput, OmnisearchToolOutput, SearchToolOutput};
+
+/// Readable markdown for the text content of a tool result.
+///
+/// The same data is returned as `structuredContent`; this is what a model or a
+/// person reading the transcript sees.
+pub trait ToMarkdown {
+    fn to_markdown(&self) -> String;
+}
+
+// Keep titles from breaking out of a markdown link
+fn link_text(text: &str) -> String {
+    let text = text.trim();
+    let text = if text.is_empty() { "Untitled" } else { text };
+    text.replace('[', "\\[").replace(']', "\\]")
+}
+
+fn link(title: &str, url: &str) -> String {
+    format!("[{}](<{}>)", link_text(title), url)
+}
+
+fn search_result_markdown(index: usize, result: &SearchResult) -> String {
+    let mut out = format!("{}. {}", index + 1, link(&result.title, &result.url));
+
+    let snippet = result.snippet.trim();
+    if !snippet.is_empty() {
+        out.push_str(&format!("\n   {}", snippet.replace('\n', " ")));
+    }
+
+    let mut details = if result.also_found_by.is_empty() {
+        vec![format!("Source: {}", result.source_provider)]
+    } else {
+        let providers: Vec<&str> = result.providers().collect();
+        vec![format!("Sources: {}", providers.join(", "))]
+    };
+    if let Some(score) = result.score {
+        details.insert(0, format!("Score: {:.2}", score));
+    }
+    if let Some(site_name) = &result.site_name {
+        details.push(format!("Site: {}", site_name));
+    }
+    if let Some(author) = &result.author {
+        details.push(format!("By: {}", author));
+    }
+    if let Some(published_at) = &result.published_at {
+        details.push(format!("Published: {}", published_at));
+    }
+    details.extend(result_details_markdown(result.details.as_ref()));
+    out.push_str(&format!("\n   _{}_", details.join(" · ")));
+    out
+}
+
+// What only a news, image or video result has to show
+fn result_details_markdown(details: Option<&ResultDetails>) -> Vec<String> {
+    let mut out = Vec::new();
+    match details {
+        Some(ResultDetails::News(news)) => {
+            if let Some(publisher) = &news.publisher {
+                out.push(format!("Publisher: {}", publisher));
+            }
+        }
+        Some(ResultDetails::Image(image)) => {
+            if let (Some(width), Some(height)) = (image.width, image.height) {
+                out.push(format!("Size: {}×{}", width, height));
+            }
+            if let Some(page_url) = &image.page_url {
+                out.push(format!("Page: <{}>", page_url));
+            }
+        }
+        Some(ResultDetails::Video(video)) => {
+            if let Some(seconds) = video.duration_seconds {
+                out.push(format!("Duration: {}:{:02}", seconds / 60, seconds % 60));
+            }
+            if let Some(channel) = &video.channel {
+                out.push(format!("Channel: {}", channel));
+            }
+        }
+        None => {}
+    }
+    out
+}
+
+fn next_page_markdown(next_page_token: Option<&str>) -> String {
+    match next_page_token {
+        Some(token) => format!("\n\n_More results: pass page_token `{}`_", token),
+        None => String::new(),
+    }
+}
+
+fn ignored_filters_markdown(provider: &str, filters: &[SearchFilter]) -> String {
+    if filters.is_empty() {
+        return String::new();
+    }
+    let filters: Vec<&str> = filters.iter().map(SearchFilter::as_str).collect();
+    format!("\n\n_Not applied by {}: {}_", provider, filters.join(", "))
+}
+
+fn search_results_markdown(results: &[SearchResult]) -> String {
+    if results.is_empty() {
+        return "No results found.".to_string();
+    }
+
+    let heading = match results.len() {
+        1 => "Found 1 result:".to_string(),
+        n => format!("Found {} results:", n),
+    };
+    let results: Vec<String> = results
+        .iter()
+        .enumerate()
+        .map(|(index, result)| search_result_markdown(index, result))
+        .collect();
+    format!("{}\n\n{}", heading, results.join("\n\n"))
+}
+
+impl ToMarkdown for SearchToolOutput {
+    fn to_markdown(&self) -> String {
+        search_results_markdown(&self.results)
+            + &ignored_filters_markdown("the provider", &self.ignored_filters)
+            + &next_page_markdown(self.next_page_token.as_deref())
+    }
+}
+
+impl ToMarkdown for OmnisearchToolOutput {
+    fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "{}\n\n_Providers used: {}_",
+            search_results_markdown(&self.results),
+            self.providers_used.join(", ")
+        );
+        if self.partial {
+            out.push_str(&format!(
+                "\n\n_Partial results: no answer from {} before the deadline_",
+                self.timed_out.join(", ")
+            ));
+        }
+        for (provider, filters) in &self.ignored_filters {
+            out.push_str(&ignored_filters_markdown(provider, filters));
+        }
+        out.push_str(&next_page_markdown(self.next_page_token.as_deref()));
+        out
+    }
+}
+
+impl ToMarkdown for BatchSearchToolOutput {
+    fn to_markdown(&self) -> String {
+        let failed = self
+            .items
+            .iter()
+            .filter(|item| item.error.is_some())
+            .count();
+        let mut out = match (self.items.len(), failed) {
+            (1, 0) => "Ran 1 search.".to_string(),
+            (n, 0) => format!("Ran {} searches.", n),
+            (n, failed) => format!("Ran {} searches, {} failed.", n, failed),
+        };
+        for (index, item) in self.items.iter().enumerate() {
+            out.push_str(&format!("\n\n## {}. {}\n\n", index + 1, item.query.trim()));
+            match (&item.response, &item.error) {
+                (Some(response), _) => out.push_str(&response.to_markdown()),
+                (None, Some(error)) => {
+                    out.push_str(&format!("_Failed ({}): {}_", error.provider, error.message))
+                }
+                (None, None) => out.push_str("No results found."),
+            }
+        }
+        out
+    }
+}
+
+impl ToMarkdown for ProcessingResult {
+    fn to_markdown(&self) -> String {
+        let metadata = &self.metadata;
+        let mut out = format!(
+            "# {}\n",
+            metadata.title.as_deref().unwrap_or("Processed content")
+        );
+
+        let mut details = Vec::new();
+        if let Some(author) = &metadata.author {
+            details.push(author.clone());
+        }
+        if let Some(date) = &metadata.date {
+            details.push(date.clone());
+        }
+        if let Some(word_count) = metadata.word_count {
+            details.push(format!("{} words", word_count));
+        }
+        details.push(format!("Source: {}", self.source_provider));
+        out.push_str(&format!("\n_{}_\n", details.join(" · ")));
+
+        out.push_str(&format!("\n{}\n", self.content.trim()));
+
+        if let Some(failed_urls) = metadata.failed_urls.as_ref().filter(|u| !u.is_empty()) {
+            out.push_str("\n## Failed URLs\n\n");
+            for url in failed_urls {
+                out.push_str(&format!("- <{}>\n", url));
+            }
+        }
+
+        out.trim_end().to_string()
+    }
+}
+
+impl ToMarkdown for EnhancementResult {
+    fn to_markdown(&self) -> String {
+        let mut out = format!("{}\n", self.enhanced_content.trim());
+
+        if !self.enhancements.is_empty() {
+            out.push_str("\n## Enhancements\n\n");
+            for enhancement in &self.enhancements {
+                out.push_str(&format!(
+                    "- **{}**: {}\n",
+                    enhancement.r#type, enhancement.description
+                ));
+            }
+        }
+
+        if let Some(sources) = self.sources.as_ref().filter(|s| !s.is_empty()) {
+            out.push_str("\n## Sources\n\n");
+            for (index, source) in sources.iter().enumerate() {
+                out.push_str(&format!(
+                    "{}. {}\n",
+                    index + 1,
+                    link(&source.title, &source.url)
+                ));
+            }
+        }
+
+        out.push_str(&format!("\n_Source: {}_", self.source_provider));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::types::{
+        Enhancement, EnhancementSource, ImageResult, ProcessingMetadata, VideoResult,
+    };
+    use crate::server::tools::{BatchSearchError, BatchSearchItem};
+
+    fn result(title: &str, score: Option<f64>) -> SearchResult {
+        SearchResult {
+            title: title.to_string(),
+            url: "https://example.com/page".to_string(),
+            snippet: "A short\nsnippet".to_string(),
+            score,
+            source_provider: "tavily".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_search_results_markdown() {
+        let output = SearchToolOutput {
+            results: vec![result("Rust [book]", Some(0.9)), result("", None)],
+            next_page_token: None,
+            ignored_filters: Vec::new(),
+        };
+        let markdown = output.to_markdown();
+
+        assert!(markdown.starts_with("Found 2 results:"));
+        assert!(markdown.contains("1. [Rust \\[book\\]](<https://example.com/page>)"));
+        assert!(markdown.contains("   A short snippet"));
+        assert!(markdown.contains("_Score: 0.90 · Source: tavily_"));
+        assert!(markdown.contains("2. [Untitled]"));
+
+        let mut merged = result("Merged", None);
+        merged.also_found_by = vec!["exa".to_string()];
+        let output = SearchToolOutput {
+            results: vec![merged],
+            next_page_token: Some("10".to_string()),
+            ignored_filters: vec![SearchFilter::TimeRange, SearchFilter::Country],
+        };
+        let markdown = output.to_markdown();
+        assert!(markdown.contains("_Sources: tavily, exa_"));
+        assert!(markdown.contains("_Not applied by the provider: time_range, country_"));
+        assert!(markdown.ends_with("_More results: pass page_token `10`_"));
+
+        let mut dated = result("Dated", None);
+        dated.site_name = Some("r/rust".to_string());
+        dated.author = Some("ferris".to_string());
+        dated.published_at = Some("2024-03-05T10:30:00Z".to_string());
+        let output = SearchToolOutput {
+            results: vec![dated],
+            next_page_token: None,
+            ignored_filters: Vec::new(),
+        };
+        assert!(output.to_markdown().contains(
+            "_Source: tavily · Site: r/rust · By: ferris · Published: 2024-03-05T10:30:00Z_"
+        ));
+
+        let empty = SearchToolOutput {
+            results: vec![],
+            next_page_token: None,
+            ignored_filters: Vec::new(),
+        };
+        assert_eq!(empty.to_markdown(), "No results found.");
+    }
+
+    #[test]
+    fn test_result_details_markdown() {
+        let mut image = result("Ferris", None);
+        image.details = Some(ResultDetails::Image(ImageResult {
+            width: Some(800),
+            height: Some(600),
+            page_url: Some("https://rustacean.net".to_string()),
+        }));
+        let mut video = result("RustConf keynote", None);
+        video.details = Some(ResultDetails::Video(VideoResult {
+            duration_seconds: Some(3725),
+            channel: Some("RustConf".to_string()),
+        }));
+        let output = SearchToolOutput {
+            results: vec![image, video],
+            next_page_token: None,
+            ignored_filters: Vec::new(),
+        };
+        let markdown = output.to_markdown();
+        assert!(
+            markdown.contains("_Source: tavily · Size: 800×600 · Page: <https://rustacean.net>_")
+        );
+        assert!(markdown.contains("_Source: tavily · Duration: 62:05 · Channel: RustConf_"));
+    }
+
+    #[test]
+    fn test_batch_search_markdown() {
+        let output = BatchSearchToolOutput {
+            items: vec![
+                BatchSearchItem {
+                    query: "rust".to_string(),
+                    response: Some(OmnisearchToolOutput {
+                        results: vec![result("Rust", None)],
+                        providers_used: vec!["tavily".to_string()],
+                        partial: false,
+                        timed_out: Vec::new(),
+                        next_page_token: None,
+                        ignored_filters: Default::default(),
+                    }),
+                    error: None,
+                },
+                BatchSearchItem {
+                    query: "tokio".to_string(),
+                    response: None,
+                    error: Some(BatchSearchError {
+                        error_type: "RateLimit".to_string(),
+                        message: "Too many requests".to_string(),
+                        provider: "exa".to_string(),
+                        retryable: true,
+                        retry_after_secs: None,
+                    }),
+                },
+            ],
+        };
+        let markdown = output.to_markdown();
+
+        assert!(markdown.starts_with("Ran 2 searches, 1 failed."));
+        assert!(markdown.contains("## 1. rust\n\nFound 1 result:"));
+        assert!(markdown.contains("## 2. tokio\n\n_Failed (exa): Too many requests_"));
+    }
+
+    #[test]
+    fn test_processing_result_markdown() {
+        let result = ProcessingResult {
+            content: "Extracted text".to_string(),
+            raw_contents: None,
+            metadata: ProcessingMetadata {
+                title: Some("Guide".to_string()),
+                author: None,
+                date: None,
+                word_count: Some(2),
+                failed_urls: Some(vec!["https://broken.example".to_string()]),
+                urls_processed: None,
+                successful_extractions: None,
+                extract_depth: None,
+            },
+            source_provider: "jina".to_string(),
+        };
+        let markdown = result.to_markdown();
+
+        assert!(markdown.starts_with("# Guide\n\n_2 words · Source: jina_"));
+        assert!(markdown.contains("\nExtracted text\n"));
+        assert!(markdown.ends_with("- <https://broken.example>"));
+    }
+
+    #[test]
+    fn test_enhancement_result_markdown() {
+        let result = EnhancementResult {
+            original_content: "Rust is fast".to_string(),
+            enhanced_content: "Rust is fast and memory safe".to_string(),
+            enhancements: vec![Enhancement {
+                r#type: "grounding".to_string(),
+                description: "Added a cited fact".to_string(),
+            }],
+            sources: Some(vec![EnhancementSource {
+                title: "Rust".to_string(),
+                url: "https://www.rust-lang.org".to_string(),
+            }]),
+            source_provider: "kagi".to_string(),
+        };
+        let markdown = result.to_markdown();
+
+        assert!(markdown.starts_with("Rust is fast and memory safe"));
+        assert!(markdown.contains("- **grounding**: Added a cited fact"));
+        assert!(markdown.contains("1. [Rust](<https://www.rust-lang.org>)"));
+        assert!(markdown.ends_with("_Source: kagi_"));
+    }
+}
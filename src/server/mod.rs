@@ -1,8 +1,16 @@
+pub mod auth;
+pub mod batch;
 pub mod handlers;
+pub mod logging;
+pub mod markdown;
+pub mod prompts;
+pub mod requests;
+pub mod resources;
 pub mod tools;
+pub mod transport;
 
 pub use handlers::setup_handlers;
 pub use tools::{
-    register_enhancement_provider, register_processing_provider, register_search_provider,
-    register_tools,
+    call_tool, call_tool_with_context, list_tools, register_enhancement_provider,
+    register_processing_provider, register_search_provider, register_tools, search_client,
 };
@@ -0,0 +1,350 @@
+use crate::config::PromptsConfig;
+use crate::server::tools::{available_tool_names, tool_name_for_provider, ProviderCategory};
+use eyre::{eyre, Result};
+use figment::{
+    providers::{Format, Toml, Yaml},
+    Figment,
+};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use rust_mcp_sdk::schema::{
+    ContentBlock, GetPromptResult, Prompt, PromptArgument, PromptMessage, Role, RpcError,
+    TextContent,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::RwLock;
+use tracing::{debug, info};
+
+const BUILTIN_PROMPTS: &str = include_str!("builtin_prompts.toml");
+
+// Matches `{argument}`, `{search_tools}` and `{tool:provider}` placeholders
+static PLACEHOLDER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{(tool:[A-Za-z0-9_-]+|[A-Za-z_][A-Za-z0-9_]*)\}").unwrap());
+
+/// A parameterised prompt, as written in a prompt file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub name: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<PromptArgumentTemplate>,
+    /// Provider categories that must have at least one available provider
+    #[serde(default)]
+    pub requires: Vec<ProviderCategory>,
+    /// Individual providers that must be available
+    #[serde(default)]
+    pub requires_providers: Vec<String>,
+    pub template: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptArgumentTemplate {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+// The layout of a prompt file: one or more `[[prompts]]` tables
+#[derive(Debug, Deserialize)]
+struct PromptFile {
+    prompts: Vec<PromptTemplate>,
+}
+
+fn tools_placeholder(category: ProviderCategory) -> String {
+    let category = serde_json::to_value(category).unwrap_or_default();
+    format!("{}_tools", category.as_str().unwrap_or_default())
+}
+
+impl PromptTemplate {
+    /// Check that every placeholder refers to a declared argument or a known tool list
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(eyre!("Prompt name must not be empty"));
+        }
+
+        let tool_lists: Vec<String> = ProviderCategory::ALL
+            .into_iter()
+            .map(tools_placeholder)
+            .collect();
+
+        for captures in PLACEHOLDER.captures_iter(&self.template) {
+            let placeholder = &captures[1];
+            if placeholder.starts_with("tool:")
+                || tool_lists.iter().any(|t| t == placeholder)
+                || self.arguments.iter().any(|a| a.name == placeholder)
+            {
+                continue;
+            }
+            return Err(eyre!(
+                "Prompt '{}' uses undeclared placeholder {{{}}}",
+                self.name,
+                placeholder
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Whether the providers this prompt relies on are currently available
+    pub fn is_available(&self) -> bool {
+        self.requires
+            .iter()
+            .all(|category| !available_tool_names(*category).is_empty())
+            && self
+                .requires_providers
+                .iter()
+                .all(|provider| tool_name_for_provider(provider).is_some())
+    }
+
+    pub fn to_prompt(&self) -> Prompt {
+        Prompt {
+            arguments: self
+                .arguments
+                .iter()
+                .map(|argument| PromptArgument {
+                    description: argument.description.clone(),
+                    name: argument.name.clone(),
+                    required: Some(argument.required),
+                    title: None,
+                })
+                .collect(),
+            description: self.description.clone(),
+            meta: None,
+            name: self.name.clone(),
+            title: self.title.clone(),
+        }
+    }
+
+    /// Fill in the template with the caller's arguments and the available tool names
+    pub fn render(&self, arguments: &HashMap<String, String>) -> Result<String, RpcError> {
+        for argument in self.arguments.iter().filter(|a| a.required) {
+            if arguments
+                .get(&argument.name)
+                .is_none_or(|v| v.trim().is_empty())
+            {
+                return Err(RpcError::invalid_params().with_message(format!(
+                    "Missing required argument '{}' for prompt '{}'",
+                    argument.name, self.name
+                )));
+            }
+        }
+
+        let rendered = PLACEHOLDER.replace_all(&self.template, |captures: &Captures| {
+            let placeholder = &captures[1];
+
+            if let Some(provider) = placeholder.strip_prefix("tool:") {
+                return tool_name_for_provider(provider).unwrap_or_else(|| provider.to_string());
+            }
+
+            if let Some(category) = ProviderCategory::ALL
+                .into_iter()
+                .find(|category| tools_placeholder(*category) == placeholder)
+            {
+                return available_tool_names(category).join(", ");
+            }
+
+            if self.arguments.iter().any(|a| a.name == placeholder) {
+                return arguments.get(placeholder).cloned().unwrap_or_default();
+            }
+
+            captures[0].to_string()
+        });
+
+        Ok(rendered.trim().to_string())
+    }
+}
+
+/// The prompt templates served over MCP, keyed by name
+pub struct PromptLibrary {
+    prompts: RwLock<BTreeMap<String, PromptTemplate>>,
+}
+
+impl Default for PromptLibrary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PromptLibrary {
+    pub fn new() -> Self {
+        Self {
+            prompts: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// A library holding the prompts shipped with the server
+    pub fn with_builtin_prompts() -> Self {
+        let library = Self::new();
+        let file: PromptFile = Figment::from(Toml::string(BUILTIN_PROMPTS))
+            .extract()
+            .expect("built-in prompts are valid");
+        for template in file.prompts {
+            library.add(template).expect("built-in prompts are valid");
+        }
+        library
+    }
+
+    /// Add a prompt, replacing any existing prompt with the same name
+    pub fn add(&self, template: PromptTemplate) -> Result<()> {
+        template.validate()?;
+        self.prompts
+            .write()
+            .unwrap()
+            .insert(template.name.clone(), template);
+        Ok(())
+    }
+
+    /// Load every `.toml`, `.yaml` and `.yml` prompt file in a directory
+    pub fn load_dir(&self, dir: impl AsRef<Path>) -> Result<usize> {
+        let dir = dir.as_ref();
+        let mut paths: Vec<_> = std::fs::read_dir(dir)
+            .map_err(|e| eyre!("Failed to read prompts directory {}: {}", dir.display(), e))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .collect();
+        paths.sort();
+
+        let mut loaded = 0;
+        for path in paths {
+            let figment = match path.extension().and_then(|e| e.to_str()) {
+                Some("toml") => Figment::from(Toml::file(&path)),
+                Some("yaml") | Some("yml") => Figment::from(Yaml::file(&path)),
+                _ => continue,
+            };
+
+            let file: PromptFile = figment
+                .extract()
+                .map_err(|e| eyre!("Invalid prompt file {}: {}", path.display(), e))?;
+            for template in file.prompts {
+                debug!("Loaded prompt '{}' from {}", template.name, path.display());
+                self.add(template)
+                    .map_err(|e| eyre!("Invalid prompt file {}: {}", path.display(), e))?;
+                loaded += 1;
+            }
+        }
+
+        Ok(loaded)
+    }
+
+    /// The prompts whose required providers are available
+    pub fn list(&self) -> Vec<Prompt> {
+        self.prompts
+            .read()
+            .unwrap()
+            .values()
+            .filter(|template| template.is_available())
+            .map(PromptTemplate::to_prompt)
+            .collect()
+    }
+
+    pub fn get(
+        &self,
+        name: &str,
+        arguments: Option<HashMap<String, String>>,
+    ) -> Result<GetPromptResult, RpcError> {
+        let template = self
+            .prompts
+            .read()
+            .unwrap()
+            .get(name)
+            .filter(|template| template.is_available())
+            .cloned()
+            .ok_or_else(|| {
+                RpcError::invalid_params().with_message(format!("Unknown prompt: {}", name))
+            })?;
+
+        let text = template.render(&arguments.unwrap_or_default())?;
+
+        Ok(GetPromptResult {
+            description: template.description.clone(),
+            messages: vec![PromptMessage {
+                content: ContentBlock::TextContent(TextContent::new(text, None, None)),
+                role: Role::User,
+            }],
+            meta: None,
+        })
+    }
+}
+
+// Global prompt library instance
+pub static PROMPT_LIBRARY: Lazy<PromptLibrary> = Lazy::new(PromptLibrary::with_builtin_prompts);
+
+/// Load the prompts directory from `[prompts]`, if one is configured
+pub fn load_configured_prompts(config: &PromptsConfig) -> Result<usize> {
+    let Some(directory) = &config.directory else {
+        return Ok(0);
+    };
+
+    let loaded = PROMPT_LIBRARY.load_dir(directory)?;
+    info!("Loaded {} prompts from {}", loaded, directory);
+    Ok(loaded)
+}
+
+/// List the globally available prompts
+pub fn list_prompts() -> Vec<Prompt> {
+    PROMPT_LIBRARY.list()
+}
+
+/// Render a global prompt with the given arguments
+pub fn get_prompt(
+    name: &str,
+    arguments: Option<HashMap<String, String>>,
+) -> Result<GetPromptResult, RpcError> {
+    PROMPT_LIBRARY.get(name, arguments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(text: &str) -> PromptTemplate {
+        PromptTemplate {
+            name: "test".to_string(),
+            title: None,
+            description: None,
+            arguments: vec![PromptArgumentTemplate {
+                name: "topic".to_string(),
+                description: None,
+                required: true,
+            }],
+            requires: vec![],
+            requires_providers: vec![],
+            template: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_builtin_prompts_parse() {
+        let library = PromptLibrary::with_builtin_prompts();
+        let prompts = library.prompts.read().unwrap();
+        assert!(prompts.contains_key("compare_sources"));
+        assert!(prompts.contains_key("find_documentation"));
+        assert!(prompts.contains_key("reddit_discussion"));
+    }
+
+    #[test]
+    fn test_validate_rejects_undeclared_placeholders() {
+        assert!(template("About {topic} using {search_tools}")
+            .validate()
+            .is_ok());
+        assert!(template("About {topc}").validate().is_err());
+        // JSON-like braces are not placeholders
+        assert!(template("Return {\"url\": ...} for {topic}")
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_render_requires_arguments() {
+        let prompt = template("Research {topic}");
+
+        let mut arguments = HashMap::new();
+        assert!(prompt.render(&arguments).is_err());
+
+        arguments.insert("topic".to_string(), "rust async".to_string());
+        assert_eq!(prompt.render(&arguments).unwrap(), "Research rust async");
+    }
+}
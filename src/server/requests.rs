@@ -0,0 +1,366 @@
+use once_cell::sync::Lazy;
+use rust_mcp_sdk::schema::{
+    CallToolRequestParams, ProgressNotification, ProgressNotificationParams, ProgressToken,
+};
+use rust_mcp_sdk::McpServer;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+// Entries for calls that never reached a handler are dropped after this long
+const STALE_REQUEST_AGE: Duration = Duration::from_secs(600);
+
+struct InFlight {
+    progress_token: Option<ProgressToken>,
+    cancellation: CancellationToken,
+    created: Instant,
+}
+
+#[derive(Default)]
+struct Registry {
+    // Keyed by session and JSON-RPC request id
+    requests: HashMap<String, InFlight>,
+    // Keys of calls not yet claimed by a handler, by session and call
+    unclaimed: HashMap<String, VecDeque<String>>,
+}
+
+/// Tool calls that have been received but not yet answered.
+///
+/// The SDK hands tool handlers neither the JSON-RPC request id nor the request's
+/// `_meta`, so the transports record each `tools/call` here as it arrives and
+/// the handler claims it by session, tool name and arguments. Identical calls
+/// made at the same time in one session are claimed in the order they arrived.
+#[derive(Clone, Default)]
+pub struct InFlightRequests {
+    registry: Arc<Mutex<Registry>>,
+}
+
+fn request_key(session_id: &str, request_id: &Value) -> String {
+    format!("{}:{}", session_id, request_id)
+}
+
+// Identifies a call by what its handler receives
+fn call_key(session_id: &str, name: &str, arguments: Map<String, Value>) -> String {
+    format!("{}\n{}\n{}", session_id, name, Value::Object(arguments))
+}
+
+impl InFlightRequests {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inspect a client message before the MCP runtime sees it.
+    ///
+    /// `tools/call` requests are registered, and `notifications/cancelled`
+    /// cancels the matching call. Batches are handled message by message;
+    /// anything else is ignored. The message itself is never changed.
+    pub fn intercept(&self, session_id: &str, message: &Value) {
+        if let Value::Array(batch) = message {
+            for message in batch {
+                self.intercept(session_id, message);
+            }
+            return;
+        }
+
+        match message.get("method").and_then(Value::as_str) {
+            Some("tools/call") => self.register(session_id, message),
+            Some("notifications/cancelled") => {
+                if let Some(request_id) = message.pointer("/params/requestId") {
+                    let key = request_key(session_id, request_id);
+                    if self.cancel(&key) {
+                        debug!("Client cancelled tool call {}", key);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Intercept a raw JSON-RPC payload, ignoring it if it isn't JSON
+    pub fn intercept_payload(&self, session_id: &str, payload: &str) {
+        if let Ok(message) = serde_json::from_str::<Value>(payload) {
+            self.intercept(session_id, &message);
+        }
+    }
+
+    fn register(&self, session_id: &str, message: &Value) {
+        let Some(request_id) = message.get("id") else {
+            return;
+        };
+        let Some(params) = message.get("params") else {
+            return;
+        };
+        let Some(name) = params.get("name").and_then(Value::as_str) else {
+            return;
+        };
+        let arguments = match params.get("arguments") {
+            Some(Value::Object(arguments)) => arguments.clone(),
+            None | Some(Value::Null) => Map::new(),
+            Some(_) => return,
+        };
+
+        let progress_token = params
+            .get("_meta")
+            .and_then(|meta| meta.get("progressToken"))
+            .and_then(|token| serde_json::from_value(token.clone()).ok());
+
+        let key = request_key(session_id, request_id);
+        let mut registry = self.registry.lock().unwrap();
+        let Registry {
+            requests,
+            unclaimed,
+        } = &mut *registry;
+        requests.retain(|_, request| request.created.elapsed() < STALE_REQUEST_AGE);
+        unclaimed.retain(|_, keys| {
+            keys.retain(|key| requests.contains_key(key));
+            !keys.is_empty()
+        });
+        requests.insert(
+            key.clone(),
+            InFlight {
+                progress_token,
+                cancellation: CancellationToken::new(),
+                created: Instant::now(),
+            },
+        );
+        unclaimed
+            .entry(call_key(session_id, name, arguments))
+            .or_default()
+            .push_back(key);
+    }
+
+    /// Claim the in-flight entry of a call a handler has received.
+    ///
+    /// Returns `None` for calls that did not come through an intercepting transport.
+    pub fn take(&self, session_id: &str, params: &CallToolRequestParams) -> Option<RequestContext> {
+        let call = call_key(
+            session_id,
+            &params.name,
+            params.arguments.clone().unwrap_or_default(),
+        );
+        let mut registry = self.registry.lock().unwrap();
+        let keys = registry.unclaimed.get_mut(&call)?;
+        let key = keys.pop_front()?;
+        if keys.is_empty() {
+            registry.unclaimed.remove(&call);
+        }
+
+        let request = registry.requests.get(&key)?;
+        Some(RequestContext {
+            progress_token: request.progress_token.clone(),
+            cancellation: request.cancellation.clone(),
+            key,
+            requests: self.clone(),
+        })
+    }
+
+    /// Cancel an in-flight call, returning whether it was found
+    pub fn cancel(&self, key: &str) -> bool {
+        match self.registry.lock().unwrap().requests.get(key) {
+            Some(request) => {
+                request.cancellation.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.registry.lock().unwrap().requests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// Global in-flight request tracker shared by the transports and tool handlers
+pub static IN_FLIGHT_REQUESTS: Lazy<InFlightRequests> = Lazy::new(InFlightRequests::new);
+
+/// A claimed in-flight tool call. The entry is forgotten when this is dropped.
+pub struct RequestContext {
+    progress_token: Option<ProgressToken>,
+    cancellation: CancellationToken,
+    key: String,
+    requests: InFlightRequests,
+}
+
+impl RequestContext {
+    pub fn progress_token(&self) -> Option<&ProgressToken> {
+        self.progress_token.as_ref()
+    }
+
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+}
+
+impl Drop for RequestContext {
+    fn drop(&mut self) {
+        self.requests
+            .registry
+            .lock()
+            .unwrap()
+            .requests
+            .remove(&self.key);
+    }
+}
+
+/// What a tool call needs to report progress and notice cancellation
+#[derive(Default)]
+pub struct ToolCallContext<'a> {
+    runtime: Option<&'a dyn McpServer>,
+    request: Option<RequestContext>,
+    cancellation: CancellationToken,
+}
+
+impl<'a> ToolCallContext<'a> {
+    pub fn new(runtime: &'a dyn McpServer, request: Option<RequestContext>) -> Self {
+        let cancellation = request
+            .as_ref()
+            .map(|request| request.cancellation().clone())
+            .unwrap_or_default();
+        Self {
+            runtime: Some(runtime),
+            request,
+            cancellation,
+        }
+    }
+
+    /// A context without progress reporting, cancelled through the given token
+    pub fn with_cancellation(cancellation: CancellationToken) -> Self {
+        Self {
+            runtime: None,
+            request: None,
+            cancellation,
+        }
+    }
+
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    /// Send `notifications/progress` if the client asked for progress
+    pub async fn report_progress(&self, progress: f64, total: Option<f64>, message: String) {
+        let (Some(runtime), Some(token)) = (
+            self.runtime,
+            self.request
+                .as_ref()
+                .and_then(RequestContext::progress_token),
+        ) else {
+            return;
+        };
+
+        let notification = ProgressNotification::new(ProgressNotificationParams {
+            message: Some(message),
+            progress,
+            progress_token: token.clone(),
+            total,
+        });
+        if let Err(e) = runtime.send_notification(notification.into()).await {
+            debug!("Failed to send progress notification: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tool_call(id: Value, meta: Value) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": { "name": "search_test", "arguments": { "query": "rust" }, "_meta": meta }
+        })
+    }
+
+    fn params(message: &Value) -> CallToolRequestParams {
+        serde_json::from_value(message["params"].clone()).unwrap()
+    }
+
+    #[test]
+    fn test_tool_calls_are_registered_and_claimed() {
+        let requests = InFlightRequests::new();
+        let message = tool_call(json!(7), json!({ "progressToken": "abc" }));
+        requests.intercept("session", &message);
+        assert_eq!(requests.len(), 1);
+
+        // The arguments reach the handler untouched
+        let params = params(&message);
+        assert_eq!(
+            params.arguments,
+            json!({ "query": "rust" }).as_object().cloned()
+        );
+
+        let context = requests.take("session", &params).unwrap();
+        assert!(matches!(
+            context.progress_token(),
+            Some(ProgressToken::String(token)) if token == "abc"
+        ));
+        assert!(requests.take("session", &params).is_none());
+
+        drop(context);
+        assert!(requests.is_empty());
+    }
+
+    #[test]
+    fn test_identical_calls_are_claimed_in_order() {
+        let requests = InFlightRequests::new();
+        let first = tool_call(json!(1), json!({ "progressToken": 1 }));
+        let second = tool_call(json!(2), json!({ "progressToken": 2 }));
+        requests.intercept("session", &json!([first, second]));
+
+        let claimed: Vec<_> = (0..2)
+            .map(|_| requests.take("session", &params(&first)).unwrap())
+            .collect();
+        assert!(matches!(
+            claimed[0].progress_token(),
+            Some(ProgressToken::Integer(1))
+        ));
+        assert!(matches!(
+            claimed[1].progress_token(),
+            Some(ProgressToken::Integer(2))
+        ));
+
+        // Another session's handler does not claim this session's calls
+        requests.intercept("session", &first);
+        assert!(requests.take("other", &params(&first)).is_none());
+    }
+
+    #[test]
+    fn test_cancelled_notification_cancels_matching_call() {
+        let requests = InFlightRequests::new();
+        let message = tool_call(json!("req-1"), Value::Null);
+        requests.intercept("session", &message);
+        let context = requests.take("session", &params(&message)).unwrap();
+        assert!(context.progress_token().is_none());
+
+        // Same request id from another session is ignored
+        let cancel = json!([{
+            "jsonrpc": "2.0",
+            "method": "notifications/cancelled",
+            "params": { "requestId": "req-1" }
+        }]);
+        requests.intercept("other", &cancel);
+        assert!(!context.cancellation().is_cancelled());
+
+        requests.intercept("session", &cancel);
+        assert!(context.cancellation().is_cancelled());
+    }
+
+    #[test]
+    fn test_unregistered_and_non_json_payloads_are_ignored() {
+        let requests = InFlightRequests::new();
+        requests.intercept_payload("s", "not json");
+        assert!(requests.is_empty());
+
+        let message = tool_call(json!(1), Value::Null);
+        assert!(requests.take("s", &params(&message)).is_none());
+    }
+}
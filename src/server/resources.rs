@@ -0,0 +1,462 @@
+use crate::common::circuit_breaker::{
+    get_all_circuit_breaker_stats, CircuitBreakerStats, CircuitState,
+};
+use crate::common::health::get_health_status;
+use crate::common::metrics::{get_provider_stats, ProviderStats};
+use crate::config::CONFIG;
+use crate::server::tools::AVAILABLE_PROVIDERS;
+use once_cell::sync::Lazy;
+use rust_mcp_sdk::mcp_server::ServerRuntime;
+use rust_mcp_sdk::schema::{
+    ReadResourceResult, Resource, ResourceTemplate, ResourceUpdatedNotificationParams, RpcError,
+    TextResourceContents,
+};
+use rust_mcp_sdk::McpServer;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+pub const HEALTH_RESOURCE_URI: &str = "omnisearch://health";
+pub const CIRCUIT_BREAKERS_RESOURCE_URI: &str = "omnisearch://circuit-breakers";
+pub const PROVIDER_STATS_URI_TEMPLATE: &str = "omnisearch://providers/{name}/stats";
+
+const PROVIDER_STATS_URI_PREFIX: &str = "omnisearch://providers/";
+const PROVIDER_STATS_URI_SUFFIX: &str = "/stats";
+const JSON_MIME_TYPE: &str = "application/json";
+
+// JSON-RPC error code the MCP spec assigns to unknown resources
+const RESOURCE_NOT_FOUND_CODE: i64 = -32002;
+
+/// How often subscribed resources are re-read to detect changes
+pub const RESOURCE_UPDATE_INTERVAL: Duration = Duration::from_secs(5);
+
+// Fields that change on every read and must not count as an update
+const VOLATILE_FIELDS: &[&str] = &[
+    "timestamp",
+    "uptime_seconds",
+    "last_checked",
+    "duration_ms",
+    "last_request_secs_ago",
+    "last_failure_secs_ago",
+    "state_age_secs",
+];
+
+/// Request statistics for one provider, as published in its stats resource
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderStatsView {
+    pub provider: String,
+    pub total_requests: u64,
+    pub successful_requests: u64,
+    pub failed_requests: u64,
+    pub cache_hits: u64,
+    pub success_rate: f64,
+    pub avg_response_time_ms: u64,
+    pub p95_response_time_ms: Option<u64>,
+    pub hedged_requests: u64,
+    pub hedge_wins: u64,
+    pub last_request_secs_ago: Option<u64>,
+}
+
+impl ProviderStatsView {
+    pub fn new(provider: &str, stats: &ProviderStats) -> Self {
+        let success_rate = if stats.total_requests > 0 {
+            stats.successful_requests as f64 / stats.total_requests as f64
+        } else {
+            0.0
+        };
+
+        Self {
+            provider: provider.to_string(),
+            total_requests: stats.total_requests,
+            successful_requests: stats.successful_requests,
+            failed_requests: stats.failed_requests,
+            cache_hits: stats.cache_hits,
+            success_rate,
+            avg_response_time_ms: stats.avg_response_time.as_millis() as u64,
+            p95_response_time_ms: stats
+                .latency_percentile(95.0)
+                .map(|latency| latency.as_millis() as u64),
+            hedged_requests: stats.hedged_requests,
+            hedge_wins: stats.hedge_wins,
+            last_request_secs_ago: stats.last_request_time.map(|t| t.elapsed().as_secs()),
+        }
+    }
+}
+
+/// The state of one provider's circuit breaker
+#[derive(Debug, Clone, Serialize)]
+pub struct CircuitBreakerView {
+    pub provider: String,
+    pub state: CircuitState,
+    pub failure_count: u32,
+    pub success_count: u32,
+    pub last_failure_secs_ago: Option<u64>,
+    pub state_age_secs: u64,
+}
+
+impl From<&CircuitBreakerStats> for CircuitBreakerView {
+    fn from(stats: &CircuitBreakerStats) -> Self {
+        Self {
+            provider: stats.provider.clone(),
+            state: stats.state.clone(),
+            failure_count: stats.failure_count,
+            success_count: stats.success_count,
+            last_failure_secs_ago: stats.last_failure_time.map(|t| t.elapsed().as_secs()),
+            state_age_secs: stats.state_changed_at.elapsed().as_secs(),
+        }
+    }
+}
+
+pub fn provider_stats_uri(provider: &str) -> String {
+    format!(
+        "{}{}{}",
+        PROVIDER_STATS_URI_PREFIX, provider, PROVIDER_STATS_URI_SUFFIX
+    )
+}
+
+fn provider_from_stats_uri(uri: &str) -> Option<&str> {
+    uri.strip_prefix(PROVIDER_STATS_URI_PREFIX)?
+        .strip_suffix(PROVIDER_STATS_URI_SUFFIX)
+        .filter(|name| !name.is_empty() && !name.contains('/'))
+}
+
+// Every registered provider name, across all categories
+fn registered_providers() -> BTreeSet<String> {
+    let mut providers = BTreeSet::new();
+    for category in [
+        &AVAILABLE_PROVIDERS.search,
+        &AVAILABLE_PROVIDERS.ai_response,
+        &AVAILABLE_PROVIDERS.processing,
+        &AVAILABLE_PROVIDERS.enhancement,
+    ] {
+        providers.extend(category.read().unwrap().iter().cloned());
+    }
+    providers
+}
+
+fn json_resource(uri: String, name: String, title: &str, description: String) -> Resource {
+    Resource {
+        annotations: None,
+        description: Some(description),
+        meta: None,
+        mime_type: Some(JSON_MIME_TYPE.to_string()),
+        name,
+        size: None,
+        title: Some(title.to_string()),
+        uri,
+    }
+}
+
+/// List the health, circuit breaker and per-provider stats resources
+pub fn list_resources() -> Vec<Resource> {
+    let mut resources = vec![
+        json_resource(
+            HEALTH_RESOURCE_URI.to_string(),
+            "health".to_string(),
+            "Server health",
+            "Overall server health and the result of each health check".to_string(),
+        ),
+        json_resource(
+            CIRCUIT_BREAKERS_RESOURCE_URI.to_string(),
+            "circuit-breakers".to_string(),
+            "Circuit breakers",
+            "Circuit breaker state for each provider".to_string(),
+        ),
+    ];
+
+    resources.extend(registered_providers().into_iter().map(|provider| {
+        json_resource(
+            provider_stats_uri(&provider),
+            format!("{}-stats", provider),
+            "Provider stats",
+            format!("Request counts and latency for the {} provider", provider),
+        )
+    }));
+
+    resources
+}
+
+pub fn list_resource_templates() -> Vec<ResourceTemplate> {
+    vec![ResourceTemplate {
+        annotations: None,
+        description: Some("Request counts and latency for a provider".to_string()),
+        meta: None,
+        mime_type: Some(JSON_MIME_TYPE.to_string()),
+        name: "provider-stats".to_string(),
+        title: Some("Provider stats".to_string()),
+        uri_template: PROVIDER_STATS_URI_TEMPLATE.to_string(),
+    }]
+}
+
+/// Read the current JSON contents of a resource
+pub async fn resource_value(uri: &str) -> Result<Value, RpcError> {
+    if uri == HEALTH_RESOURCE_URI {
+        return Ok(serde_json::to_value(get_health_status().await).unwrap_or_default());
+    }
+
+    if uri == CIRCUIT_BREAKERS_RESOURCE_URI {
+        let mut breakers: Vec<CircuitBreakerView> = get_all_circuit_breaker_stats()
+            .await
+            .values()
+            .map(CircuitBreakerView::from)
+            .collect();
+        breakers.sort_by(|a, b| a.provider.cmp(&b.provider));
+
+        return Ok(json!({
+            "enabled": CONFIG.circuit_breaker.enabled,
+            "breakers": breakers,
+        }));
+    }
+
+    if let Some(provider) =
+        provider_from_stats_uri(uri).filter(|p| registered_providers().contains(*p))
+    {
+        let stats = get_provider_stats(provider).await.unwrap_or_default();
+        return Ok(
+            serde_json::to_value(ProviderStatsView::new(provider, &stats)).unwrap_or_default(),
+        );
+    }
+
+    Err(RpcError {
+        code: RESOURCE_NOT_FOUND_CODE,
+        data: Some(json!({ "uri": uri })),
+        message: format!("Resource not found: {}", uri),
+    })
+}
+
+pub async fn read_resource(uri: &str) -> Result<ReadResourceResult, RpcError> {
+    let value = resource_value(uri).await?;
+
+    Ok(ReadResourceResult {
+        contents: vec![TextResourceContents {
+            meta: None,
+            mime_type: Some(JSON_MIME_TYPE.to_string()),
+            text: serde_json::to_string_pretty(&value).unwrap_or_default(),
+            uri: uri.to_string(),
+        }
+        .into()],
+        meta: None,
+    })
+}
+
+// Strip fields that change on every read so only meaningful changes are compared
+fn fingerprint(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(key, _)| !VOLATILE_FIELDS.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), fingerprint(value)))
+                .collect::<Map<String, Value>>(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(fingerprint).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Resource subscriptions per MCP session.
+///
+/// Stdio has a single session with no id, which is tracked under the empty string.
+pub struct ResourceSubscriptions {
+    sessions: RwLock<HashMap<String, HashSet<String>>>,
+    // Last published fingerprint of each subscribed resource
+    fingerprints: RwLock<HashMap<String, Value>>,
+}
+
+impl Default for ResourceSubscriptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResourceSubscriptions {
+    pub fn new() -> Self {
+        Self {
+            sessions: RwLock::new(HashMap::new()),
+            fingerprints: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn subscribe(&self, session_id: &str, uri: &str, current: &Value) {
+        self.sessions
+            .write()
+            .unwrap()
+            .entry(session_id.to_string())
+            .or_default()
+            .insert(uri.to_string());
+        self.fingerprints
+            .write()
+            .unwrap()
+            .entry(uri.to_string())
+            .or_insert_with(|| fingerprint(current));
+    }
+
+    pub fn unsubscribe(&self, session_id: &str, uri: &str) {
+        let mut sessions = self.sessions.write().unwrap();
+        if let Some(uris) = sessions.get_mut(session_id) {
+            uris.remove(uri);
+            if uris.is_empty() {
+                sessions.remove(session_id);
+            }
+        }
+        self.forget_unsubscribed(&sessions);
+    }
+
+    pub fn remove_session(&self, session_id: &str) {
+        let mut sessions = self.sessions.write().unwrap();
+        sessions.remove(session_id);
+        self.forget_unsubscribed(&sessions);
+    }
+
+    fn forget_unsubscribed(&self, sessions: &HashMap<String, HashSet<String>>) {
+        self.fingerprints
+            .write()
+            .unwrap()
+            .retain(|uri, _| sessions.values().any(|uris| uris.contains(uri)));
+    }
+
+    pub fn subscribed_uris(&self) -> Vec<String> {
+        self.fingerprints.read().unwrap().keys().cloned().collect()
+    }
+
+    pub fn subscribers(&self, uri: &str) -> Vec<String> {
+        self.sessions
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(_, uris)| uris.contains(uri))
+            .map(|(session_id, _)| session_id.clone())
+            .collect()
+    }
+
+    /// Record the latest contents of a subscribed resource, returning whether it changed
+    pub fn update(&self, uri: &str, current: &Value) -> bool {
+        let current = fingerprint(current);
+        let mut fingerprints = self.fingerprints.write().unwrap();
+        match fingerprints.get_mut(uri) {
+            Some(previous) if *previous != current => {
+                *previous = current;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+pub static RESOURCE_SUBSCRIPTIONS: Lazy<ResourceSubscriptions> =
+    Lazy::new(ResourceSubscriptions::new);
+
+/// Subscribe a session to updates of an existing resource
+pub async fn subscribe_resource(session_id: &str, uri: &str) -> Result<(), RpcError> {
+    let current = resource_value(uri).await?;
+    RESOURCE_SUBSCRIPTIONS.subscribe(session_id, uri, &current);
+    debug!("Session '{}' subscribed to {}", session_id, uri);
+    Ok(())
+}
+
+pub fn unsubscribe_resource(session_id: &str, uri: &str) {
+    RESOURCE_SUBSCRIPTIONS.unsubscribe(session_id, uri);
+}
+
+/// Re-read every subscribed resource and return the URIs whose contents changed
+pub async fn changed_resources() -> Vec<String> {
+    let mut changed = Vec::new();
+    for uri in RESOURCE_SUBSCRIPTIONS.subscribed_uris() {
+        match resource_value(&uri).await {
+            Ok(value) if RESOURCE_SUBSCRIPTIONS.update(&uri, &value) => changed.push(uri),
+            Ok(_) => {}
+            Err(e) => warn!("Failed to read subscribed resource {}: {}", uri, e.message),
+        }
+    }
+    changed
+}
+
+/// Send `notifications/resources/updated` to subscribers whenever a resource changes.
+///
+/// `runtime_for` resolves a session id to its server runtime; sessions it can
+/// no longer resolve are dropped from the subscriptions.
+pub async fn watch_resource_updates<F, Fut>(interval: Duration, runtime_for: F)
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Option<Arc<ServerRuntime>>>,
+{
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+
+        for uri in changed_resources().await {
+            for session_id in RESOURCE_SUBSCRIPTIONS.subscribers(&uri) {
+                let Some(runtime) = runtime_for(session_id.clone()).await else {
+                    RESOURCE_SUBSCRIPTIONS.remove_session(&session_id);
+                    continue;
+                };
+
+                let params = ResourceUpdatedNotificationParams { uri: uri.clone() };
+                if let Err(e) = runtime.send_resource_updated(params).await {
+                    warn!(
+                        "Failed to notify session '{}' about {}: {}",
+                        session_id, uri, e
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_stats_uri_round_trip() {
+        let uri = provider_stats_uri("tavily");
+        assert_eq!(uri, "omnisearch://providers/tavily/stats");
+        assert_eq!(provider_from_stats_uri(&uri), Some("tavily"));
+
+        assert_eq!(
+            provider_from_stats_uri("omnisearch://providers//stats"),
+            None
+        );
+        assert_eq!(
+            provider_from_stats_uri("omnisearch://providers/a/b/stats"),
+            None
+        );
+        assert_eq!(provider_from_stats_uri(HEALTH_RESOURCE_URI), None);
+    }
+
+    #[test]
+    fn test_updates_ignore_volatile_fields() {
+        let subscriptions = ResourceSubscriptions::new();
+        let initial =
+            json!({ "status": "healthy", "timestamp": 1, "checks": [{ "last_checked": 1 }] });
+        subscriptions.subscribe("session", "omnisearch://health", &initial);
+
+        let same =
+            json!({ "status": "healthy", "timestamp": 2, "checks": [{ "last_checked": 2 }] });
+        assert!(!subscriptions.update("omnisearch://health", &same));
+
+        let degraded = json!({ "status": "degraded", "timestamp": 3, "checks": [] });
+        assert!(subscriptions.update("omnisearch://health", &degraded));
+        assert!(!subscriptions.update("omnisearch://health", &degraded));
+    }
+
+    #[test]
+    fn test_unsubscribe_forgets_resource() {
+        let subscriptions = ResourceSubscriptions::new();
+        subscriptions.subscribe("a", HEALTH_RESOURCE_URI, &json!({}));
+        subscriptions.subscribe("b", HEALTH_RESOURCE_URI, &json!({}));
+        assert_eq!(subscriptions.subscribers(HEALTH_RESOURCE_URI).len(), 2);
+
+        subscriptions.unsubscribe("a", HEALTH_RESOURCE_URI);
+        assert_eq!(subscriptions.subscribers(HEALTH_RESOURCE_URI), vec!["b"]);
+        assert_eq!(subscriptions.subscribed_uris(), vec![HEALTH_RESOURCE_URI]);
+
+        subscriptions.remove_session("b");
+        assert!(subscriptions.subscribed_uris().is_empty());
+    }
+}
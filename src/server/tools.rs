@@ -1,7 +1,218 @@
-use crate::common::types::{EnhancementProvider, ProcessingProvider, SearchProvider};
+use crate::client::{
+    OmnisearchClient, ProviderEvent, SearchCategory, SearchRequest, SearchResponse,
+};
+use crate::common::cache::CacheManager;
+use crate::common::capabilities::{ignored_filters, post_filter, within_limits};
+use crate::common::circuit_breaker::{CircuitBreakerManager, CIRCUIT_BREAKER_MANAGER};
+use crate::common::metrics::{record_client_request_metrics, record_request_metrics};
+use crate::common::rate_limiter::{
+    check_client_rate_limit, RateLimiterManager, RATE_LIMITER_MANAGER,
+};
+use crate::common::types::{
+    BaseSearchParams, EnhancementParams, EnhancementProvider, EnhancementResult, ErrorType,
+    Pagination, ProcessingParams, ProcessingProvider, ProcessingResult, ProviderCapabilities,
+    ProviderError, SearchFilter, SearchProvider, SearchResult, SearchVertical,
+};
+use crate::common::validation::{validate_page_token, validate_search_params};
+use crate::config::CONFIG;
+use crate::server::auth::ClientIdentity;
+use crate::server::markdown::ToMarkdown;
+use crate::server::requests::ToolCallContext;
+use futures::{stream, Stream, StreamExt};
 use once_cell::sync::Lazy;
-use std::collections::HashMap;
-use std::sync::RwLock;
+use rust_mcp_sdk::schema::{
+    schema_utils::{CallToolError, UnknownTool},
+    CallToolResult, ContentBlock, TextContent, Tool, ToolInputSchema, ToolOutputSchema,
+};
+use schemars::{gen::SchemaSettings, JsonSchema};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+// Tool name prefixes, one per provider category
+pub const SEARCH_TOOL_PREFIX: &str = "search_";
+pub const AI_RESPONSE_TOOL_PREFIX: &str = "ai_";
+pub const PROCESSING_TOOL_PREFIX: &str = "process_";
+pub const ENHANCEMENT_TOOL_PREFIX: &str = "enhance_";
+
+/// Name of the umbrella search tool that routes between search providers
+pub const OMNISEARCH_TOOL_NAME: &str = "omnisearch";
+
+const OMNISEARCH_TOOL_DESCRIPTION: &str = "Search the web without choosing a provider. \
+The server picks the best available search provider for the query, using the optional \
+category and include_domains hints, and falls back to the next provider if one fails. \
+Set max_providers to query several providers at once and merge their results, and \
+deadline_ms to get whatever results have arrived within a time budget. \
+Narrow results with time_range, country, language and safe_search; filters a \
+provider could not apply are listed in ignored_filters. Set vertical to news, \
+images, videos or academic to search only providers that return that kind of result. \
+Use the individual search_* tools only when a specific provider is required.";
+
+/// Name of the tool that searches for news articles across providers
+pub const SEARCH_NEWS_TOOL_NAME: &str = "search_news";
+
+const SEARCH_NEWS_TOOL_DESCRIPTION: &str = "Search for news articles. Works like \
+omnisearch, but only uses providers with a news index, and results carry the \
+publisher where the provider names it. Combine with time_range for recent coverage.";
+
+/// Name of the tool that searches for images across providers
+pub const SEARCH_IMAGES_TOOL_NAME: &str = "search_images";
+
+const SEARCH_IMAGES_TOOL_DESCRIPTION: &str = "Search for images. Works like \
+omnisearch, but only uses providers with image search. Each result's url is the \
+image itself, with its size and the page it appears on where known.";
+
+/// Name of the tool that runs many `omnisearch` queries in one call
+pub const BATCH_SEARCH_TOOL_NAME: &str = "batch_search";
+
+const BATCH_SEARCH_TOOL_DESCRIPTION: &str = "Run many searches in one call, as the \
+omnisearch tool would run each of them. Results come back in the same order as the \
+searches, each with its own results or error. Use this for lists of queries, such as \
+one query per entity, rather than calling omnisearch repeatedly.";
+
+/// Most searches accepted in one `batch_search` call
+pub const MAX_BATCH_SEARCHES: usize = 500;
+
+/// Searches run at once in a batch unless the caller asks otherwise
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 4;
+
+/// Most searches run at once in a batch
+pub const MAX_BATCH_CONCURRENCY: usize = 16;
+
+/// Arguments accepted by the `omnisearch` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OmnisearchParams {
+    #[serde(flatten)]
+    pub search: BaseSearchParams,
+    /// The kind of results wanted; steers which providers are tried first
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<SearchCategory>,
+    /// Query up to this many providers at once and merge their results, instead
+    /// of using the first provider that succeeds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_providers: Option<u32>,
+    /// Stop waiting for providers after this many milliseconds and return the
+    /// results that have arrived
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline_ms: Option<u64>,
+}
+
+/// Structured output of the `omnisearch` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OmnisearchToolOutput {
+    /// Results in the order the provider ranked them
+    pub results: Vec<SearchResult>,
+    /// Providers whose results were returned
+    pub providers_used: Vec<String>,
+    /// Whether the deadline passed before every provider answered
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub partial: bool,
+    /// Providers that had not answered when the deadline passed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub timed_out: Vec<String>,
+    /// Pass as `page_token` to get more results, if there are any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_page_token: Option<String>,
+    /// Filters that providers whose results were used could not apply
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub ignored_filters: BTreeMap<String, Vec<SearchFilter>>,
+}
+
+impl From<SearchResponse> for OmnisearchToolOutput {
+    fn from(response: SearchResponse) -> Self {
+        Self {
+            results: response.results,
+            providers_used: response.providers_used,
+            partial: response.partial,
+            timed_out: response.timed_out,
+            next_page_token: response.next_page_token,
+            ignored_filters: response.ignored_filters,
+        }
+    }
+}
+
+/// Arguments accepted by the `batch_search` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BatchSearchParams {
+    /// The searches to run, each with the arguments of the omnisearch tool
+    pub searches: Vec<OmnisearchParams>,
+    /// How many searches to run at once (default 4, at most 16)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<u32>,
+}
+
+/// Structured output of the `batch_search` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BatchSearchToolOutput {
+    /// One item per search, in the order they were given
+    pub items: Vec<BatchSearchItem>,
+}
+
+/// The outcome of one search in a batch
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BatchSearchItem {
+    /// The query searched for
+    pub query: String,
+    /// The search's output, if it succeeded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<OmnisearchToolOutput>,
+    /// Why the search failed, if it did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<BatchSearchError>,
+}
+
+/// A failed search in a batch, as an error tool result would describe it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BatchSearchError {
+    pub error_type: String,
+    pub message: String,
+    pub provider: String,
+    /// Whether the same search may succeed if tried again later
+    #[serde(default)]
+    pub retryable: bool,
+    /// Seconds the provider asked us to wait before trying again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_secs: Option<u64>,
+}
+
+impl BatchSearchItem {
+    pub fn new(query: String, outcome: Result<SearchResponse, ProviderError>) -> Self {
+        match outcome {
+            Ok(response) => Self {
+                query,
+                response: Some(response.into()),
+                error: None,
+            },
+            Err(e) => Self {
+                query,
+                response: None,
+                error: Some(BatchSearchError {
+                    error_type: format!("{:?}", e.error_type),
+                    retryable: e.is_retryable(),
+                    retry_after_secs: e.retry_after.map(|wait| wait.as_secs()),
+                    message: e.message,
+                    provider: e.provider,
+                }),
+            },
+        }
+    }
+}
+
+/// Structured output of the search and AI response tools
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SearchToolOutput {
+    /// Results in the order the provider ranked them
+    pub results: Vec<SearchResult>,
+    /// Pass as `page_token` to get more results, if there are any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_page_token: Option<String>,
+    /// Filters the provider could not apply
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignored_filters: Vec<SearchFilter>,
+}
 
 // Track available providers by category
 pub static AVAILABLE_PROVIDERS: Lazy<AvailableProviders> = Lazy::new(AvailableProviders::new);
@@ -30,11 +241,79 @@ impl AvailableProviders {
     }
 }
 
+/// The kinds of provider tracked in `AVAILABLE_PROVIDERS`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderCategory {
+    Search,
+    AiResponse,
+    Processing,
+    Enhancement,
+}
+
+impl ProviderCategory {
+    pub const ALL: [ProviderCategory; 4] = [
+        ProviderCategory::Search,
+        ProviderCategory::AiResponse,
+        ProviderCategory::Processing,
+        ProviderCategory::Enhancement,
+    ];
+
+    pub fn tool_prefix(&self) -> &'static str {
+        match self {
+            ProviderCategory::Search => SEARCH_TOOL_PREFIX,
+            ProviderCategory::AiResponse => AI_RESPONSE_TOOL_PREFIX,
+            ProviderCategory::Processing => PROCESSING_TOOL_PREFIX,
+            ProviderCategory::Enhancement => ENHANCEMENT_TOOL_PREFIX,
+        }
+    }
+
+    fn providers(&self) -> &RwLock<std::collections::HashSet<String>> {
+        match self {
+            ProviderCategory::Search => &AVAILABLE_PROVIDERS.search,
+            ProviderCategory::AiResponse => &AVAILABLE_PROVIDERS.ai_response,
+            ProviderCategory::Processing => &AVAILABLE_PROVIDERS.processing,
+            ProviderCategory::Enhancement => &AVAILABLE_PROVIDERS.enhancement,
+        }
+    }
+}
+
+/// Sorted tool names of the available providers in one category
+pub fn available_tool_names(category: ProviderCategory) -> Vec<String> {
+    let mut names: Vec<String> = category
+        .providers()
+        .read()
+        .unwrap()
+        .iter()
+        .map(|name| format!("{}{}", category.tool_prefix(), name))
+        .collect();
+    names.sort();
+    names
+}
+
+/// The tool name an available provider is exposed under
+pub fn tool_name_for_provider(provider: &str) -> Option<String> {
+    ProviderCategory::ALL.into_iter().find_map(|category| {
+        category
+            .providers()
+            .read()
+            .unwrap()
+            .contains(provider)
+            .then(|| format!("{}{}", category.tool_prefix(), provider))
+    })
+}
+
 // Provider registry
 pub struct ToolRegistry {
-    search_providers: RwLock<HashMap<String, Box<dyn SearchProvider>>>,
-    processing_providers: RwLock<HashMap<String, Box<dyn ProcessingProvider>>>,
-    enhancement_providers: RwLock<HashMap<String, Box<dyn EnhancementProvider>>>,
+    search_providers: RwLock<HashMap<String, Arc<dyn SearchProvider>>>,
+    processing_providers: RwLock<HashMap<String, Arc<dyn ProcessingProvider>>>,
+    enhancement_providers: RwLock<HashMap<String, Arc<dyn EnhancementProvider>>>,
+    // What every search through `search_client` goes through
+    cache: Arc<CacheManager>,
+    rate_limiter: Arc<RateLimiterManager>,
+    circuit_breaker: Arc<CircuitBreakerManager>,
+    // Built on first use, and again after a search provider is registered
+    search_client: RwLock<Option<Arc<OmnisearchClient>>>,
 }
 
 impl Default for ToolRegistry {
@@ -44,11 +323,30 @@ impl Default for ToolRegistry {
 }
 
 impl ToolRegistry {
+    /// An empty registry whose searches use the configured cache and the
+    /// process-wide rate limiter and circuit breakers
     pub fn new() -> Self {
+        Self::with_search_services(
+            Arc::new(CacheManager::with_config(&CONFIG.cache)),
+            Arc::new(RATE_LIMITER_MANAGER.clone()),
+            Arc::new(CIRCUIT_BREAKER_MANAGER.clone()),
+        )
+    }
+
+    /// An empty registry whose searches use these instead
+    pub fn with_search_services(
+        cache: Arc<CacheManager>,
+        rate_limiter: Arc<RateLimiterManager>,
+        circuit_breaker: Arc<CircuitBreakerManager>,
+    ) -> Self {
         Self {
             search_providers: RwLock::new(HashMap::new()),
             processing_providers: RwLock::new(HashMap::new()),
             enhancement_providers: RwLock::new(HashMap::new()),
+            cache,
+            rate_limiter,
+            circuit_breaker,
+            search_client: RwLock::new(None),
         }
     }
 
@@ -61,7 +359,7 @@ impl ToolRegistry {
         self.search_providers
             .write()
             .unwrap()
-            .insert(name.clone(), provider);
+            .insert(name.clone(), Arc::from(provider));
         if is_ai_response {
             AVAILABLE_PROVIDERS
                 .ai_response
@@ -71,6 +369,7 @@ impl ToolRegistry {
         } else {
             AVAILABLE_PROVIDERS.search.write().unwrap().insert(name);
         }
+        *self.search_client.write().unwrap() = None;
     }
 
     pub fn register_processing_provider(&self, provider: Box<dyn ProcessingProvider>) {
@@ -78,7 +377,7 @@ impl ToolRegistry {
         self.processing_providers
             .write()
             .unwrap()
-            .insert(name.clone(), provider);
+            .insert(name.clone(), Arc::from(provider));
         AVAILABLE_PROVIDERS.processing.write().unwrap().insert(name);
     }
 
@@ -87,13 +386,723 @@ impl ToolRegistry {
         self.enhancement_providers
             .write()
             .unwrap()
-            .insert(name.clone(), provider);
+            .insert(name.clone(), Arc::from(provider));
         AVAILABLE_PROVIDERS
             .enhancement
             .write()
             .unwrap()
             .insert(name);
     }
+
+    /// Describe every registered provider as an MCP tool, sorted by tool name
+    pub fn list_tools(&self) -> Vec<Tool> {
+        let ai_response = AVAILABLE_PROVIDERS.ai_response.read().unwrap();
+        let mut tools = Vec::new();
+
+        let client = self.search_client();
+        if !client.available_providers().is_empty() {
+            tools.push(Tool {
+                title: Some("Omnisearch".to_string()),
+                ..build_tool(
+                    "",
+                    OMNISEARCH_TOOL_NAME,
+                    OMNISEARCH_TOOL_DESCRIPTION,
+                    input_schema_for::<OmnisearchParams>(),
+                    output_schema_for::<OmnisearchToolOutput>(),
+                )
+            });
+            for (vertical, title, name, description) in [
+                (
+                    SearchVertical::News,
+                    "News search",
+                    SEARCH_NEWS_TOOL_NAME,
+                    SEARCH_NEWS_TOOL_DESCRIPTION,
+                ),
+                (
+                    SearchVertical::Images,
+                    "Image search",
+                    SEARCH_IMAGES_TOOL_NAME,
+                    SEARCH_IMAGES_TOOL_DESCRIPTION,
+                ),
+            ] {
+                if !client
+                    .route(&SearchRequest::new("").vertical(vertical))
+                    .is_empty()
+                {
+                    tools.push(Tool {
+                        title: Some(title.to_string()),
+                        ..build_tool(
+                            "",
+                            name,
+                            description,
+                            vertical_input_schema(),
+                            output_schema_for::<OmnisearchToolOutput>(),
+                        )
+                    });
+                }
+            }
+            tools.push(Tool {
+                title: Some("Batch search".to_string()),
+                ..build_tool(
+                    "",
+                    BATCH_SEARCH_TOOL_NAME,
+                    BATCH_SEARCH_TOOL_DESCRIPTION,
+                    input_schema_for::<BatchSearchParams>(),
+                    output_schema_for::<BatchSearchToolOutput>(),
+                )
+            });
+        }
+
+        for provider in self.search_providers.read().unwrap().values() {
+            let prefix = if ai_response.contains(provider.name()) {
+                AI_RESPONSE_TOOL_PREFIX
+            } else {
+                SEARCH_TOOL_PREFIX
+            };
+            tools.push(build_tool(
+                prefix,
+                provider.name(),
+                provider.description(),
+                search_input_schema(&provider.capabilities()),
+                output_schema_for::<SearchToolOutput>(),
+            ));
+        }
+
+        for provider in self.processing_providers.read().unwrap().values() {
+            tools.push(build_tool(
+                PROCESSING_TOOL_PREFIX,
+                provider.name(),
+                provider.description(),
+                input_schema_for::<ProcessingParams>(),
+                output_schema_for::<ProcessingResult>(),
+            ));
+        }
+
+        for provider in self.enhancement_providers.read().unwrap().values() {
+            tools.push(build_tool(
+                ENHANCEMENT_TOOL_PREFIX,
+                provider.name(),
+                provider.description(),
+                input_schema_for::<EnhancementParams>(),
+                output_schema_for::<EnhancementResult>(),
+            ));
+        }
+
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        tools
+    }
+
+    /// Run the provider behind `tool_name` with the given MCP arguments.
+    ///
+    /// Unknown tools are reported as a `CallToolError`. Bad arguments and provider
+    /// failures come back as a `CallToolResult` with `is_error` set, so the model
+    /// can see what went wrong and retry.
+    pub async fn call_tool(
+        &self,
+        tool_name: &str,
+        arguments: Option<Map<String, Value>>,
+    ) -> Result<CallToolResult, CallToolError> {
+        self.call_tool_with_context(tool_name, arguments, &ToolCallContext::default())
+            .await
+    }
+
+    /// Run a tool call, reporting progress and stopping early if it is cancelled.
+    ///
+    /// Cancelling drops the in-flight provider requests and returns an error result.
+    pub async fn call_tool_with_context(
+        &self,
+        tool_name: &str,
+        arguments: Option<Map<String, Value>>,
+        context: &ToolCallContext<'_>,
+    ) -> Result<CallToolResult, CallToolError> {
+        tokio::select! {
+            biased;
+            _ = context.cancellation().cancelled() => Ok(error_result(&ProviderError::new(
+                ErrorType::ProviderError,
+                "Request cancelled by the client".to_string(),
+                tool_name.to_string(),
+                None,
+            ))),
+            result = self.dispatch(tool_name, arguments, context) => result,
+        }
+    }
+
+    /// The client over the registered web search providers, excluding AI response
+    /// providers. Every caller shares its cache, rate limiter and circuit breakers.
+    pub fn search_client(&self) -> Arc<OmnisearchClient> {
+        if let Some(client) = self.search_client.read().unwrap().as_ref() {
+            return Arc::clone(client);
+        }
+
+        // Built under the lock, so a provider registered meanwhile is not missed
+        let mut cached = self.search_client.write().unwrap();
+        if let Some(client) = cached.as_ref() {
+            return Arc::clone(client);
+        }
+        let ai_response = AVAILABLE_PROVIDERS.ai_response.read().unwrap().clone();
+        let client = OmnisearchClient::from_providers(
+            self.search_providers
+                .read()
+                .unwrap()
+                .values()
+                .filter(|provider| !ai_response.contains(provider.name()))
+                .cloned(),
+        )
+        .with_cache(Arc::clone(&self.cache))
+        .with_rate_limiter(Arc::clone(&self.rate_limiter))
+        .with_circuit_breaker(Arc::clone(&self.circuit_breaker));
+        Arc::clone(cached.insert(Arc::new(client)))
+    }
+
+    async fn dispatch(
+        &self,
+        tool_name: &str,
+        arguments: Option<Map<String, Value>>,
+        context: &ToolCallContext<'_>,
+    ) -> Result<CallToolResult, CallToolError> {
+        if tool_name == OMNISEARCH_TOOL_NAME {
+            return Ok(run_omnisearch(&self.search_client(), arguments, None, context).await);
+        }
+        for (name, vertical) in [
+            (SEARCH_NEWS_TOOL_NAME, SearchVertical::News),
+            (SEARCH_IMAGES_TOOL_NAME, SearchVertical::Images),
+        ] {
+            if tool_name == name {
+                let client = self.search_client();
+                return Ok(run_omnisearch(&client, arguments, Some(vertical), context).await);
+            }
+        }
+        if tool_name == BATCH_SEARCH_TOOL_NAME {
+            return Ok(run_batch_search(&self.search_client(), arguments, context).await);
+        }
+
+        let ai_response = AVAILABLE_PROVIDERS.ai_response.read().unwrap().clone();
+
+        if let Some(name) = tool_name
+            .strip_prefix(SEARCH_TOOL_PREFIX)
+            .filter(|name| !ai_response.contains(*name))
+            .or_else(|| {
+                tool_name
+                    .strip_prefix(AI_RESPONSE_TOOL_PREFIX)
+                    .filter(|name| ai_response.contains(*name))
+            })
+        {
+            let provider = self.search_providers.read().unwrap().get(name).cloned();
+            if let Some(provider) = provider {
+                return Ok(run_search(provider.as_ref(), arguments, context).await);
+            }
+        } else if let Some(name) = tool_name.strip_prefix(PROCESSING_TOOL_PREFIX) {
+            let provider = self.processing_providers.read().unwrap().get(name).cloned();
+            if let Some(provider) = provider {
+                return Ok(run_processing(provider.as_ref(), arguments, context).await);
+            }
+        } else if let Some(name) = tool_name.strip_prefix(ENHANCEMENT_TOOL_PREFIX) {
+            let provider = self
+                .enhancement_providers
+                .read()
+                .unwrap()
+                .get(name)
+                .cloned();
+            if let Some(provider) = provider {
+                return Ok(run_enhancement(provider.as_ref(), arguments, context).await);
+            }
+        }
+
+        Err(CallToolError::unknown_tool(tool_name))
+    }
+}
+
+async fn run_search(
+    provider: &dyn SearchProvider,
+    arguments: Option<Map<String, Value>>,
+    context: &ToolCallContext<'_>,
+) -> CallToolResult {
+    let params = match parse_arguments(provider.name(), arguments)
+        .and_then(|params| validated_search_params(provider.name(), params))
+        .and_then(|params| validated_page_token(provider, params))
+    {
+        Ok(params) => within_limits(provider, params),
+        Err(e) => return error_result(&e),
+    };
+
+    let search = provider.search(params.clone());
+    match timed(provider.name(), "search", context, search).await {
+        Ok(results) => success_result(&SearchToolOutput {
+            next_page_token: provider.next_page_token(&params, &results),
+            ignored_filters: ignored_filters(provider, &params),
+            results: post_filter(provider, &params, results),
+        }),
+        Err(e) => error_result(&e),
+    }
+}
+
+// Run an `omnisearch` call, or a vertical tool's call with its vertical set
+async fn run_omnisearch(
+    client: &OmnisearchClient,
+    arguments: Option<Map<String, Value>>,
+    vertical: Option<SearchVertical>,
+    context: &ToolCallContext<'_>,
+) -> CallToolResult {
+    let mut request =
+        match parse_arguments(OMNISEARCH_TOOL_NAME, arguments).and_then(omnisearch_request) {
+            Ok(request) => request,
+            Err(e) => return error_result(&e),
+        };
+    if vertical.is_some() {
+        request.vertical = vertical;
+    }
+
+    if request.max_providers.is_some_and(|max| max > 1) {
+        return run_omnisearch_stream(client, request, context).await;
+    }
+
+    match client.search(request).await {
+        Ok(response) => {
+            context
+                .report_progress(
+                    1.0,
+                    Some(1.0),
+                    format!("{} finished", response.providers_used.join(", ")),
+                )
+                .await;
+            success_result(&OmnisearchToolOutput::from(response))
+        }
+        Err(e) => error_result(&e),
+    }
+}
+
+// The client request for validated `omnisearch` arguments
+fn omnisearch_request(params: OmnisearchParams) -> Result<SearchRequest, ProviderError> {
+    let search = validated_search_params(OMNISEARCH_TOOL_NAME, params.search)?;
+    Ok(SearchRequest {
+        query: search.query,
+        limit: search.limit,
+        include_domains: search.include_domains,
+        exclude_domains: search.exclude_domains,
+        preferred_provider: None,
+        category: params.category,
+        merge_strategy: None,
+        max_providers: params.max_providers.map(|max| max as usize),
+        deadline: params.deadline_ms.map(Duration::from_millis),
+        offset: search.offset,
+        page_token: search.page_token,
+        time_range: search.time_range,
+        country: search.country,
+        language: search.language,
+        safe_search: search.safe_search,
+        vertical: search.vertical,
+    })
+}
+
+// Query several providers at once, reporting progress as each one finishes
+async fn run_omnisearch_stream(
+    client: &OmnisearchClient,
+    request: SearchRequest,
+    context: &ToolCallContext<'_>,
+) -> CallToolResult {
+    let events = client.search_stream(request);
+    tokio::pin!(events);
+
+    let mut total = 0.0;
+    let mut finished = 0.0;
+    while let Some(event) = events.next().await {
+        let message = match event {
+            ProviderEvent::Started { providers } => {
+                total = providers.len() as f64;
+                continue;
+            }
+            ProviderEvent::Results { provider, results } => {
+                format!("{} returned {} results", provider, results.len())
+            }
+            ProviderEvent::Failed { provider, error } => {
+                format!("{} failed: {}", provider, error.message)
+            }
+            ProviderEvent::Completed(Ok(response)) => {
+                return success_result(&OmnisearchToolOutput::from(response))
+            }
+            ProviderEvent::Completed(Err(e)) => return error_result(&e),
+        };
+        finished += 1.0;
+        context
+            .report_progress(finished, Some(total), message)
+            .await;
+    }
+
+    error_result(&ProviderError::new(
+        ErrorType::ProviderError,
+        "Search ended without a result".to_string(),
+        OMNISEARCH_TOOL_NAME.to_string(),
+        None,
+    ))
+}
+
+async fn run_batch_search(
+    client: &OmnisearchClient,
+    arguments: Option<Map<String, Value>>,
+    context: &ToolCallContext<'_>,
+) -> CallToolResult {
+    let params: BatchSearchParams = match parse_arguments(BATCH_SEARCH_TOOL_NAME, arguments) {
+        Ok(params) => params,
+        Err(e) => return error_result(&e),
+    };
+    if params.searches.is_empty() || params.searches.len() > MAX_BATCH_SEARCHES {
+        return error_result(&ProviderError::new(
+            ErrorType::InvalidInput,
+            format!(
+                "A batch needs between 1 and {} searches",
+                MAX_BATCH_SEARCHES
+            ),
+            BATCH_SEARCH_TOOL_NAME.to_string(),
+            None,
+        ));
+    }
+    let concurrency = params
+        .concurrency
+        .map_or(DEFAULT_BATCH_CONCURRENCY, |concurrency| {
+            concurrency as usize
+        });
+
+    let total = params.searches.len();
+    let searches = params.searches.into_iter().map(Ok).collect();
+    let finished = batch_search_items(client, searches, concurrency);
+    tokio::pin!(finished);
+
+    let mut items: Vec<Option<BatchSearchItem>> = (0..total).map(|_| None).collect();
+    let mut done = 0;
+    while let Some((index, item)) = finished.next().await {
+        done += 1;
+        let message = match &item.error {
+            Some(error) => format!("\"{}\" failed: {}", item.query, error.message),
+            None => format!("\"{}\" finished", item.query),
+        };
+        context
+            .report_progress(done as f64, Some(total as f64), message)
+            .await;
+        items[index] = Some(item);
+    }
+
+    success_result(&BatchSearchToolOutput {
+        items: items.into_iter().flatten().collect(),
+    })
+}
+
+/// Run a batch of `omnisearch` searches, yielding each one's item as it finishes
+/// along with its position in `searches`.
+///
+/// Searches whose arguments could not be read are given as errors and fail
+/// without being run. At most `concurrency` searches run at once, capped at
+/// [`MAX_BATCH_CONCURRENCY`].
+pub fn batch_search_items(
+    client: &OmnisearchClient,
+    searches: Vec<Result<OmnisearchParams, ProviderError>>,
+    concurrency: usize,
+) -> impl Stream<Item = (usize, BatchSearchItem)> + '_ {
+    let mut queries = Vec::new();
+    let mut positions = Vec::new();
+    let mut requests = Vec::new();
+    let mut rejected = Vec::new();
+    for (index, search) in searches.into_iter().enumerate() {
+        let query = search
+            .as_ref()
+            .map(|params| params.search.query.clone())
+            .unwrap_or_default();
+        match search.and_then(omnisearch_request) {
+            Ok(request) => {
+                queries.push(query);
+                positions.push(index);
+                requests.push(request);
+            }
+            Err(e) => rejected.push((index, BatchSearchItem::new(query, Err(e)))),
+        }
+    }
+
+    let concurrency = concurrency.clamp(1, MAX_BATCH_CONCURRENCY);
+    let finished =
+        client
+            .search_batch_stream(requests, concurrency)
+            .map(move |(index, outcome)| {
+                let query = std::mem::take(&mut queries[index]);
+                (positions[index], BatchSearchItem::new(query, outcome))
+            });
+    stream::iter(rejected).chain(finished)
+}
+
+async fn run_processing(
+    provider: &dyn ProcessingProvider,
+    arguments: Option<Map<String, Value>>,
+    context: &ToolCallContext<'_>,
+) -> CallToolResult {
+    let params: ProcessingParams = match parse_arguments(provider.name(), arguments) {
+        Ok(params) => params,
+        Err(e) => return error_result(&e),
+    };
+
+    if params.urls.is_empty() {
+        return error_result(&ProviderError::new(
+            ErrorType::InvalidInput,
+            "At least one URL is required".to_string(),
+            provider.name().to_string(),
+            None,
+        ));
+    }
+
+    match timed(
+        provider.name(),
+        "process",
+        context,
+        provider.process_content(params.urls, params.extract_depth),
+    )
+    .await
+    {
+        Ok(result) => success_result(&result),
+        Err(e) => error_result(&e),
+    }
+}
+
+async fn run_enhancement(
+    provider: &dyn EnhancementProvider,
+    arguments: Option<Map<String, Value>>,
+    context: &ToolCallContext<'_>,
+) -> CallToolResult {
+    let params: EnhancementParams = match parse_arguments(provider.name(), arguments) {
+        Ok(params) => params,
+        Err(e) => return error_result(&e),
+    };
+
+    if params.content.trim().is_empty() {
+        return error_result(&ProviderError::new(
+            ErrorType::InvalidInput,
+            "Content must not be empty".to_string(),
+            provider.name().to_string(),
+            None,
+        ));
+    }
+
+    match timed(
+        provider.name(),
+        "enhance",
+        context,
+        provider.enhance_content(params.content),
+    )
+    .await
+    {
+        Ok(result) => success_result(&result),
+        Err(e) => error_result(&e),
+    }
+}
+
+// Run a provider call, recording it in the provider's metrics and reporting
+// progress once the provider has finished
+async fn timed<T>(
+    provider: &str,
+    operation: &str,
+    context: &ToolCallContext<'_>,
+    call: impl Future<Output = Result<T, ProviderError>>,
+) -> Result<T, ProviderError> {
+    let start = Instant::now();
+    let result = call.await;
+    record_request_metrics(
+        provider,
+        operation,
+        start.elapsed(),
+        result.is_ok(),
+        None,
+        false,
+    )
+    .await;
+    context
+        .report_progress(1.0, Some(1.0), format!("{} finished", provider))
+        .await;
+    result
+}
+
+fn validated_search_params(
+    provider: &str,
+    params: BaseSearchParams,
+) -> Result<BaseSearchParams, ProviderError> {
+    validate_search_params(&params)
+        .map(|validated| validated.to_base_params())
+        .map_err(|e| {
+            ProviderError::new(
+                ErrorType::InvalidInput,
+                e.to_string(),
+                provider.to_string(),
+                None,
+            )
+        })
+}
+
+fn validated_page_token(
+    provider: &dyn SearchProvider,
+    params: BaseSearchParams,
+) -> Result<BaseSearchParams, ProviderError> {
+    validate_page_token(provider, &params)
+        .map(|()| params)
+        .map_err(|e| {
+            ProviderError::new(
+                ErrorType::InvalidInput,
+                e.to_string(),
+                provider.name().to_string(),
+                None,
+            )
+        })
+}
+
+fn parse_arguments<T: DeserializeOwned>(
+    provider: &str,
+    arguments: Option<Map<String, Value>>,
+) -> Result<T, ProviderError> {
+    serde_json::from_value(Value::Object(arguments.unwrap_or_default())).map_err(|e| {
+        ProviderError::new(
+            ErrorType::InvalidInput,
+            format!("Invalid arguments: {}", e),
+            provider.to_string(),
+            None,
+        )
+    })
+}
+
+/// A tool result carrying `payload` as `structuredContent`, matching the tool's
+/// `outputSchema`, with a markdown rendering as its text content
+pub fn success_result<T: Serialize + ToMarkdown>(payload: &T) -> CallToolResult {
+    let text = payload.to_markdown();
+    let structured = match serde_json::to_value(payload).unwrap_or_default() {
+        Value::Object(map) => Some(map),
+        _ => None,
+    };
+
+    CallToolResult {
+        content: vec![ContentBlock::TextContent(TextContent::new(
+            text, None, None,
+        ))],
+        is_error: None,
+        meta: None,
+        structured_content: structured,
+    }
+}
+
+/// Convert a `ProviderError` into a tool result the model can read
+pub fn error_result(error: &ProviderError) -> CallToolResult {
+    let mut details = json!({
+        "error_type": format!("{:?}", error.error_type),
+        "message": error.message,
+        "provider": error.provider,
+        "retryable": error.is_retryable(),
+    });
+    if let Some(retry_after) = error.retry_after {
+        details["retry_after_secs"] = retry_after.as_secs().into();
+    }
+
+    CallToolResult {
+        content: vec![ContentBlock::TextContent(TextContent::new(
+            error.to_string(),
+            None,
+            None,
+        ))],
+        is_error: Some(true),
+        meta: None,
+        structured_content: details.as_object().cloned(),
+    }
+}
+
+fn build_tool(
+    prefix: &str,
+    provider_name: &str,
+    description: &str,
+    input_schema: ToolInputSchema,
+    output_schema: ToolOutputSchema,
+) -> Tool {
+    Tool {
+        name: format!("{}{}", prefix, provider_name),
+        description: Some(description.to_string()),
+        input_schema,
+        annotations: None,
+        meta: None,
+        output_schema: Some(output_schema),
+        title: None,
+    }
+}
+
+/// Generate an MCP tool input schema from a parameter type's JSON Schema.
+///
+/// Subschemas are inlined because `ToolInputSchema` has no room for `$defs`.
+pub fn input_schema_for<T: JsonSchema>() -> ToolInputSchema {
+    let (required, properties) = object_schema_for::<T>();
+    ToolInputSchema::new(required, properties)
+}
+
+/// The input schema of a provider's search tool: the search parameters without
+/// the filters and paging it cannot apply, and with `limit` capped at its maximum.
+///
+/// Domain filters stay, since results are filtered by domain afterwards.
+pub fn search_input_schema(capabilities: &ProviderCapabilities) -> ToolInputSchema {
+    let (required, mut properties) = object_schema_for::<BaseSearchParams>();
+    if let Some(properties) = &mut properties {
+        for filter in [
+            SearchFilter::TimeRange,
+            SearchFilter::Country,
+            SearchFilter::Language,
+            SearchFilter::SafeSearch,
+        ] {
+            if !capabilities.supports(filter) {
+                properties.remove(filter.as_str());
+            }
+        }
+        if capabilities.pagination == Pagination::None {
+            properties.remove("offset");
+            properties.remove("page_token");
+        }
+        if let Some(limit) = properties.get_mut("limit") {
+            limit.insert("maximum".to_string(), capabilities.max_limit.into());
+        }
+    }
+    ToolInputSchema::new(required, properties)
+}
+
+// The `omnisearch` arguments without `vertical`, which the vertical tools set
+fn vertical_input_schema() -> ToolInputSchema {
+    let (required, mut properties) = object_schema_for::<OmnisearchParams>();
+    if let Some(properties) = &mut properties {
+        properties.remove(SearchFilter::Vertical.as_str());
+    }
+    ToolInputSchema::new(required, properties)
+}
+
+/// Generate an MCP tool output schema from a result type's JSON Schema
+pub fn output_schema_for<T: JsonSchema>() -> ToolOutputSchema {
+    let (required, properties) = object_schema_for::<T>();
+    ToolOutputSchema::new(required, properties)
+}
+
+type ObjectSchema = (Vec<String>, Option<HashMap<String, Map<String, Value>>>);
+
+// The required fields and inlined properties of an object type's JSON Schema
+fn object_schema_for<T: JsonSchema>() -> ObjectSchema {
+    let settings = SchemaSettings::draft07().with(|s| s.inline_subschemas = true);
+    let root = settings.into_generator().into_root_schema_for::<T>();
+    let schema = serde_json::to_value(&root.schema).unwrap_or_default();
+
+    let required = schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| {
+            r.iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let properties = schema
+        .get("properties")
+        .and_then(|p| p.as_object())
+        .map(|props| {
+            props
+                .iter()
+                .filter_map(|(name, prop)| prop.as_object().map(|o| (name.clone(), o.clone())))
+                .collect()
+        });
+
+    (required, properties)
 }
 
 // Global registry instance
@@ -104,6 +1113,86 @@ pub fn register_tools() -> eyre::Result<()> {
     Ok(())
 }
 
+/// The client over the globally registered web search providers
+pub fn search_client() -> Arc<OmnisearchClient> {
+    REGISTRY.search_client()
+}
+
+/// List all globally registered providers as MCP tools
+pub fn list_tools() -> Vec<Tool> {
+    REGISTRY.list_tools()
+}
+
+/// Dispatch a tool call to the globally registered provider behind `tool_name`.
+///
+/// Calls from an authenticated client are rate limited and recorded per client.
+pub async fn call_tool(
+    tool_name: &str,
+    arguments: Option<Map<String, Value>>,
+    client: Option<&ClientIdentity>,
+) -> Result<CallToolResult, CallToolError> {
+    call_tool_with_context(tool_name, arguments, client, &ToolCallContext::default()).await
+}
+
+/// Like `call_tool`, reporting progress and honouring cancellation through `context`
+pub async fn call_tool_with_context(
+    tool_name: &str,
+    arguments: Option<Map<String, Value>>,
+    client: Option<&ClientIdentity>,
+    context: &ToolCallContext<'_>,
+) -> Result<CallToolResult, CallToolError> {
+    let Some(client) = client else {
+        return REGISTRY
+            .call_tool_with_context(tool_name, arguments, context)
+            .await;
+    };
+
+    if let Err(e) = check_client_rate_limit(&client.client_id).await {
+        return Ok(error_result(&ProviderError::new(
+            ErrorType::RateLimit,
+            e.to_string(),
+            tool_name.to_string(),
+            None,
+        )));
+    }
+
+    // Errors are returned as they are, so that authenticated and anonymous
+    // clients see the same response for an unknown tool or bad arguments
+    let start = Instant::now();
+    let result = REGISTRY
+        .call_tool_with_context(tool_name, arguments, context)
+        .await
+        .map_err(DispatchError::from);
+    let success = matches!(&result, Ok(result) if result.is_error != Some(true));
+    record_client_request_metrics(&client.client_id, tool_name, success, start.elapsed()).await;
+
+    result.map_err(CallToolError::from)
+}
+
+// A `CallToolError` as a `Send` value, so it can be held across an await
+enum DispatchError {
+    UnknownTool(UnknownTool),
+    Other(String),
+}
+
+impl From<CallToolError> for DispatchError {
+    fn from(error: CallToolError) -> Self {
+        match error.0.downcast::<UnknownTool>() {
+            Ok(unknown) => Self::UnknownTool(*unknown),
+            Err(error) => Self::Other(error.to_string()),
+        }
+    }
+}
+
+impl From<DispatchError> for CallToolError {
+    fn from(error: DispatchError) -> Self {
+        match error {
+            DispatchError::UnknownTool(unknown) => CallToolError::new(unknown),
+            DispatchError::Other(message) => CallToolError::from_message(message),
+        }
+    }
+}
+
 pub fn register_search_provider(provider: Box<dyn SearchProvider>, is_ai_response: bool) {
     REGISTRY.register_search_provider(provider, is_ai_response);
 }
@@ -0,0 +1,761 @@
+use crate::common::health::{get_health_status, ServiceStatus};
+use crate::config::Config;
+use crate::server::auth::{AuthError, Authenticator, ClientIdentity, SESSION_CLIENTS};
+use crate::server::logging::{forward_log_messages, MCP_LOGGING};
+use crate::server::requests::IN_FLIGHT_REQUESTS;
+use crate::server::resources::{
+    watch_resource_updates, RESOURCE_SUBSCRIPTIONS, RESOURCE_UPDATE_INTERVAL,
+};
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use eyre::{eyre, Result};
+use futures::StreamExt;
+use rust_mcp_sdk::mcp_server::hyper_runtime::HyperRuntime;
+use rust_mcp_sdk::mcp_server::{
+    hyper_server, server_runtime, HyperServerOptions, IdGenerator, ServerHandler, ServerRuntime,
+    SessionStore, UuidGenerator,
+};
+use rust_mcp_sdk::schema::{schema_utils::ClientMessage, InitializeResult};
+use rust_mcp_sdk::{McpServer, SseTransport, TransportOptions};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{debug, error, info, warn};
+
+const MCP_SESSION_ID_HEADER: &str = "mcp-session-id";
+const STREAMABLE_HTTP_ENDPOINT: &str = "/mcp";
+const SSE_ENDPOINT: &str = "/sse";
+const MAX_REQUEST_BODY_BYTES: usize = 4 * 1024 * 1024;
+const STDIO_BUFFER_BYTES: usize = 64 * 1024;
+
+/// How the MCP server talks to its clients
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransportMode {
+    /// One client over stdin/stdout (the default, used by desktop MCP hosts)
+    #[default]
+    Stdio,
+    /// Streamable HTTP with legacy SSE fallback, shared by many clients
+    Http,
+}
+
+impl FromStr for TransportMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "stdio" => Ok(TransportMode::Stdio),
+            "http" => Ok(TransportMode::Http),
+            other => Err(format!(
+                "Unknown transport '{}', expected 'stdio' or 'http'",
+                other
+            )),
+        }
+    }
+}
+
+/// Read the transport mode from command line arguments.
+///
+/// Accepts `--transport <mode>` and `--transport=<mode>`; defaults to stdio.
+pub fn parse_transport_args<I>(args: I) -> Result<TransportMode, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    let mut mode = TransportMode::default();
+
+    while let Some(arg) = args.next() {
+        if let Some(value) = arg.strip_prefix("--transport=") {
+            mode = value.parse()?;
+        } else if arg == "--transport" {
+            let value = args
+                .next()
+                .ok_or_else(|| "--transport requires a value".to_string())?;
+            mode = value.parse()?;
+        }
+    }
+
+    Ok(mode)
+}
+
+/// Serve MCP to a single client over stdin/stdout.
+///
+/// The SDK's stdio runtime handles one message at a time, so a
+/// `notifications/cancelled` would only be read after the call it cancels had
+/// finished. Instead stdin is read here and every message passes through
+/// `IN_FLIGHT_REQUESTS` before being handed to the runtime over an in-memory
+/// pipe, which lets cancellation take effect while the call is still running.
+pub async fn serve_stdio(
+    server_details: InitializeResult,
+    handler: impl ServerHandler,
+) -> Result<()> {
+    let supports_subscriptions = server_details
+        .capabilities
+        .resources
+        .as_ref()
+        .and_then(|resources| resources.subscribe)
+        .unwrap_or(false);
+    let supports_logging = server_details.capabilities.logging.is_some();
+
+    let (read_rx, mut read_tx) = tokio::io::duplex(STDIO_BUFFER_BYTES);
+    let (mut write_rx, write_tx) = tokio::io::duplex(STDIO_BUFFER_BYTES);
+    // Only used by transports that receive payloads out of band, which stdio does not
+    let (_, receiver_tx) = tokio::io::duplex(STDIO_BUFFER_BYTES);
+
+    let transport = SseTransport::<ClientMessage>::new(
+        read_rx,
+        write_tx,
+        receiver_tx,
+        Arc::new(TransportOptions::default()),
+    )
+    .map_err(|e| eyre!("Failed to create transport: {}", e))?;
+    let server: Arc<ServerRuntime> = Arc::new(server_runtime::create_server(
+        server_details,
+        transport,
+        handler,
+    ));
+
+    let reader = tokio::spawn(async move {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            IN_FLIGHT_REQUESTS.intercept_payload("", &line);
+            if read_tx.write_all(line.as_bytes()).await.is_err()
+                || read_tx.write_all(b"\n").await.is_err()
+            {
+                break;
+            }
+        }
+        // Dropping the pipe ends the runtime once stdin is closed
+    });
+    let writer = tokio::spawn(async move {
+        let _ = tokio::io::copy(&mut write_rx, &mut tokio::io::stdout()).await;
+    });
+
+    // Stdio has a single session, so every subscription and log level notifies
+    // this runtime
+    let watcher = supports_subscriptions.then(|| {
+        let notifier = Arc::clone(&server);
+        tokio::spawn(watch_resource_updates(
+            RESOURCE_UPDATE_INTERVAL,
+            move |_session_id| {
+                let runtime = Arc::clone(&notifier);
+                async move { Some(runtime) }
+            },
+        ))
+    });
+    let log_forwarder = supports_logging.then(|| {
+        let notifier = Arc::clone(&server);
+        tokio::spawn(forward_log_messages(true, move |_session_id| {
+            let runtime = Arc::clone(&notifier);
+            async move { Some(runtime) }
+        }))
+    });
+
+    info!("Omnisearch MCP server running on stdio");
+
+    if let Err(start_error) = server.start().await {
+        error!(
+            "MCP stdio server stopped: {}",
+            start_error
+                .rpc_error_message()
+                .unwrap_or(&start_error.to_string())
+        );
+    }
+
+    for task in [watcher, log_forwarder].into_iter().flatten() {
+        task.abort();
+    }
+    reader.abort();
+    let _ = writer.await;
+    Ok(())
+}
+
+/// Serve MCP over Streamable HTTP (with legacy SSE) on the `[server]` address,
+/// authenticating clients as configured in `[auth]`
+pub async fn serve_http(
+    server_details: InitializeResult,
+    handler: impl ServerHandler,
+    config: &Config,
+) -> Result<()> {
+    let authenticator = Authenticator::from_config(&config.auth)
+        .await?
+        .map(Arc::new);
+    if authenticator.is_none() {
+        warn!("HTTP authentication is disabled; anyone who can reach the server can use it");
+    }
+
+    let server = &config.server;
+    let listener = TcpListener::bind((server.host.as_str(), server.port))
+        .await
+        .map_err(|e| eyre!("Failed to bind {}:{}: {}", server.host, server.port, e))?;
+
+    serve_http_on(
+        listener,
+        server_details,
+        handler,
+        server.max_connections,
+        authenticator,
+    )
+    .await
+}
+
+/// Serve MCP over HTTP on an already bound listener.
+///
+/// The SDK's HTTP server offers no way to reject a request before it reaches
+/// the MCP runtime, so it runs on a loopback port behind a small gateway that
+/// owns the public listener. The gateway checks bearer tokens, records which
+/// client owns each session and turns away new sessions once
+/// `max_connections` are open. The loopback server only answers requests that
+/// carry a Host known to the gateway alone, so other local processes cannot
+/// go around it.
+pub async fn serve_http_on(
+    listener: TcpListener,
+    server_details: InitializeResult,
+    handler: impl ServerHandler,
+    max_connections: usize,
+    authenticator: Option<Arc<Authenticator>>,
+) -> Result<()> {
+    let supports_subscriptions = server_details
+        .capabilities
+        .resources
+        .as_ref()
+        .and_then(|resources| resources.subscribe)
+        .unwrap_or(false);
+    let supports_logging = server_details.capabilities.logging.is_some();
+
+    let upstream = Upstream::start(server_details, handler).await?;
+    let sessions = upstream.sessions.clone();
+
+    let watcher = supports_subscriptions.then(|| {
+        let sessions = sessions.clone();
+        tokio::spawn(watch_resource_updates(
+            RESOURCE_UPDATE_INTERVAL,
+            move |session_id| {
+                let sessions = sessions.clone();
+                async move {
+                    let runtime = sessions.get(&session_id).await?;
+                    let runtime = runtime.lock().await.clone();
+                    Some(runtime)
+                }
+            },
+        ))
+    });
+    let log_forwarder = supports_logging.then(|| {
+        let sessions = sessions.clone();
+        tokio::spawn(forward_log_messages(false, move |session_id| {
+            let sessions = sessions.clone();
+            async move {
+                let runtime = sessions.get(&session_id).await?;
+                let runtime = runtime.lock().await.clone();
+                Some(runtime)
+            }
+        }))
+    });
+
+    let gateway = Arc::new(HttpGateway {
+        upstream: upstream.url.clone(),
+        upstream_host: upstream.host.clone(),
+        client: reqwest::Client::new(),
+        limiter: ConnectionLimiter::new(max_connections, sessions),
+        authenticator,
+    });
+
+    info!(
+        "Omnisearch MCP server listening on http://{}{} (SSE at {}, max {} connections)",
+        listener.local_addr()?,
+        STREAMABLE_HTTP_ENDPOINT,
+        SSE_ENDPOINT,
+        max_connections
+    );
+
+    axum::serve(listener, gateway_router(gateway))
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+
+    for task in [watcher, log_forwarder].into_iter().flatten() {
+        task.abort();
+    }
+    upstream.runtime.graceful_shutdown(None);
+    Ok(())
+}
+
+// The SDK's HTTP server on a loopback port, reachable only through the gateway
+struct Upstream {
+    url: String,
+    // The Host every request must carry; a random value only the gateway is given
+    host: HeaderValue,
+    sessions: Arc<dyn SessionStore>,
+    runtime: HyperRuntime,
+}
+
+impl Upstream {
+    async fn start(server_details: InitializeResult, handler: impl ServerHandler) -> Result<Self> {
+        let host = format!("{}.omnisearch.invalid", UuidGenerator {}.generate());
+        let server = hyper_server::create_server(
+            server_details,
+            handler,
+            HyperServerOptions {
+                host: "127.0.0.1".to_string(),
+                port: 0,
+                sse_support: true,
+                // The SDK's DNS rebinding check turns away any other Host
+                allowed_hosts: Some(vec![host.clone()]),
+                dns_rebinding_protection: true,
+                ..Default::default()
+            },
+        );
+        let sessions = server.state().session_store.clone();
+        let handle = server.server_handle();
+        let runtime = server
+            .start_runtime()
+            .await
+            .map_err(|e| eyre!("Failed to start MCP HTTP server: {}", e))?;
+        let addr = handle
+            .listening()
+            .await
+            .ok_or_else(|| eyre!("MCP HTTP server failed to listen"))?;
+        Ok(Self {
+            url: format!("http://{}", addr),
+            host: HeaderValue::from_str(&host)?,
+            sessions,
+            runtime,
+        })
+    }
+}
+
+/// Caps the number of concurrent MCP sessions on the HTTP transport.
+///
+/// A request that would open a session takes a slot before it is forwarded, so
+/// concurrent `initialize` requests cannot overshoot the limit. The slot is held
+/// for as long as the session lives: by the response stream for SSE sessions,
+/// and against the session id for Streamable HTTP ones, until it is deleted or
+/// the runtime drops it.
+pub struct ConnectionLimiter {
+    max_connections: usize,
+    slots: Arc<Semaphore>,
+    held: Mutex<HashMap<String, OwnedSemaphorePermit>>,
+    sessions: Arc<dyn SessionStore>,
+}
+
+impl ConnectionLimiter {
+    pub fn new(max_connections: usize, sessions: Arc<dyn SessionStore>) -> Self {
+        Self {
+            max_connections,
+            slots: Arc::new(Semaphore::new(max_connections)),
+            held: Mutex::new(HashMap::new()),
+            sessions,
+        }
+    }
+
+    pub fn max_connections(&self) -> usize {
+        self.max_connections
+    }
+
+    /// Take a slot for a new session, or `None` when all are in use.
+    ///
+    /// Slots of sessions the runtime has since dropped are reclaimed first.
+    pub async fn reserve(&self) -> Option<OwnedSemaphorePermit> {
+        if let Ok(permit) = Arc::clone(&self.slots).try_acquire_owned() {
+            return Some(permit);
+        }
+        let open: HashSet<String> = self.sessions.keys().await.into_iter().collect();
+        self.held
+            .lock()
+            .unwrap()
+            .retain(|session_id, _| open.contains(session_id));
+        Arc::clone(&self.slots).try_acquire_owned().ok()
+    }
+
+    /// Keep a slot for as long as `session_id` is open
+    pub fn hold(&self, session_id: &str, permit: OwnedSemaphorePermit) {
+        self.held
+            .lock()
+            .unwrap()
+            .insert(session_id.to_string(), permit);
+    }
+
+    /// Free the slot of a closed session
+    pub fn release(&self, session_id: &str) {
+        self.held.lock().unwrap().remove(session_id);
+    }
+}
+
+struct HttpGateway {
+    upstream: String,
+    upstream_host: HeaderValue,
+    client: reqwest::Client,
+    limiter: ConnectionLimiter,
+    authenticator: Option<Arc<Authenticator>>,
+}
+
+fn gateway_router(gateway: Arc<HttpGateway>) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .fallback(forward)
+        .with_state(gateway)
+}
+
+async fn health() -> impl IntoResponse {
+    let status = get_health_status().await;
+    let code = match status.status {
+        ServiceStatus::Unhealthy => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::OK,
+    };
+    (code, Json(status))
+}
+
+/// Whether a request would open a new MCP session
+fn opens_session(method: &Method, path: &str, headers: &HeaderMap) -> bool {
+    if headers.contains_key(MCP_SESSION_ID_HEADER) {
+        return false;
+    }
+    (method == Method::POST && path == STREAMABLE_HTTP_ENDPOINT)
+        || (method == Method::GET && path == SSE_ENDPOINT)
+}
+
+/// The MCP session a request belongs to, from the Streamable HTTP header or
+/// the legacy SSE `sessionId` query parameter
+fn request_session_id(uri: &Uri, headers: &HeaderMap) -> Option<String> {
+    if let Some(id) = headers
+        .get(MCP_SESSION_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+    {
+        return Some(id.to_string());
+    }
+
+    uri.query()?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("sessionId="))
+        .map(str::to_string)
+}
+
+fn rpc_error_response(status: StatusCode, message: String) -> Response {
+    (
+        status,
+        Json(json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": { "code": -32000, "message": message },
+        })),
+    )
+        .into_response()
+}
+
+fn unauthorized(error: AuthError) -> Response {
+    let challenge = match error {
+        AuthError::InvalidToken(_) => "Bearer realm=\"omnisearch-mcp\", error=\"invalid_token\"",
+        _ => "Bearer realm=\"omnisearch-mcp\"",
+    };
+    let mut response = rpc_error_response(StatusCode::UNAUTHORIZED, error.to_string());
+    response.headers_mut().insert(
+        header::WWW_AUTHENTICATE,
+        HeaderValue::from_static(challenge),
+    );
+    response
+}
+
+async fn forward(State(gateway): State<Arc<HttpGateway>>, request: Request) -> Response {
+    let (parts, body) = request.into_parts();
+
+    let client: Option<ClientIdentity> = match &gateway.authenticator {
+        Some(authenticator) => {
+            let header = parts
+                .headers
+                .get(header::AUTHORIZATION)
+                .and_then(|v| v.to_str().ok());
+            match authenticator.authenticate_header(header).await {
+                Ok(identity) => Some(identity),
+                Err(e) => {
+                    debug!("Rejecting unauthenticated request: {}", e);
+                    return unauthorized(e);
+                }
+            }
+        }
+        None => None,
+    };
+
+    let session_id = request_session_id(&parts.uri, &parts.headers);
+    if let (Some(client), Some(session_id)) = (&client, &session_id) {
+        if let Err(e) = SESSION_CLIENTS.bind(session_id, client) {
+            warn!(
+                "Client '{}' tried to use session {} owned by another client",
+                client.client_id, session_id
+            );
+            return rpc_error_response(StatusCode::FORBIDDEN, e.to_string());
+        }
+    }
+
+    let mut slot = if opens_session(&parts.method, parts.uri.path(), &parts.headers) {
+        match gateway.limiter.reserve().await {
+            Some(permit) => Some(permit),
+            None => {
+                warn!(
+                    "Rejecting new MCP session: connection limit of {} reached",
+                    gateway.limiter.max_connections()
+                );
+                return rpc_error_response(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    format!(
+                        "Connection limit reached ({} sessions)",
+                        gateway.limiter.max_connections()
+                    ),
+                );
+            }
+        }
+    } else {
+        None
+    };
+
+    let is_delete = parts.method == Method::DELETE;
+
+    let body = match to_bytes(body, MAX_REQUEST_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(_) => return StatusCode::PAYLOAD_TOO_LARGE.into_response(),
+    };
+
+    // Track tool calls and cancellations before the runtime sees them
+    if parts.method == Method::POST {
+        if let Ok(payload) = std::str::from_utf8(&body) {
+            let session = session_id.as_deref().unwrap_or_default();
+            IN_FLIGHT_REQUESTS.intercept_payload(session, payload);
+        }
+    }
+
+    let path = parts
+        .uri
+        .path_and_query()
+        .map(|p| p.as_str())
+        .unwrap_or("/");
+    let mut upstream_request = gateway
+        .client
+        .request(parts.method, format!("{}{}", gateway.upstream, path));
+    for (name, value) in parts.headers.iter() {
+        if name != header::HOST && name != header::CONTENT_LENGTH {
+            upstream_request = upstream_request.header(name, value);
+        }
+    }
+    upstream_request = upstream_request.header(header::HOST, gateway.upstream_host.clone());
+
+    let upstream_response = match upstream_request.body(body).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            warn!("MCP HTTP upstream request failed: {}", e);
+            return StatusCode::BAD_GATEWAY.into_response();
+        }
+    };
+
+    // Remember who owns a newly created Streamable HTTP session, and keep its
+    // slot until it is closed
+    let new_session = upstream_response
+        .headers()
+        .get(MCP_SESSION_ID_HEADER)
+        .and_then(|v| v.to_str().ok());
+    if let Some(new_session) = new_session {
+        if let Some(client) = &client {
+            let _ = SESSION_CLIENTS.bind(new_session, client);
+        }
+        if let Some(permit) = slot.take() {
+            gateway.limiter.hold(new_session, permit);
+        }
+    }
+    // A session that failed to open gives its slot straight back
+    if !upstream_response.status().is_success() {
+        slot = None;
+    }
+    if is_delete && upstream_response.status().is_success() {
+        if let Some(session_id) = &session_id {
+            gateway.limiter.release(session_id);
+            SESSION_CLIENTS.remove(session_id);
+            RESOURCE_SUBSCRIPTIONS.remove_session(session_id);
+            MCP_LOGGING.remove_session(session_id);
+        }
+    }
+
+    let mut response = Response::builder().status(upstream_response.status());
+    for (name, value) in upstream_response.headers().iter() {
+        if name != header::TRANSFER_ENCODING && name != header::CONNECTION {
+            response = response.header(name, value);
+        }
+    }
+
+    // Stream the body through so SSE responses are delivered as they arrive.
+    // An SSE session lasts as long as its stream, which holds the slot until
+    // the client disconnects.
+    let body = upstream_response.bytes_stream().map(move |chunk| {
+        let _slot = &slot;
+        chunk
+    });
+    response
+        .body(Body::from_stream(body))
+        .unwrap_or_else(|_| StatusCode::BAD_GATEWAY.into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_mcp_sdk::mcp_server::InMemorySessionStore;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_transport_defaults_to_stdio() {
+        assert_eq!(
+            parse_transport_args(args(&["omnisearch-mcp"])).unwrap(),
+            TransportMode::Stdio
+        );
+    }
+
+    #[test]
+    fn test_transport_flag_forms() {
+        assert_eq!(
+            parse_transport_args(args(&["omnisearch-mcp", "--transport", "http"])).unwrap(),
+            TransportMode::Http
+        );
+        assert_eq!(
+            parse_transport_args(args(&["omnisearch-mcp", "--transport=HTTP"])).unwrap(),
+            TransportMode::Http
+        );
+        assert!(parse_transport_args(args(&["omnisearch-mcp", "--transport"])).is_err());
+        assert!(parse_transport_args(args(&["omnisearch-mcp", "--transport", "ws"])).is_err());
+    }
+
+    #[test]
+    fn test_request_session_id() {
+        let mut headers = HeaderMap::new();
+        let uri: Uri = "/messages?sessionId=abc-123".parse().unwrap();
+        assert_eq!(
+            request_session_id(&uri, &headers),
+            Some("abc-123".to_string())
+        );
+
+        headers.insert(MCP_SESSION_ID_HEADER, "from-header".parse().unwrap());
+        let uri: Uri = "/mcp".parse().unwrap();
+        assert_eq!(
+            request_session_id(&uri, &headers),
+            Some("from-header".to_string())
+        );
+
+        assert_eq!(request_session_id(&uri, &HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_opens_session() {
+        let mut headers = HeaderMap::new();
+        assert!(opens_session(&Method::POST, "/mcp", &headers));
+        assert!(opens_session(&Method::GET, "/sse", &headers));
+        assert!(!opens_session(&Method::POST, "/messages", &headers));
+
+        headers.insert(MCP_SESSION_ID_HEADER, "abc".parse().unwrap());
+        assert!(!opens_session(&Method::POST, "/mcp", &headers));
+    }
+
+    #[tokio::test]
+    async fn test_connection_limiter_reserves_slots() {
+        let sessions: Arc<dyn SessionStore> = Arc::new(InMemorySessionStore::new());
+        let limiter = Arc::new(ConnectionLimiter::new(2, sessions));
+
+        // Concurrent reservations never hand out more slots than the limit
+        let attempts = (0..10).map(|_| {
+            let limiter = Arc::clone(&limiter);
+            tokio::spawn(async move { limiter.reserve().await })
+        });
+        let mut permits = Vec::new();
+        for attempt in futures::future::join_all(attempts).await {
+            permits.extend(attempt.unwrap());
+        }
+        assert_eq!(permits.len(), 2);
+        assert!(limiter.reserve().await.is_none());
+
+        // Dropping a reservation, as a failed `initialize` does, frees its slot
+        permits.pop();
+        let permit = limiter.reserve().await.unwrap();
+
+        // A session the runtime no longer has gives its slot back
+        limiter.hold("gone", permit);
+        assert!(limiter.reserve().await.is_some());
+    }
+
+    struct TestHandler;
+
+    #[async_trait::async_trait]
+    impl ServerHandler for TestHandler {}
+
+    #[tokio::test]
+    async fn test_upstream_refuses_requests_that_skip_the_gateway() {
+        let details = InitializeResult {
+            server_info: rust_mcp_sdk::schema::Implementation {
+                name: "omnisearch-mcp-test".to_string(),
+                version: "0.0.0".to_string(),
+                title: None,
+            },
+            capabilities: Default::default(),
+            meta: None,
+            instructions: None,
+            protocol_version: rust_mcp_sdk::schema::LATEST_PROTOCOL_VERSION.to_string(),
+        };
+        let upstream = Upstream::start(details, TestHandler).await.unwrap();
+        let initialize = |host: Option<&HeaderValue>| {
+            let mut request = reqwest::Client::new()
+                .post(format!("{}{}", upstream.url, STREAMABLE_HTTP_ENDPOINT))
+                .header(header::CONTENT_TYPE, "application/json")
+                .header(header::ACCEPT, "application/json, text/event-stream")
+                .body(
+                    json!({
+                        "jsonrpc": "2.0",
+                        "id": 1,
+                        "method": "initialize",
+                        "params": {
+                            "protocolVersion": rust_mcp_sdk::schema::LATEST_PROTOCOL_VERSION,
+                            "capabilities": {},
+                            "clientInfo": { "name": "test", "version": "1.0" }
+                        }
+                    })
+                    .to_string(),
+                );
+            if let Some(host) = host {
+                request = request.header(header::HOST, host.clone());
+            }
+            request.send()
+        };
+
+        for sse in [false, true] {
+            let direct = if sse {
+                reqwest::get(format!("{}{}", upstream.url, SSE_ENDPOINT))
+                    .await
+                    .unwrap()
+            } else {
+                initialize(None).await.unwrap()
+            };
+            assert_eq!(direct.status(), StatusCode::FORBIDDEN);
+        }
+        assert!(upstream.sessions.keys().await.is_empty());
+
+        let through_gateway = initialize(Some(&upstream.host)).await.unwrap();
+        assert_eq!(through_gateway.status(), StatusCode::OK);
+        upstream.runtime.graceful_shutdown(None);
+    }
+
+    #[tokio::test]
+    async fn test_connection_limiter_release() {
+        let sessions: Arc<dyn SessionStore> = Arc::new(InMemorySessionStore::new());
+        let limiter = ConnectionLimiter::new(1, sessions);
+
+        let permit = limiter.reserve().await.unwrap();
+        limiter.hold("abc", permit);
+        limiter.release("abc");
+        assert!(limiter.reserve().await.is_some());
+    }
+}
@@ -0,0 +1,418 @@
+//! Tests for HTTP transport authentication using locally minted tokens
+
+use async_trait::async_trait;
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use omnisearch_mcp::config::{AuthConfig, JwtConfig, StaticTokenConfig};
+use omnisearch_mcp::server::auth::{
+    client_for_session, AuthError, AuthMethod, Authenticator, ClientIdentity,
+};
+use omnisearch_mcp::server::call_tool;
+use omnisearch_mcp::server::transport::serve_http_on;
+use rust_mcp_sdk::mcp_server::ServerHandler;
+use rust_mcp_sdk::schema::{
+    schema_utils::{CallToolError, UnknownTool},
+    CallToolRequest, CallToolResult, ContentBlock, Implementation, InitializeResult,
+    ServerCapabilities, ServerCapabilitiesTools, TextContent, LATEST_PROTOCOL_VERSION,
+};
+use rust_mcp_sdk::McpServer;
+use serde_json::json;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::TcpListener;
+
+const SIGNING_SECRET: &[u8] = b"omnisearch-test-signing-secret";
+const SIGNING_SECRET_B64: &str = "b21uaXNlYXJjaC10ZXN0LXNpZ25pbmctc2VjcmV0";
+const ISSUER: &str = "https://auth.example.com";
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn mint_token(subject: &str, issuer: &str, expires_at: u64) -> String {
+    let mut header = Header::new(Algorithm::HS256);
+    header.kid = Some("test-key".to_string());
+    encode(
+        &header,
+        &json!({ "sub": subject, "iss": issuer, "exp": expires_at }),
+        &EncodingKey::from_secret(SIGNING_SECRET),
+    )
+    .unwrap()
+}
+
+fn write_jwks() -> tempfile::NamedTempFile {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    let jwks = json!({
+        "keys": [{
+            "kty": "oct",
+            "kid": "test-key",
+            "alg": "HS256",
+            "k": SIGNING_SECRET_B64,
+        }]
+    });
+    write!(file, "{}", jwks).unwrap();
+    file
+}
+
+async fn authenticator(jwks: &tempfile::NamedTempFile) -> Authenticator {
+    let config = AuthConfig {
+        enabled: true,
+        tokens: vec![StaticTokenConfig {
+            client_id: "ci-bot".to_string(),
+            token: "static-secret".to_string(),
+        }],
+        jwt: Some(JwtConfig {
+            jwks_path: Some(jwks.path().to_string_lossy().to_string()),
+            jwks_url: None,
+            jwks_refresh_seconds: 3600,
+            algorithms: Vec::new(),
+            issuer: Some(ISSUER.to_string()),
+            audience: None,
+            client_id_claim: "sub".to_string(),
+        }),
+    };
+    Authenticator::from_config(&config).await.unwrap().unwrap()
+}
+
+#[tokio::test]
+async fn test_jwt_authentication() {
+    let jwks = write_jwks();
+    let auth = authenticator(&jwks).await;
+
+    let token = mint_token("alice", ISSUER, now() + 300);
+    let identity = auth.authenticate(&token).await.unwrap();
+    assert_eq!(identity.client_id, "alice");
+    assert_eq!(identity.method, AuthMethod::Jwt);
+
+    let identity = auth.authenticate("static-secret").await.unwrap();
+    assert_eq!(identity.client_id, "ci-bot");
+    assert_eq!(identity.method, AuthMethod::StaticToken);
+}
+
+#[tokio::test]
+async fn test_jwt_rejections() {
+    let jwks = write_jwks();
+    let auth = authenticator(&jwks).await;
+
+    let expired = mint_token("alice", ISSUER, now() - 3600);
+    assert!(matches!(
+        auth.authenticate(&expired).await,
+        Err(AuthError::InvalidToken(_))
+    ));
+
+    let wrong_issuer = mint_token("alice", "https://evil.example.com", now() + 300);
+    assert!(matches!(
+        auth.authenticate(&wrong_issuer).await,
+        Err(AuthError::InvalidToken(_))
+    ));
+
+    let forged = encode(
+        &Header {
+            kid: Some("test-key".to_string()),
+            ..Header::new(Algorithm::HS256)
+        },
+        &json!({ "sub": "mallory", "iss": ISSUER, "exp": now() + 300 }),
+        &EncodingKey::from_secret(b"not-the-real-secret"),
+    )
+    .unwrap();
+    assert!(matches!(
+        auth.authenticate(&forged).await,
+        Err(AuthError::InvalidToken(_))
+    ));
+}
+
+fn jwt_config(jwks: &tempfile::NamedTempFile, algorithms: &[&str]) -> AuthConfig {
+    AuthConfig {
+        enabled: true,
+        tokens: Vec::new(),
+        jwt: Some(JwtConfig {
+            jwks_path: Some(jwks.path().to_string_lossy().to_string()),
+            jwks_url: None,
+            jwks_refresh_seconds: 3600,
+            algorithms: algorithms.iter().map(|a| a.to_string()).collect(),
+            issuer: Some(ISSUER.to_string()),
+            audience: None,
+            client_id_claim: "sub".to_string(),
+        }),
+    }
+}
+
+fn sign(algorithm: Algorithm, kid: &str, secret: &[u8]) -> String {
+    encode(
+        &Header {
+            kid: Some(kid.to_string()),
+            ..Header::new(algorithm)
+        },
+        &json!({ "sub": "alice", "iss": ISSUER, "exp": now() + 300 }),
+        &EncodingKey::from_secret(secret),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_jwt_algorithm_comes_from_key_not_token() {
+    let jwks = write_jwks();
+    let auth = authenticator(&jwks).await;
+
+    // The key is pinned to HS256, so a token claiming HS512 is refused
+    let token = sign(Algorithm::HS512, "test-key", SIGNING_SECRET);
+    assert!(matches!(
+        auth.authenticate(&token).await,
+        Err(AuthError::InvalidToken(_))
+    ));
+
+    // A key pinned to an algorithm that is not configured is unusable
+    let auth = Authenticator::from_config(&jwt_config(&jwks, &["RS256"]))
+        .await
+        .unwrap()
+        .unwrap();
+    let token = sign(Algorithm::HS256, "test-key", SIGNING_SECRET);
+    assert!(auth.authenticate(&token).await.is_err());
+}
+
+#[tokio::test]
+async fn test_jwt_key_without_alg_uses_configured_algorithms() {
+    let mut jwks = tempfile::NamedTempFile::new().unwrap();
+    let keys = json!({ "keys": [{ "kty": "oct", "kid": "test-key", "k": SIGNING_SECRET_B64 }] });
+    write!(jwks, "{}", keys).unwrap();
+
+    // Without a configured algorithm there is nothing to check the token against
+    let auth = Authenticator::from_config(&jwt_config(&jwks, &[]))
+        .await
+        .unwrap()
+        .unwrap();
+    let token = sign(Algorithm::HS256, "test-key", SIGNING_SECRET);
+    assert!(auth.authenticate(&token).await.is_err());
+
+    let auth = Authenticator::from_config(&jwt_config(&jwks, &["HS256"]))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(auth.authenticate(&token).await.is_ok());
+    let token = sign(Algorithm::HS384, "test-key", SIGNING_SECRET);
+    assert!(auth.authenticate(&token).await.is_err());
+
+    assert!(Authenticator::from_config(&jwt_config(&jwks, &["HS999"]))
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_jwt_reloads_jwks_for_unknown_key() {
+    let jwks = write_jwks();
+    let auth = authenticator(&jwks).await;
+
+    const ROTATED_SECRET: &[u8] = b"omnisearch-rotated-signing-secret";
+    let token = sign(Algorithm::HS256, "rotated-key", ROTATED_SECRET);
+
+    // The key is published after the server started
+    let rotated = json!({
+        "keys": [{
+            "kty": "oct",
+            "kid": "rotated-key",
+            "alg": "HS256",
+            "k": "b21uaXNlYXJjaC1yb3RhdGVkLXNpZ25pbmctc2VjcmV0",
+        }]
+    });
+    std::fs::write(jwks.path(), rotated.to_string()).unwrap();
+
+    let identity = auth.authenticate(&token).await.unwrap();
+    assert_eq!(identity.client_id, "alice");
+}
+
+#[tokio::test]
+async fn test_authenticated_tool_errors_match_anonymous() {
+    let client = ClientIdentity {
+        client_id: "tool-errors".to_string(),
+        method: AuthMethod::StaticToken,
+    };
+
+    // An unknown tool is a protocol error whether or not the client authenticated
+    let anonymous = call_tool("search_no-such-provider", None, None)
+        .await
+        .unwrap_err();
+    let authenticated = call_tool("search_no-such-provider", None, Some(&client))
+        .await
+        .unwrap_err();
+    assert!(anonymous.0.is::<UnknownTool>());
+    assert!(authenticated.0.is::<UnknownTool>());
+    assert_eq!(authenticated.to_string(), anonymous.to_string());
+}
+
+struct WhoAmIHandler;
+
+#[async_trait]
+impl ServerHandler for WhoAmIHandler {
+    async fn handle_call_tool_request(
+        &self,
+        _request: CallToolRequest,
+        runtime: &dyn McpServer,
+    ) -> Result<CallToolResult, CallToolError> {
+        let client = runtime
+            .session_id()
+            .and_then(|id| client_for_session(&id))
+            .map(|c| c.client_id)
+            .unwrap_or_else(|| "anonymous".to_string());
+        Ok(CallToolResult {
+            content: vec![ContentBlock::TextContent(TextContent::new(
+                format!("client={}", client),
+                None,
+                None,
+            ))],
+            is_error: None,
+            meta: None,
+            structured_content: None,
+        })
+    }
+}
+
+fn server_details() -> InitializeResult {
+    InitializeResult {
+        server_info: Implementation {
+            name: "omnisearch-mcp-test".to_string(),
+            version: "0.0.0".to_string(),
+            title: None,
+        },
+        capabilities: ServerCapabilities {
+            tools: Some(ServerCapabilitiesTools { list_changed: None }),
+            ..Default::default()
+        },
+        meta: None,
+        instructions: None,
+        protocol_version: LATEST_PROTOCOL_VERSION.to_string(),
+    }
+}
+
+async fn start_server(auth: Authenticator) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_http_on(
+        listener,
+        server_details(),
+        WhoAmIHandler,
+        10,
+        Some(Arc::new(auth)),
+    ));
+    addr
+}
+
+async fn post(
+    addr: SocketAddr,
+    token: Option<&str>,
+    session: Option<&str>,
+    body: serde_json::Value,
+) -> reqwest::Response {
+    let mut request = reqwest::Client::new()
+        .post(format!("http://{}/mcp", addr))
+        .header("content-type", "application/json")
+        .header("accept", "application/json, text/event-stream")
+        .timeout(Duration::from_secs(5))
+        .body(body.to_string());
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    if let Some(session) = session {
+        request = request
+            .header("mcp-session-id", session)
+            .header("mcp-protocol-version", LATEST_PROTOCOL_VERSION);
+    }
+    request.send().await.unwrap()
+}
+
+fn initialize_request() -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": LATEST_PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": { "name": "test", "version": "1.0" }
+        }
+    })
+}
+
+#[tokio::test]
+async fn test_http_requires_bearer_token() {
+    let jwks = write_jwks();
+    let addr = start_server(authenticator(&jwks).await).await;
+
+    let response = post(addr, None, None, initialize_request()).await;
+    assert_eq!(response.status(), 401);
+    assert!(response
+        .headers()
+        .get("www-authenticate")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .starts_with("Bearer"));
+
+    let response = post(addr, Some("wrong-token"), None, initialize_request()).await;
+    assert_eq!(response.status(), 401);
+
+    // Health checks stay open
+    let response = reqwest::get(format!("http://{}/health", addr))
+        .await
+        .unwrap();
+    assert_ne!(response.status(), 401);
+}
+
+#[tokio::test]
+async fn test_http_attaches_client_identity_to_session() {
+    let jwks = write_jwks();
+    let addr = start_server(authenticator(&jwks).await).await;
+    let token = mint_token("bob", ISSUER, now() + 300);
+
+    let response = post(addr, Some(&token), None, initialize_request()).await;
+    assert_eq!(response.status(), 200);
+    let session = response
+        .headers()
+        .get("mcp-session-id")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    response.text().await.unwrap();
+
+    post(
+        addr,
+        Some(&token),
+        Some(&session),
+        json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+    )
+    .await;
+
+    let response = post(
+        addr,
+        Some(&token),
+        Some(&session),
+        json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": { "name": "whoami", "arguments": {} }
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), 200);
+    assert!(response.text().await.unwrap().contains("client=bob"));
+
+    // Another client cannot ride on bob's session
+    let response = post(
+        addr,
+        Some("static-secret"),
+        Some(&session),
+        json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "tools/call",
+            "params": { "name": "whoami", "arguments": {} }
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), 403);
+}
@@ -18,6 +18,13 @@ async fn test_baidu_provider_comprehensive_search() {
             "stackoverflow.com".to_string(),
         ]),
         exclude_domains: Some(vec!["reddit.com".to_string()]),
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -35,7 +42,7 @@ async fn test_baidu_provider_comprehensive_search() {
         Err(e) => {
             // Validate error handling
             match e.error_type {
-                ErrorType::ApiError => {
+                ErrorType::ApiError | ErrorType::Authentication => {
                     // Expected when API key is missing
                     assert!(
                         e.message.contains("Missing SerpApi API key")
@@ -62,6 +69,10 @@ async fn test_baidu_provider_comprehensive_search() {
                             || e.message.contains("API internal error")
                     );
                 }
+                _ => {
+                    // Network failures, timeouts and the like
+                    assert!(!e.message.is_empty());
+                }
             }
         }
     }
@@ -77,6 +88,13 @@ async fn test_baidu_provider_edge_cases() {
         limit: Some(1),
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -96,6 +114,13 @@ async fn test_baidu_provider_edge_cases() {
         limit: Some(10), // High limit
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -122,6 +147,13 @@ async fn test_baidu_provider_edge_cases() {
             "stackoverflow.com".to_string(),
             "reddit.com".to_string(),
         ]),
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -148,6 +180,13 @@ async fn test_baidu_provider_error_scenarios() {
             limit: Some(1),
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
         // Empty domains scenario
         BaseSearchParams {
@@ -155,6 +194,13 @@ async fn test_baidu_provider_error_scenarios() {
             limit: Some(1),
             include_domains: Some(vec![]),
             exclude_domains: Some(vec![]),
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
         // Very long query scenario
         BaseSearchParams {
@@ -162,6 +208,13 @@ async fn test_baidu_provider_error_scenarios() {
             limit: Some(1),
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
     ];
 
@@ -15,6 +15,13 @@ async fn test_baidu_provider_missing_api_key_error() {
         limit: Some(5),
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -49,6 +56,13 @@ async fn test_baidu_provider_network_error_handling() {
         limit: Some(0),                // Invalid limit
         include_domains: Some(vec![]), // Empty domains
         exclude_domains: Some(vec![]), // Empty domains
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -87,6 +101,13 @@ async fn test_baidu_provider_empty_query_handling() {
         limit: None,
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
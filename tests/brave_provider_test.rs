@@ -0,0 +1,440 @@
+//! Tests for the Brave Search provider against a mock Brave API
+#![cfg(feature = "brave")]
+
+use mockito::{Matcher, Server};
+use omnisearch_mcp::{
+    common::types::{
+        BaseSearchParams, ErrorType, ImageResult, NewsResult, ResultDetails, SafeSearch,
+        SearchFilter, SearchProvider, SearchVertical, TimeRange, VideoResult,
+    },
+    config::BraveProviderConfig,
+    providers::brave::BraveSearchProvider,
+};
+use std::time::Duration;
+
+fn provider(base_url: &str, goggles: Option<&str>) -> BraveSearchProvider {
+    BraveSearchProvider::with_config(BraveProviderConfig {
+        enabled: true,
+        api_key: Some("test-key".to_string()),
+        rate_limit: 100,
+        timeout_seconds: 5,
+        base_url: Some(base_url.to_string()),
+        goggles: goggles.map(str::to_string),
+    })
+}
+
+fn params(query: &str) -> BaseSearchParams {
+    BaseSearchParams {
+        query: query.to_string(),
+        limit: Some(5),
+        include_domains: None,
+        exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
+    }
+}
+
+#[tokio::test]
+async fn test_brave_web_search() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/web/search")
+        .match_header("x-subscription-token", "test-key")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("q".into(), "rust".into()),
+            Matcher::UrlEncoded("count".into(), "5".into()),
+            Matcher::UrlEncoded("freshness".into(), "pw".into()),
+            Matcher::UrlEncoded("country".into(), "DE".into()),
+            Matcher::UrlEncoded("search_lang".into(), "pt-br".into()),
+            Matcher::UrlEncoded("safesearch".into(), "strict".into()),
+            Matcher::UrlEncoded("goggles".into(), "https://example.com/tech.goggle".into()),
+        ]))
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "type": "search",
+                "web": {
+                    "results": [
+                        {
+                            "title": "The Rust Programming Language",
+                            "url": "https://www.rust-lang.org/",
+                            "description": "A language empowering everyone.",
+                            "page_age": "2024-03-05T10:30:00",
+                            "language": "en",
+                            "profile": {"name": "Rust"},
+                            "meta_url": {"hostname": "www.rust-lang.org"},
+                            "thumbnail": {"src": "https://imgs.search.brave.com/rust.png"},
+                            "extra_snippets": ["Fast, reliable, productive."]
+                        },
+                        {
+                            "title": "Rust on Wikipedia",
+                            "url": "https://en.wikipedia.org/wiki/Rust",
+                            "description": "Rust is a programming language.",
+                            "age": "2 days ago",
+                            "meta_url": {"hostname": "en.wikipedia.org"}
+                        }
+                    ]
+                }
+            }"#,
+        )
+        .create_async()
+        .await;
+
+    let mut search = params("rust");
+    search.time_range = Some(TimeRange::Week);
+    search.country = Some("de".to_string());
+    search.language = Some("pt-BR".to_string());
+    search.safe_search = Some(SafeSearch::Strict);
+
+    let results = provider(&server.url(), Some("https://example.com/tech.goggle"))
+        .search(search)
+        .await
+        .unwrap();
+    mock.assert_async().await;
+
+    assert_eq!(results.len(), 2);
+    let first = &results[0];
+    assert_eq!(first.title, "The Rust Programming Language");
+    assert_eq!(first.url, "https://www.rust-lang.org/");
+    assert_eq!(first.snippet, "A language empowering everyone.");
+    assert_eq!(first.source_provider, "brave");
+    assert_eq!(first.published_at.as_deref(), Some("2024-03-05T10:30:00Z"));
+    assert_eq!(first.language.as_deref(), Some("en"));
+    assert_eq!(first.site_name.as_deref(), Some("Rust"));
+    assert_eq!(
+        first.thumbnail_url.as_deref(),
+        Some("https://imgs.search.brave.com/rust.png")
+    );
+    assert_eq!(
+        first.extra["extra_snippets"],
+        serde_json::json!(["Fast, reliable, productive."])
+    );
+    assert!(first.details.is_none());
+
+    let second = &results[1];
+    assert_eq!(second.published_at, None);
+    assert_eq!(second.extra["age"], "2 days ago");
+    assert_eq!(second.site_name.as_deref(), Some("en.wikipedia.org"));
+}
+
+#[tokio::test]
+async fn test_brave_news_search() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/news/search")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("q".into(), "rust release".into()),
+            Matcher::UrlEncoded("freshness".into(), "2024-01-01to2024-06-30".into()),
+            Matcher::UrlEncoded("goggles".into(), "https://example.com/news.goggle".into()),
+        ]))
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "type": "news",
+                "results": [
+                    {
+                        "title": "Rust 1.80 released",
+                        "url": "https://blog.rust-lang.org/2024/07/25/Rust-1.80.0.html",
+                        "description": "The Rust team is happy to announce a new version.",
+                        "page_age": "2024-06-25T00:00:00",
+                        "meta_url": {"hostname": "blog.rust-lang.org"}
+                    }
+                ]
+            }"#,
+        )
+        .create_async()
+        .await;
+
+    let mut search = params("rust release");
+    search.vertical = Some(SearchVertical::News);
+    search.time_range = Some(TimeRange::Custom {
+        start: Some("2024-01-01".to_string()),
+        end: Some("2024-06-30".to_string()),
+    });
+
+    let results = provider(&server.url(), Some("https://example.com/news.goggle"))
+        .search(search)
+        .await
+        .unwrap();
+    mock.assert_async().await;
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(
+        results[0].published_at.as_deref(),
+        Some("2024-06-25T00:00:00Z")
+    );
+    assert_eq!(
+        results[0].details,
+        Some(ResultDetails::News(NewsResult {
+            publisher: Some("blog.rust-lang.org".to_string()),
+        }))
+    );
+}
+
+#[tokio::test]
+async fn test_brave_image_search() {
+    let mut server = Server::new_async().await;
+    // Image search has no offset, so the first page is fetched along with the second
+    let mock = server
+        .mock("GET", "/images/search")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("count".into(), "2".into()),
+            Matcher::UrlEncoded("safesearch".into(), "strict".into()),
+        ]))
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "type": "images",
+                "results": [
+                    {
+                        "title": "Ferris the crab",
+                        "url": "https://rustacean.net/",
+                        "properties": {"url": "https://rustacean.net/assets/ferris.png"}
+                    },
+                    {
+                        "title": "Rust logo",
+                        "url": "https://www.rust-lang.org/logos",
+                        "thumbnail": {"src": "https://imgs.search.brave.com/logo.png"},
+                        "properties": {
+                            "url": "https://www.rust-lang.org/logos/rust-logo.png",
+                            "width": 512,
+                            "height": 512
+                        }
+                    }
+                ]
+            }"#,
+        )
+        .create_async()
+        .await;
+
+    let mut search = params("rust logo");
+    search.vertical = Some(SearchVertical::Images);
+    search.limit = Some(1);
+    search.offset = Some(1);
+    search.safe_search = Some(SafeSearch::Moderate);
+
+    let results = provider(&server.url(), Some("https://example.com/tech.goggle"))
+        .search(search)
+        .await
+        .unwrap();
+    mock.assert_async().await;
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(
+        results[0].url,
+        "https://www.rust-lang.org/logos/rust-logo.png"
+    );
+    assert_eq!(
+        results[0].details,
+        Some(ResultDetails::Image(ImageResult {
+            width: Some(512),
+            height: Some(512),
+            page_url: Some("https://www.rust-lang.org/logos".to_string()),
+        }))
+    );
+}
+
+#[tokio::test]
+async fn test_brave_video_search_pages_by_count() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/videos/search")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("count".into(), "5".into()),
+            Matcher::UrlEncoded("offset".into(), "2".into()),
+        ]))
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "type": "videos",
+                "results": [
+                    {
+                        "title": "Rust in 100 seconds",
+                        "url": "https://www.youtube.com/watch?v=5C_HPTJg5ek",
+                        "description": "Learn the basics of Rust.",
+                        "video": {"duration": "1:02:05", "creator": "Fireship"}
+                    }
+                ]
+            }"#,
+        )
+        .create_async()
+        .await;
+
+    let mut search = params("rust");
+    search.vertical = Some(SearchVertical::Videos);
+    search.offset = Some(10);
+
+    let results = provider(&server.url(), None).search(search).await.unwrap();
+    mock.assert_async().await;
+
+    assert_eq!(
+        results[0].details,
+        Some(ResultDetails::Video(VideoResult {
+            duration_seconds: Some(3725),
+            channel: Some("Fireship".to_string()),
+        }))
+    );
+}
+
+// A web search response with `count` numbered results
+fn web_results(count: usize) -> String {
+    let results: Vec<_> = (0..count)
+        .map(|i| {
+            serde_json::json!({
+                "title": format!("Result {}", i),
+                "url": format!("https://example.com/{}", i),
+            })
+        })
+        .collect();
+    serde_json::json!({ "type": "search", "web": { "results": results } }).to_string()
+}
+
+#[tokio::test]
+async fn test_brave_unaligned_offset_fits_in_one_page() {
+    let mut server = Server::new_async().await;
+    // Results 15 to 24 are the third to twelfth of the second page of thirteen
+    let mock = server
+        .mock("GET", "/web/search")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("count".into(), "13".into()),
+            Matcher::UrlEncoded("offset".into(), "1".into()),
+        ]))
+        .with_header("content-type", "application/json")
+        .with_body(web_results(13))
+        .create_async()
+        .await;
+
+    let mut search = params("rust");
+    search.limit = Some(10);
+    search.offset = Some(15);
+
+    let results = provider(&server.url(), None).search(search).await.unwrap();
+    mock.assert_async().await;
+
+    assert_eq!(results.len(), 10);
+    assert_eq!(results[0].title, "Result 2");
+    assert_eq!(results[9].title, "Result 11");
+}
+
+#[tokio::test]
+async fn test_brave_pages_end_at_tenth_page() {
+    let mut server = Server::new_async().await;
+    // Past the tenth page of ten, larger pages reach further
+    let mock = server
+        .mock("GET", "/web/search")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("count".into(), "11".into()),
+            Matcher::UrlEncoded("offset".into(), "9".into()),
+        ]))
+        .with_header("content-type", "application/json")
+        .with_body(web_results(11))
+        .create_async()
+        .await;
+
+    let provider = provider(&server.url(), None);
+    let mut search = params("rust");
+    search.limit = Some(10);
+    search.page_token = Some("100".to_string());
+    let results = provider.search(search.clone()).await.unwrap();
+    mock.assert_async().await;
+    assert_eq!(results.len(), 10);
+    assert_eq!(results[0].title, "Result 1");
+    assert_eq!(
+        provider.next_page_token(&search, &results),
+        Some("110".to_string())
+    );
+
+    // No token is issued for, and no request made past, the two hundredth result
+    search.page_token = Some("190".to_string());
+    assert_eq!(provider.next_page_token(&search, &results), None);
+    search.page_token = Some("200".to_string());
+    let error = provider.search(search).await.unwrap_err();
+    assert_eq!(error.error_type, ErrorType::InvalidInput);
+}
+
+#[tokio::test]
+async fn test_brave_rate_limit_error() {
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/web/search")
+        .match_query(Matcher::Any)
+        .with_status(429)
+        .with_header("retry-after", "30")
+        .with_body(r#"{"type": "ErrorResponse"}"#)
+        .create_async()
+        .await;
+
+    let error = provider(&server.url(), None)
+        .search(params("rust"))
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.error_type, ErrorType::RateLimit);
+    assert_eq!(error.provider, "brave");
+    assert_eq!(error.http_status, Some(429));
+    assert_eq!(error.retry_after, Some(Duration::from_secs(30)));
+}
+
+#[tokio::test]
+async fn test_brave_invalid_api_key() {
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/web/search")
+        .match_query(Matcher::Any)
+        .with_status(401)
+        .create_async()
+        .await;
+
+    let error = provider(&server.url(), None)
+        .search(params("rust"))
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.error_type, ErrorType::Authentication);
+}
+
+#[tokio::test]
+async fn test_brave_missing_api_key() {
+    let provider = BraveSearchProvider::with_config(BraveProviderConfig {
+        enabled: true,
+        api_key: None,
+        rate_limit: 100,
+        timeout_seconds: 5,
+        base_url: None,
+        goggles: None,
+    });
+
+    let error = provider.search(params("rust")).await.unwrap_err();
+    assert_eq!(error.error_type, ErrorType::Authentication);
+    assert!(error.message.contains("Missing Brave Search API key"));
+}
+
+#[test]
+fn test_brave_provider_metadata() {
+    let provider = provider("http://localhost", None);
+
+    assert_eq!(provider.name(), "brave");
+    assert!(provider.description().contains("Brave Search API"));
+
+    let capabilities = provider.capabilities();
+    assert_eq!(capabilities.max_limit, 20);
+    assert!(capabilities.serves(SearchVertical::News));
+    assert!(capabilities.serves(SearchVertical::Images));
+    assert!(capabilities.serves(SearchVertical::Videos));
+    assert!(!capabilities.serves(SearchVertical::Academic));
+
+    // Image search cannot be limited by date
+    let mut search = params("rust");
+    search.time_range = Some(TimeRange::Day);
+    assert!(provider.unsupported_filters(&search).is_empty());
+    search.vertical = Some(SearchVertical::Images);
+    assert_eq!(
+        provider.unsupported_filters(&search),
+        vec![SearchFilter::TimeRange]
+    );
+}
@@ -18,6 +18,13 @@ async fn test_brightdata_provider_comprehensive_search() {
             "stackoverflow.com".to_string(),
         ]),
         exclude_domains: Some(vec!["reddit.com".to_string()]),
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -35,7 +42,7 @@ async fn test_brightdata_provider_comprehensive_search() {
         Err(e) => {
             // Validate error handling
             match e.error_type {
-                ErrorType::ApiError => {
+                ErrorType::ApiError | ErrorType::Authentication => {
                     // Expected when credentials are missing
                     assert!(
                         e.message.contains("Missing Bright Data username")
@@ -63,6 +70,10 @@ async fn test_brightdata_provider_comprehensive_search() {
                             || e.message.contains("Provider internal error")
                     );
                 }
+                _ => {
+                    // Network failures, timeouts and the like
+                    assert!(!e.message.is_empty());
+                }
             }
         }
     }
@@ -78,6 +89,13 @@ async fn test_brightdata_provider_edge_cases() {
         limit: Some(1),
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -97,6 +115,13 @@ async fn test_brightdata_provider_edge_cases() {
         limit: Some(15), // High limit
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -127,6 +152,13 @@ async fn test_brightdata_provider_edge_cases() {
             "facebook.com".to_string(),
             "twitter.com".to_string(),
         ]),
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -153,6 +185,13 @@ async fn test_brightdata_provider_error_scenarios() {
             limit: Some(1),
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
         // Empty domains scenario
         BaseSearchParams {
@@ -160,6 +199,13 @@ async fn test_brightdata_provider_error_scenarios() {
             limit: Some(1),
             include_domains: Some(vec![]),
             exclude_domains: Some(vec![]),
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
         // Very long query scenario
         BaseSearchParams {
@@ -167,6 +213,13 @@ async fn test_brightdata_provider_error_scenarios() {
             limit: Some(1),
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
     ];
 
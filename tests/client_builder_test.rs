@@ -0,0 +1,173 @@
+//! Tests for building an `OmnisearchClient` without global state
+
+use async_trait::async_trait;
+use omnisearch_mcp::common::cache::CacheManager;
+use omnisearch_mcp::common::circuit_breaker::CircuitBreakerManager;
+use omnisearch_mcp::common::metrics::MetricsCollector;
+use omnisearch_mcp::common::rate_limiter::RateLimiterManager;
+use omnisearch_mcp::common::types::{
+    BaseSearchParams, ErrorType, ProviderError, SearchProvider, SearchResult,
+};
+use omnisearch_mcp::{Config, OmnisearchClient, SearchRequest};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+struct MockSearch {
+    name: &'static str,
+    fail: bool,
+    calls: Arc<AtomicUsize>,
+}
+
+impl MockSearch {
+    fn new(name: &'static str, fail: bool) -> (Box<Self>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = Box::new(Self {
+            name,
+            fail,
+            calls: Arc::clone(&calls),
+        });
+        (provider, calls)
+    }
+}
+
+#[async_trait]
+impl SearchProvider for MockSearch {
+    async fn search(&self, params: BaseSearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if self.fail {
+            return Err(ProviderError::new(
+                ErrorType::ApiError,
+                "Upstream unavailable".to_string(),
+                self.name.to_string(),
+                None,
+            ));
+        }
+        Ok(vec![SearchResult {
+            title: format!("{} result for {}", self.name, params.query),
+            url: format!("https://{}.example.com", self.name),
+            snippet: "Mock snippet".to_string(),
+            score: None,
+            source_provider: self.name.to_string(),
+            ..Default::default()
+        }])
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        "Mock search provider"
+    }
+}
+
+fn request(query: &str) -> SearchRequest {
+    SearchRequest::new(query).provider("tavily")
+}
+
+#[tokio::test]
+async fn test_builder_uses_only_custom_providers_without_config() {
+    let (provider, calls) = MockSearch::new("tavily", false);
+    let client = OmnisearchClient::builder()
+        .provider(provider)
+        .build()
+        .unwrap();
+
+    assert_eq!(client.available_providers(), vec!["tavily"]);
+    let response = client.search(request("rust")).await.unwrap();
+    assert_eq!(response.results[0].title, "tavily result for rust");
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    let error = OmnisearchClient::builder().build().err().unwrap();
+    assert!(error.message.contains("No search providers"));
+}
+
+#[tokio::test]
+async fn test_builder_creates_configured_providers() {
+    let mut config = Config::default();
+    config.providers.tavily.api_key = Some("test-key".to_string());
+    config.providers.exa.api_key = None;
+
+    let (provider, calls) = MockSearch::new("tavily", false);
+    let client = OmnisearchClient::builder()
+        .config(config)
+        .http_client(reqwest::Client::new())
+        .provider(provider)
+        .build()
+        .unwrap();
+
+    assert!(client.has_provider("duckduckgo"));
+    assert!(!client.has_provider("exa"));
+
+    // The custom provider replaces the built-in one of the same name
+    client.search(request("rust")).await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_builder_cache_and_rate_limiter() {
+    let mut config = Config::default();
+    config.cache.enabled = true;
+    config.rate_limiting.enabled = true;
+    config.providers.tavily.rate_limit = 1;
+
+    let (provider, calls) = MockSearch::new("tavily", false);
+    let client = OmnisearchClient::builder()
+        .config(config.clone())
+        .provider(provider)
+        .cache(Arc::new(CacheManager::with_config(&config.cache)))
+        .rate_limiter(Arc::new(RateLimiterManager::with_config(&config)))
+        .build()
+        .unwrap();
+
+    // The repeated query is served from the cache without using the rate limit
+    client.search(request("rust")).await.unwrap();
+    client.search(request("rust")).await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    let error = client.search(request("async")).await.unwrap_err();
+    assert_eq!(error.error_type, ErrorType::RateLimit);
+    assert_eq!(error.provider, "tavily");
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_builder_circuit_breaker_and_metrics() {
+    let mut config = Config::default();
+    config.circuit_breaker.enabled = true;
+    config.circuit_breaker.failure_threshold = 1;
+    config.circuit_breaker.timeout_seconds = 60;
+    config.metrics.enabled = true;
+
+    let metrics = Arc::new(MetricsCollector::with_config(&config.metrics));
+    let (provider, calls) = MockSearch::new("tavily", true);
+    let client = OmnisearchClient::builder()
+        .provider(provider)
+        .circuit_breaker(Arc::new(CircuitBreakerManager::with_config(
+            &config.circuit_breaker,
+        )))
+        .metrics(Arc::clone(&metrics))
+        .build()
+        .unwrap();
+
+    // The provider's own error comes through the breaker unchanged
+    let error = client.search(request("rust")).await.unwrap_err();
+    assert_eq!(error.error_type, ErrorType::ApiError);
+    assert_eq!(error.message, "Upstream unavailable");
+
+    let error = client.search(request("rust")).await.unwrap_err();
+    assert!(error.message.contains("Circuit breaker is open"));
+    assert_eq!(error.error_type, ErrorType::CircuitOpen);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    let stats = metrics.get_provider_stats("tavily").await.unwrap();
+    assert_eq!(stats.failed_requests, 2);
+}
+
+#[tokio::test]
+async fn test_initialize_with_existing_subscriber() {
+    let _ = tracing_subscriber::fmt::try_init();
+    // Must not panic now that a global subscriber is installed
+    let _ = omnisearch_mcp::initialize().await;
+    let _ = omnisearch_mcp::initialize().await;
+}
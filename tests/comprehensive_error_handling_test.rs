@@ -47,6 +47,13 @@ async fn test_all_providers_handle_missing_credentials() {
         limit: Some(10),
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     for provider in providers {
@@ -59,8 +66,8 @@ async fn test_all_providers_handle_missing_credentials() {
 
         if let Err(e) = result {
             assert!(
-                matches!(e.error_type, ErrorType::ApiError | ErrorType::ProviderError),
-                "Provider {} should return ApiError or ProviderError for missing credentials",
+                matches!(e.error_type, ErrorType::Authentication),
+                "Provider {} should return Authentication for missing credentials",
                 provider.name()
             );
         }
@@ -86,6 +93,13 @@ async fn test_empty_query_handling() {
         limit: Some(10),
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     for provider in providers {
@@ -101,7 +115,11 @@ async fn test_empty_query_handling() {
                 assert!(
                     matches!(
                         e.error_type,
-                        ErrorType::InvalidInput | ErrorType::ApiError | ErrorType::ProviderError
+                        ErrorType::InvalidInput
+                            | ErrorType::ApiError
+                            | ErrorType::ProviderError
+                            | ErrorType::Network
+                            | ErrorType::Timeout
                     ),
                     "Provider {} should handle empty query gracefully, got error: {:?}",
                     provider.name(),
@@ -122,6 +140,13 @@ async fn test_invalid_limit_handling() {
         limit: Some(0),
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     let result = provider.search(params).await;
@@ -129,9 +154,16 @@ async fn test_invalid_limit_handling() {
     match result {
         Ok(results) => assert!(results.is_empty() || results.len() <= 1),
         Err(e) => {
-            // Accept either InvalidInput or ApiError - provider may handle differently
+            // Accept either InvalidInput or ApiError - provider may handle differently;
+            // without a connection the request fails before either
             assert!(
-                matches!(e.error_type, ErrorType::InvalidInput | ErrorType::ApiError),
+                matches!(
+                    e.error_type,
+                    ErrorType::InvalidInput
+                        | ErrorType::ApiError
+                        | ErrorType::Network
+                        | ErrorType::Timeout
+                ),
                 "Expected InvalidInput or ApiError, got {:?}",
                 e.error_type
             );
@@ -144,6 +176,13 @@ async fn test_invalid_limit_handling() {
         limit: Some(100000),
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     let result = provider.search(params).await;
@@ -151,9 +190,16 @@ async fn test_invalid_limit_handling() {
     match result {
         Ok(results) => assert!(results.len() <= 100), // Reasonable maximum
         Err(e) => {
-            // Accept either InvalidInput or ApiError - provider may handle differently
+            // Accept either InvalidInput or ApiError - provider may handle differently;
+            // without a connection the request fails before either
             assert!(
-                matches!(e.error_type, ErrorType::InvalidInput | ErrorType::ApiError),
+                matches!(
+                    e.error_type,
+                    ErrorType::InvalidInput
+                        | ErrorType::ApiError
+                        | ErrorType::Network
+                        | ErrorType::Timeout
+                ),
                 "Expected InvalidInput or ApiError, got {:?}",
                 e.error_type
             );
@@ -180,6 +226,13 @@ async fn test_special_characters_in_query() {
             limit: Some(5),
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         };
 
         let result = provider.search(params).await;
@@ -191,7 +244,11 @@ async fn test_special_characters_in_query() {
                 assert!(
                     matches!(
                         e.error_type,
-                        ErrorType::InvalidInput | ErrorType::ApiError | ErrorType::ProviderError
+                        ErrorType::InvalidInput
+                            | ErrorType::ApiError
+                            | ErrorType::ProviderError
+                            | ErrorType::Network
+                            | ErrorType::Timeout
                     ),
                     "Should handle special characters gracefully in query: {}, got error: {:?}",
                     query,
@@ -220,6 +277,13 @@ async fn test_domain_filter_validation() {
             limit: Some(5),
             include_domains: Some(domains.iter().map(|s| s.to_string()).collect()),
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         };
 
         let result = provider.search(params).await;
@@ -245,6 +309,8 @@ fn test_provider_error_display_formatting() {
             error_type,
             message: "Test error message".to_string(),
             provider: "test-provider".to_string(),
+            http_status: None,
+            retry_after: None,
             source: None,
         };
 
@@ -258,6 +324,8 @@ fn test_provider_error_display_formatting() {
             error_type: ErrorType::ApiError,
             message: "Test error".to_string(),
             provider: "test-provider".to_string(),
+            http_status: None,
+            retry_after: None,
             source: Some(source_error),
         };
 
@@ -281,6 +349,13 @@ async fn test_concurrent_error_handling() {
         limit: Some(10),
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
     futures.push(provider.search(params1));
 
@@ -290,6 +365,13 @@ async fn test_concurrent_error_handling() {
         limit: Some(0),
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
     futures.push(provider.search(params2));
 
@@ -299,6 +381,13 @@ async fn test_concurrent_error_handling() {
         limit: Some(5),
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
     futures.push(provider.search(params3));
 
@@ -323,6 +412,8 @@ fn test_provider_error_creation_helpers() {
         error_type: ErrorType::ApiError,
         message: "API failed".to_string(),
         provider: "test-provider".to_string(),
+        http_status: None,
+        retry_after: None,
         source: None,
     };
     assert_eq!(api_error.error_type, ErrorType::ApiError);
@@ -331,6 +422,8 @@ fn test_provider_error_creation_helpers() {
         error_type: ErrorType::RateLimit,
         message: "Rate limit exceeded".to_string(),
         provider: "test-provider".to_string(),
+        http_status: None,
+        retry_after: None,
         source: None,
     };
     assert_eq!(rate_limit_error.error_type, ErrorType::RateLimit);
@@ -339,6 +432,8 @@ fn test_provider_error_creation_helpers() {
         error_type: ErrorType::InvalidInput,
         message: "Invalid input".to_string(),
         provider: "test-provider".to_string(),
+        http_status: None,
+        retry_after: None,
         source: None,
     };
     assert_eq!(invalid_input_error.error_type, ErrorType::InvalidInput);
@@ -356,6 +451,13 @@ async fn test_network_timeout_simulation() {
         limit: Some(10),
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     // Simulate very short timeout
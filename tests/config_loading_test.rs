@@ -0,0 +1,32 @@
+//! Tests for layering configuration files over the built-in defaults
+
+use omnisearch_mcp::config::Config;
+use std::io::Write;
+
+#[test]
+fn test_partial_config_file_keeps_defaults() {
+    let mut file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+    writeln!(
+        file,
+        "[server]\nhost = \"0.0.0.0\"\nport = 4000\nmax_connections = 25"
+    )
+    .unwrap();
+
+    let config = Config::load_from_path(file.path()).unwrap();
+    let defaults = Config::default();
+
+    assert_eq!(config.server.host, "0.0.0.0");
+    assert_eq!(config.server.port, 4000);
+    assert_eq!(config.server.max_connections, 25);
+    assert_eq!(config.cache.ttl_seconds, defaults.cache.ttl_seconds);
+    assert_eq!(
+        config.providers.duckduckgo.timeout_seconds,
+        defaults.providers.duckduckgo.timeout_seconds
+    );
+}
+
+#[test]
+fn test_missing_config_file_uses_defaults() {
+    let config = Config::load_from_path("does-not-exist.toml").unwrap();
+    assert_eq!(config.server.port, Config::default().server.port);
+}
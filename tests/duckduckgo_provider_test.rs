@@ -9,6 +9,13 @@ fn create_test_params(query: &str, limit: Option<u32>) -> BaseSearchParams {
         limit,
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     }
 }
 
@@ -48,6 +48,44 @@ fn test_error_type_display() {
     assert_eq!(format!("{}", ErrorType::ProviderError), "Provider Error");
 }
 
+#[test]
+fn test_provider_error_retryability() {
+    let error = |error_type| {
+        ProviderError::new(
+            error_type,
+            "Test error".to_string(),
+            "test_provider".to_string(),
+            None,
+        )
+    };
+
+    for error_type in [
+        ErrorType::RateLimit,
+        ErrorType::ProviderError,
+        ErrorType::Timeout,
+        ErrorType::Network,
+        ErrorType::Unavailable,
+    ] {
+        assert!(error(error_type).is_retryable());
+    }
+    for error_type in [
+        ErrorType::ApiError,
+        ErrorType::InvalidInput,
+        ErrorType::Authentication,
+        ErrorType::QuotaExhausted,
+        ErrorType::ParseError,
+        ErrorType::CircuitOpen,
+    ] {
+        assert!(!error(error_type).is_retryable());
+    }
+
+    let error = error(ErrorType::RateLimit)
+        .with_http_status(429)
+        .with_retry_after(Some(std::time::Duration::from_secs(30)));
+    assert_eq!(error.http_status, Some(429));
+    assert_eq!(error.retry_after, Some(std::time::Duration::from_secs(30)));
+}
+
 #[test]
 fn test_provider_error_display() {
     let error = ProviderError::new(
@@ -71,6 +109,7 @@ fn test_search_result_with_score() {
         snippet: "Test snippet".to_string(),
         score: Some(0.85),
         source_provider: "test_provider".to_string(),
+        ..Default::default()
     };
 
     assert_eq!(result.title, "Test Title");
@@ -88,6 +127,7 @@ fn test_search_result_without_score() {
         snippet: "Test snippet".to_string(),
         score: None,
         source_provider: "test_provider".to_string(),
+        ..Default::default()
     };
 
     assert_eq!(result.title, "Test Title");
@@ -104,6 +144,13 @@ fn test_base_search_params_with_all_fields() {
         limit: Some(10),
         include_domains: Some(vec!["example.com".to_string(), "test.com".to_string()]),
         exclude_domains: Some(vec!["exclude.com".to_string()]),
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     assert_eq!(params.query, "test query");
@@ -125,6 +172,13 @@ fn test_base_search_params_with_none_fields() {
         limit: None,
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     assert_eq!(params.query, "test query");
@@ -1,7 +1,7 @@
 //! Comprehensive tests for Exa provider to increase coverage
 
 use omnisearch_mcp::{
-    common::types::{BaseSearchParams, ErrorType, SearchProvider},
+    common::types::{BaseSearchParams, ErrorType, Pagination, SearchProvider, SearchResult},
     providers::exa::ExaSearchProvider,
 };
 
@@ -18,6 +18,13 @@ async fn test_exa_provider_comprehensive_search() {
             "stackoverflow.com".to_string(),
         ]),
         exclude_domains: Some(vec!["reddit.com".to_string()]),
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -35,7 +42,7 @@ async fn test_exa_provider_comprehensive_search() {
         Err(e) => {
             // Validate error handling
             match e.error_type {
-                ErrorType::ApiError => {
+                ErrorType::ApiError | ErrorType::Authentication => {
                     // Expected when API key is missing
                     assert!(
                         e.message.contains("Missing Exa API key")
@@ -62,6 +69,10 @@ async fn test_exa_provider_comprehensive_search() {
                             || e.message.contains("Provider internal error")
                     );
                 }
+                _ => {
+                    // Network failures, timeouts and the like
+                    assert!(!e.message.is_empty());
+                }
             }
         }
     }
@@ -77,6 +88,13 @@ async fn test_exa_provider_edge_cases() {
         limit: Some(1),
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -96,6 +114,13 @@ async fn test_exa_provider_edge_cases() {
         limit: Some(10), // High limit
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -126,6 +151,13 @@ async fn test_exa_provider_edge_cases() {
             "facebook.com".to_string(),
             "twitter.com".to_string(),
         ]),
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -152,6 +184,13 @@ async fn test_exa_provider_error_scenarios() {
             limit: Some(1),
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
         // Empty domains scenario
         BaseSearchParams {
@@ -159,6 +198,13 @@ async fn test_exa_provider_error_scenarios() {
             limit: Some(1),
             include_domains: Some(vec![]),
             exclude_domains: Some(vec![]),
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
         // Very long query scenario
         BaseSearchParams {
@@ -166,6 +212,13 @@ async fn test_exa_provider_error_scenarios() {
             limit: Some(1),
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
     ];
 
@@ -237,3 +290,18 @@ fn test_exa_provider_api_key_validation() {
     // This ensures the API key validation logic is tested
     // TODO: Implement API key validation test
 }
+
+#[test]
+fn test_exa_provider_has_no_pages() {
+    let provider = ExaSearchProvider::new();
+    assert_eq!(provider.capabilities().pagination, Pagination::None);
+
+    // A full page still has no page after it
+    let params = BaseSearchParams {
+        query: "rust".to_string(),
+        limit: Some(2),
+        ..Default::default()
+    };
+    let results = vec![SearchResult::default(); 2];
+    assert_eq!(provider.next_page_token(&params, &results), None);
+}
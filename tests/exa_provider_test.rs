@@ -13,6 +13,13 @@ async fn test_exa_provider_search() {
         limit: Some(3),
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     // Note: This test will fail if no Exa API key is configured
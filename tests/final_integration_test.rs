@@ -155,6 +155,13 @@ async fn test_provider_search_interface_compliance() {
             limit: Some(1),
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         };
 
         match provider.search(params).await {
@@ -220,6 +227,9 @@ fn validate_provider_error(
                 | omnisearch_mcp::common::types::ErrorType::InvalidInput
                 | omnisearch_mcp::common::types::ErrorType::RateLimit
                 | omnisearch_mcp::common::types::ErrorType::ProviderError
+                | omnisearch_mcp::common::types::ErrorType::Authentication
+                | omnisearch_mcp::common::types::ErrorType::Network
+                | omnisearch_mcp::common::types::ErrorType::Timeout
         ),
         "Provider error should have a valid error type"
     );
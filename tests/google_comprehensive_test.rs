@@ -18,6 +18,13 @@ async fn test_google_provider_comprehensive_search() {
             "stackoverflow.com".to_string(),
         ]),
         exclude_domains: Some(vec!["reddit.com".to_string()]),
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -35,7 +42,7 @@ async fn test_google_provider_comprehensive_search() {
         Err(e) => {
             // Validate error handling
             match e.error_type {
-                ErrorType::ApiError => {
+                ErrorType::ApiError | ErrorType::Authentication => {
                     // Expected when API key is missing
                     assert!(
                         e.message.contains("Missing Google API key")
@@ -46,6 +53,10 @@ async fn test_google_provider_comprehensive_search() {
                 ErrorType::RateLimit | ErrorType::InvalidInput | ErrorType::ProviderError => {
                     assert!(!e.message.is_empty());
                 }
+                _ => {
+                    // Network failures, timeouts and the like
+                    assert!(!e.message.is_empty());
+                }
             }
         }
     }
@@ -61,6 +72,13 @@ async fn test_google_provider_edge_cases() {
         limit: Some(1),
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -80,6 +98,13 @@ async fn test_google_provider_edge_cases() {
         limit: Some(10), // Reasonable limit for Google
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -103,6 +128,13 @@ async fn test_google_provider_edge_cases() {
             "reactjs.org".to_string(),
         ]),
         exclude_domains: Some(vec!["wikipedia.org".to_string(), "youtube.com".to_string()]),
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -129,6 +161,13 @@ async fn test_google_provider_error_scenarios() {
             limit: Some(1),
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
         // Empty domains scenario
         BaseSearchParams {
@@ -136,6 +175,13 @@ async fn test_google_provider_error_scenarios() {
             limit: Some(1),
             include_domains: Some(vec![]),
             exclude_domains: Some(vec![]),
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
         // Very long query scenario
         BaseSearchParams {
@@ -143,6 +189,13 @@ async fn test_google_provider_error_scenarios() {
             limit: Some(1),
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
     ];
 
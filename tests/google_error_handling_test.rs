@@ -1,7 +1,8 @@
 //! Tests for Google provider error handling scenarios
 
 use omnisearch_mcp::{
-    common::types::{BaseSearchParams, ErrorType, SearchProvider},
+    common::types::{BaseSearchParams, ErrorType, SearchProvider, SearchResult},
+    config::GoogleProviderConfig,
     providers::google::GoogleCustomSearchProvider,
 };
 
@@ -15,6 +16,13 @@ async fn test_google_provider_missing_credentials_error() {
         limit: Some(5),
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -51,7 +59,14 @@ async fn test_google_provider_invalid_parameters() {
         query: "".to_string(),         // Empty query
         limit: Some(0),                // Invalid limit
         include_domains: Some(vec![]), // Empty domains
-        exclude_domains: Some(vec![]), // Empty domains
+        exclude_domains: Some(vec![]), // Empty domains,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -77,6 +92,13 @@ async fn test_google_provider_extreme_limits() {
         limit: Some(100), // Very high limit
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -91,3 +113,31 @@ async fn test_google_provider_extreme_limits() {
         }
     }
 }
+
+#[tokio::test]
+async fn test_google_provider_stops_at_hundredth_result() {
+    let provider = GoogleCustomSearchProvider::with_config(GoogleProviderConfig {
+        enabled: true,
+        api_key: Some("test-key".to_string()),
+        search_engine_id: Some("test-engine".to_string()),
+        rate_limit: 100,
+        timeout_seconds: 5,
+    });
+    let params = |page_token: &str| BaseSearchParams {
+        query: "rust".to_string(),
+        limit: Some(10),
+        page_token: Some(page_token.to_string()),
+        ..Default::default()
+    };
+
+    // Custom Search returns nothing past its hundredth result
+    let results = vec![SearchResult::default(); 10];
+    assert_eq!(
+        provider.next_page_token(&params("80"), &results),
+        Some("90".to_string())
+    );
+    assert_eq!(provider.next_page_token(&params("90"), &results), None);
+
+    let error = provider.search(params("100")).await.unwrap_err();
+    assert_eq!(error.error_type, ErrorType::InvalidInput);
+}
@@ -0,0 +1,112 @@
+//! Tests for serving MCP over the HTTP transport
+
+use async_trait::async_trait;
+use omnisearch_mcp::server::transport::serve_http_on;
+use rust_mcp_sdk::mcp_server::ServerHandler;
+use rust_mcp_sdk::schema::{
+    Implementation, InitializeResult, ServerCapabilities, ServerCapabilitiesTools,
+    LATEST_PROTOCOL_VERSION,
+};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+struct TestHandler;
+
+#[async_trait]
+impl ServerHandler for TestHandler {}
+
+fn server_details() -> InitializeResult {
+    InitializeResult {
+        server_info: Implementation {
+            name: "omnisearch-mcp-test".to_string(),
+            version: "0.0.0".to_string(),
+            title: None,
+        },
+        capabilities: ServerCapabilities {
+            tools: Some(ServerCapabilitiesTools { list_changed: None }),
+            ..Default::default()
+        },
+        meta: None,
+        instructions: None,
+        protocol_version: LATEST_PROTOCOL_VERSION.to_string(),
+    }
+}
+
+async fn start_server(max_connections: usize) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_http_on(
+        listener,
+        server_details(),
+        TestHandler,
+        max_connections,
+        None,
+    ));
+    addr
+}
+
+async fn initialize(addr: SocketAddr) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(format!("http://{}/mcp", addr))
+        .header("content-type", "application/json")
+        .header("accept", "application/json, text/event-stream")
+        .timeout(Duration::from_secs(5))
+        .body(
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": LATEST_PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": { "name": "test", "version": "1.0" }
+                }
+            })
+            .to_string(),
+        )
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_http_transport_initializes_session() {
+    let addr = start_server(10).await;
+
+    let response = initialize(addr).await;
+    assert_eq!(response.status(), 200);
+    assert!(response.headers().contains_key("mcp-session-id"));
+
+    let body = response.text().await.unwrap();
+    assert!(body.contains("omnisearch-mcp-test"));
+}
+
+#[tokio::test]
+async fn test_http_transport_enforces_max_connections() {
+    let addr = start_server(1).await;
+
+    let first = initialize(addr).await;
+    assert_eq!(first.status(), 200);
+    first.text().await.unwrap();
+
+    let second = initialize(addr).await;
+    assert_eq!(second.status(), 503);
+    let body: serde_json::Value = second.json().await.unwrap();
+    assert!(body["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("Connection limit"));
+}
+
+#[tokio::test]
+async fn test_http_transport_health_endpoint() {
+    let addr = start_server(1).await;
+
+    let response = reqwest::get(format!("http://{}/health", addr))
+        .await
+        .unwrap();
+    assert!(response.status() == 200 || response.status() == 503);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body.get("status").is_some());
+}
@@ -29,13 +29,8 @@ fn test_handle_http_error_edge_cases() {
         "Internal server error",
     );
 
-    // Any error type is acceptable for 402
-    match error1.error_type {
-        ErrorType::ApiError
-        | ErrorType::ProviderError
-        | ErrorType::InvalidInput
-        | ErrorType::RateLimit => {} // Test passes if compilation succeeds,
-    }
+    assert_eq!(error1.error_type, ErrorType::QuotaExhausted);
+    assert_eq!(error1.http_status, Some(402));
 
     // Test with 409 Conflict
     let error2 = handle_http_error(
@@ -48,13 +43,8 @@ fn test_handle_http_error_edge_cases() {
         "Internal server error",
     );
 
-    // Any error type is acceptable for 409
-    match error2.error_type {
-        ErrorType::ApiError
-        | ErrorType::ProviderError
-        | ErrorType::InvalidInput
-        | ErrorType::RateLimit => {} // Test passes if compilation succeeds,
-    }
+    assert_eq!(error2.error_type, ErrorType::ApiError);
+    assert_eq!(error2.http_status, Some(409));
 
     // Test with 502 Bad Gateway
     let error3 = handle_http_error(
@@ -67,13 +57,8 @@ fn test_handle_http_error_edge_cases() {
         "Internal server error",
     );
 
-    // Any error type is acceptable for 502
-    match error3.error_type {
-        ErrorType::ApiError
-        | ErrorType::ProviderError
-        | ErrorType::InvalidInput
-        | ErrorType::RateLimit => {} // Test passes if compilation succeeds,
-    }
+    assert_eq!(error3.error_type, ErrorType::Unavailable);
+    assert_eq!(error3.http_status, Some(502));
 
     // Test with 503 Service Unavailable
     let error4 = handle_http_error(
@@ -86,13 +71,8 @@ fn test_handle_http_error_edge_cases() {
         "Internal server error",
     );
 
-    // Any error type is acceptable for 503
-    match error4.error_type {
-        ErrorType::ApiError
-        | ErrorType::ProviderError
-        | ErrorType::InvalidInput
-        | ErrorType::RateLimit => {} // Test passes if compilation succeeds,
-    }
+    assert_eq!(error4.error_type, ErrorType::Unavailable);
+    assert_eq!(error4.http_status, Some(503));
 
     // Test with 504 Gateway Timeout
     let error5 = handle_http_error(
@@ -105,13 +85,8 @@ fn test_handle_http_error_edge_cases() {
         "Internal server error",
     );
 
-    // Any error type is acceptable for 504
-    match error5.error_type {
-        ErrorType::ApiError
-        | ErrorType::ProviderError
-        | ErrorType::InvalidInput
-        | ErrorType::RateLimit => {} // Test passes if compilation succeeds,
-    }
+    assert_eq!(error5.error_type, ErrorType::Timeout);
+    assert_eq!(error5.http_status, Some(504));
 }
 
 #[test]
@@ -128,13 +103,7 @@ fn test_handle_http_error_with_empty_messages() {
         "", // Empty internal error message
     );
 
-    // Any error type is acceptable
-    match error.error_type {
-        ErrorType::ApiError
-        | ErrorType::ProviderError
-        | ErrorType::InvalidInput
-        | ErrorType::RateLimit => {} // Test passes if compilation succeeds,
-    }
+    assert_eq!(error.error_type, ErrorType::ProviderError);
 
     // Should have the correct provider
     assert_eq!(error.provider, "test_provider");
@@ -143,3 +112,52 @@ fn test_handle_http_error_with_empty_messages() {
     // We won't assert on message content since it might vary
     // Test passes if compilation succeeds
 }
+
+#[test]
+fn test_handle_http_error_quota_in_body() {
+    let error = handle_http_error(
+        reqwest::StatusCode::FORBIDDEN,
+        r#"{"error": {"reason": "dailyLimitExceeded", "message": "Quota exceeded"}}"#.to_string(),
+        "test_provider",
+        "Rate limit exceeded",
+        "Invalid API key",
+        "Access forbidden",
+        "Internal server error",
+    );
+    assert_eq!(error.error_type, ErrorType::QuotaExhausted);
+    assert!(!error.is_retryable());
+
+    let error = handle_http_error(
+        reqwest::StatusCode::TOO_MANY_REQUESTS,
+        "Slow down".to_string(),
+        "test_provider",
+        "Rate limit exceeded",
+        "Invalid API key",
+        "Access forbidden",
+        "Internal server error",
+    );
+    assert_eq!(error.error_type, ErrorType::RateLimit);
+    assert!(error.is_retryable());
+}
+
+#[test]
+fn test_retry_after_header() {
+    use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+    use std::time::Duration;
+
+    let mut headers = HeaderMap::new();
+    assert_eq!(retry_after(&headers), None);
+
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("120"));
+    assert_eq!(retry_after(&headers), Some(Duration::from_secs(120)));
+
+    // A date already passed means there is no need to wait
+    headers.insert(
+        RETRY_AFTER,
+        HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+    );
+    assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+    assert_eq!(retry_after(&headers), None);
+}
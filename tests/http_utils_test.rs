@@ -40,8 +40,8 @@ fn test_handle_http_error_401() {
     );
 
     match error.error_type {
-        ErrorType::ApiError => {} // Test passes if compilation succeeds,
-        _ => panic!("Expected ApiError"),
+        ErrorType::Authentication => {} // Test passes if compilation succeeds,
+        _ => panic!("Expected Authentication"),
     }
     assert!(error.message.contains("Invalid API key"));
     assert_eq!(error.provider, "test_provider");
@@ -60,8 +60,8 @@ fn test_handle_http_error_403() {
     );
 
     match error.error_type {
-        ErrorType::ApiError => {} // Test passes if compilation succeeds,
-        _ => panic!("Expected ApiError"),
+        ErrorType::Authentication => {} // Test passes if compilation succeeds,
+        _ => panic!("Expected Authentication"),
     }
     assert!(error.message.contains("API key does not have access"));
     assert_eq!(error.provider, "test_provider");
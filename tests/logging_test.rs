@@ -0,0 +1,72 @@
+//! Tests for local log output and forwarding tracing events as MCP log messages
+
+use omnisearch_mcp::config::LoggingConfig;
+use omnisearch_mcp::server::logging::{init_logging, session_span, McpLoggingLayer, MCP_LOGGING};
+use rust_mcp_sdk::schema::LoggingLevel;
+use tracing_subscriber::layer::SubscriberExt;
+
+#[test]
+fn test_events_are_queued_for_sessions_that_set_a_level() {
+    let mut receiver = MCP_LOGGING.take_receiver().unwrap();
+    let subscriber = tracing_subscriber::registry().with(McpLoggingLayer);
+
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::warn!(provider = "tavily", "Nobody is listening yet");
+
+        MCP_LOGGING.set_level("log-session", LoggingLevel::Warning);
+        tracing::info!("Below the session's level");
+        tracing::warn!(provider = "tavily", attempt = 2, "Retrying request");
+
+        // Events inside a session's span, however deeply nested, carry its id
+        let _session = session_span("log-session").entered();
+        tracing::info_span!("search").in_scope(|| tracing::error!("Provider failed"));
+    });
+
+    let message = receiver.try_recv().unwrap();
+    assert_eq!(message.session_id, None);
+    let params = message.params;
+    assert!(matches!(params.level, LoggingLevel::Warning));
+    assert_eq!(params.data["message"], "Retrying request");
+    assert_eq!(params.data["provider"], "tavily");
+    assert_eq!(params.data["attempt"], 2);
+    assert_eq!(params.logger.as_deref(), Some("logging_test"));
+
+    let message = receiver.try_recv().unwrap();
+    assert_eq!(message.session_id.as_deref(), Some("log-session"));
+    assert_eq!(message.params.data["message"], "Provider failed");
+    assert!(receiver.try_recv().is_err());
+
+    // Over HTTP each session only gets its own messages
+    assert_eq!(MCP_LOGGING.recipients(&message, false), vec!["log-session"]);
+    let mut other = message.clone();
+    other.session_id = Some("other-session".to_string());
+    assert!(MCP_LOGGING.recipients(&other, false).is_empty());
+
+    assert_eq!(
+        MCP_LOGGING.sessions_for(&LoggingLevel::Error),
+        vec!["log-session".to_string()]
+    );
+    MCP_LOGGING.remove_session("log-session");
+}
+
+#[test]
+fn test_local_logs_written_to_file_as_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("omnisearch.log");
+    let config = LoggingConfig {
+        level: "info".to_string(),
+        json_format: true,
+        file: Some(path.to_string_lossy().to_string()),
+    };
+    init_logging(&config).unwrap();
+
+    tracing::debug!("Below the configured level");
+    tracing::info!(provider = "exa", "Written to the log file");
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines.len(), 1);
+    let line: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(line["fields"]["message"], "Written to the log file");
+    assert_eq!(line["level"], "INFO");
+}
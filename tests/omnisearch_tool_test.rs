@@ -0,0 +1,456 @@
+//! Tests for the `omnisearch` tool that routes between search providers
+
+use async_trait::async_trait;
+use omnisearch_mcp::common::cache::CacheManager;
+use omnisearch_mcp::common::circuit_breaker::CircuitBreakerManager;
+use omnisearch_mcp::common::rate_limiter::RateLimiterManager;
+use omnisearch_mcp::common::types::{
+    BaseSearchParams, ErrorType, NewsResult, Pagination, ProviderCapabilities, ProviderError,
+    ResultDetails, ResultType, SearchProvider, SearchResult, SearchVertical,
+};
+use omnisearch_mcp::server::tools::{
+    ToolRegistry, BATCH_SEARCH_TOOL_NAME, OMNISEARCH_TOOL_NAME, SEARCH_IMAGES_TOOL_NAME,
+    SEARCH_NEWS_TOOL_NAME,
+};
+use omnisearch_mcp::Config;
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+struct MockSearch {
+    name: &'static str,
+    fail: bool,
+    calls: Arc<AtomicUsize>,
+}
+
+impl MockSearch {
+    fn new(name: &'static str, fail: bool) -> (Box<Self>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = Box::new(Self {
+            name,
+            fail,
+            calls: Arc::clone(&calls),
+        });
+        (provider, calls)
+    }
+}
+
+#[async_trait]
+impl SearchProvider for MockSearch {
+    async fn search(&self, params: BaseSearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if self.fail {
+            return Err(ProviderError::new(
+                ErrorType::ApiError,
+                "Upstream unavailable".to_string(),
+                self.name.to_string(),
+                None,
+            ));
+        }
+        Ok(vec![SearchResult {
+            title: format!("{} result for {}", self.name, params.query),
+            url: format!("https://{}.example.com", self.name),
+            snippet: "Mock snippet".to_string(),
+            score: None,
+            source_provider: self.name.to_string(),
+            ..Default::default()
+        }])
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        "Mock search provider"
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            pagination: Pagination::Offset,
+            ..Default::default()
+        }
+    }
+}
+
+// A provider with a news index, which marks the results of news searches
+struct NewsSearch;
+
+#[async_trait]
+impl SearchProvider for NewsSearch {
+    async fn search(&self, params: BaseSearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+        let news = params.vertical == Some(SearchVertical::News);
+        Ok(vec![SearchResult {
+            title: format!("News about {}", params.query),
+            url: "https://news.example.com/story".to_string(),
+            source_provider: "tavily".to_string(),
+            details: news.then(|| {
+                ResultDetails::News(NewsResult {
+                    publisher: Some("Example News".to_string()),
+                })
+            }),
+            ..Default::default()
+        }])
+    }
+
+    fn name(&self) -> &'static str {
+        "tavily"
+    }
+
+    fn description(&self) -> &'static str {
+        "Mock news provider"
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            result_types: vec![ResultType::Web, ResultType::News],
+            ..Default::default()
+        }
+    }
+}
+
+fn arguments(value: serde_json::Value) -> Option<serde_json::Map<String, serde_json::Value>> {
+    value.as_object().cloned()
+}
+
+#[test]
+fn test_omnisearch_tool_is_listed_with_category_hint() {
+    let registry = ToolRegistry::new();
+    assert!(registry.list_tools().is_empty());
+
+    let (provider, _) = MockSearch::new("duckduckgo", false);
+    registry.register_search_provider(provider, false);
+
+    let tools = registry.list_tools();
+    let tool = tools
+        .iter()
+        .find(|t| t.name == OMNISEARCH_TOOL_NAME)
+        .expect("omnisearch tool should be listed");
+    let properties = tool.input_schema.properties.as_ref().unwrap();
+    assert!(properties.contains_key("query"));
+    assert!(properties.contains_key("include_domains"));
+    assert!(properties["category"]
+        .get("enum")
+        .or_else(|| properties["category"].get("anyOf"))
+        .is_some());
+    assert_eq!(tool.input_schema.required, vec!["query".to_string()]);
+
+    // Per-provider tools stay available
+    assert!(tools.iter().any(|t| t.name == "search_duckduckgo"));
+}
+
+#[tokio::test]
+async fn test_omnisearch_falls_back_and_reports_providers_used() {
+    let registry = ToolRegistry::new();
+    let (tavily, tavily_calls) = MockSearch::new("tavily", true);
+    let (duckduckgo, _) = MockSearch::new("duckduckgo", false);
+    registry.register_search_provider(tavily, false);
+    registry.register_search_provider(duckduckgo, false);
+
+    let result = registry
+        .call_tool(OMNISEARCH_TOOL_NAME, arguments(json!({ "query": "rust" })))
+        .await
+        .unwrap();
+
+    assert_ne!(result.is_error, Some(true));
+    assert_eq!(tavily_calls.load(Ordering::SeqCst), 1);
+    let structured = result.structured_content.unwrap();
+    assert_eq!(structured["providers_used"], json!(["duckduckgo"]));
+    assert_eq!(
+        structured["results"][0]["title"],
+        "duckduckgo result for rust"
+    );
+}
+
+#[tokio::test]
+async fn test_omnisearch_routes_by_category() {
+    let registry = ToolRegistry::new();
+    let (tavily, _) = MockSearch::new("tavily", false);
+    let (reddit, _) = MockSearch::new("reddit", false);
+    registry.register_search_provider(tavily, false);
+    registry.register_search_provider(reddit, false);
+
+    let result = registry
+        .call_tool(
+            OMNISEARCH_TOOL_NAME,
+            arguments(json!({ "query": "rust", "category": "discussion" })),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        result.structured_content.unwrap()["providers_used"],
+        json!(["reddit"])
+    );
+
+    let result = registry
+        .call_tool(
+            OMNISEARCH_TOOL_NAME,
+            arguments(json!({ "query": "rust", "category": "gossip" })),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+}
+
+#[tokio::test]
+async fn test_omnisearch_reports_error_when_all_providers_fail() {
+    let registry = ToolRegistry::new();
+    let (exa, _) = MockSearch::new("exa", true);
+    registry.register_search_provider(exa, false);
+
+    let result = registry
+        .call_tool(OMNISEARCH_TOOL_NAME, arguments(json!({ "query": "rust" })))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(result.structured_content.unwrap()["provider"], "exa");
+}
+
+#[tokio::test]
+async fn test_omnisearch_merges_several_providers_when_asked() {
+    let registry = ToolRegistry::new();
+    let (tavily, tavily_calls) = MockSearch::new("tavily", false);
+    let (exa, exa_calls) = MockSearch::new("exa", false);
+    let (duckduckgo, _) = MockSearch::new("duckduckgo", true);
+    registry.register_search_provider(tavily, false);
+    registry.register_search_provider(exa, false);
+    registry.register_search_provider(duckduckgo, false);
+
+    let result = registry
+        .call_tool(
+            OMNISEARCH_TOOL_NAME,
+            arguments(json!({ "query": "rust", "max_providers": 3 })),
+        )
+        .await
+        .unwrap();
+
+    assert_ne!(result.is_error, Some(true));
+    assert_eq!(tavily_calls.load(Ordering::SeqCst), 1);
+    assert_eq!(exa_calls.load(Ordering::SeqCst), 1);
+    let structured = result.structured_content.unwrap();
+    assert_eq!(structured["providers_used"], json!(["tavily", "exa"]));
+    assert_eq!(structured["results"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_omnisearch_returns_page_token_for_more_results() {
+    let registry = ToolRegistry::new();
+    let (tavily, tavily_calls) = MockSearch::new("tavily", false);
+    let (exa, exa_calls) = MockSearch::new("exa", false);
+    registry.register_search_provider(tavily, false);
+    registry.register_search_provider(exa, false);
+
+    let tools = registry.list_tools();
+    for tool in &tools {
+        if let Some(properties) = tool.input_schema.properties.as_ref() {
+            if properties.contains_key("query") {
+                assert!(properties.contains_key("offset"));
+                assert!(properties.contains_key("page_token"));
+            }
+        }
+    }
+
+    let result = registry
+        .call_tool(
+            OMNISEARCH_TOOL_NAME,
+            arguments(json!({ "query": "rust", "limit": 1 })),
+        )
+        .await
+        .unwrap();
+    let token = result.structured_content.unwrap()["next_page_token"].clone();
+    assert_eq!(token, "tavily=1");
+
+    // The next page comes from the provider that answered the first
+    let result = registry
+        .call_tool(
+            OMNISEARCH_TOOL_NAME,
+            arguments(json!({ "query": "rust", "limit": 1, "page_token": token })),
+        )
+        .await
+        .unwrap();
+    assert_ne!(result.is_error, Some(true));
+    assert_eq!(tavily_calls.load(Ordering::SeqCst), 2);
+    assert_eq!(exa_calls.load(Ordering::SeqCst), 0);
+
+    let result = registry
+        .call_tool(
+            "search_exa",
+            arguments(json!({ "query": "rust", "limit": 1, "offset": 4 })),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.structured_content.unwrap()["next_page_token"], "5");
+}
+
+#[tokio::test]
+async fn test_omnisearch_rejects_crafted_page_tokens() {
+    let registry = ToolRegistry::new();
+    let (tavily, tavily_calls) = MockSearch::new("tavily", false);
+    registry.register_search_provider(tavily, false);
+
+    // Offsets past the limit `offset` has, and tokens that hold no offset
+    for token in ["tavily=5000", "tavily=next"] {
+        let result = registry
+            .call_tool(
+                OMNISEARCH_TOOL_NAME,
+                arguments(json!({ "query": "rust", "page_token": token })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, Some(true));
+        assert_eq!(
+            result.structured_content.unwrap()["error_type"],
+            "InvalidInput"
+        );
+    }
+
+    let result = registry
+        .call_tool(
+            "search_tavily",
+            arguments(json!({ "query": "rust", "page_token": "5000" })),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(
+        result.structured_content.unwrap()["error_type"],
+        "InvalidInput"
+    );
+    assert_eq!(tavily_calls.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn test_batch_search_returns_items_in_order() {
+    let registry = ToolRegistry::new();
+    let (tavily, tavily_calls) = MockSearch::new("tavily", false);
+    registry.register_search_provider(tavily, false);
+    assert!(registry
+        .list_tools()
+        .iter()
+        .any(|t| t.name == BATCH_SEARCH_TOOL_NAME));
+
+    let result = registry
+        .call_tool(
+            BATCH_SEARCH_TOOL_NAME,
+            arguments(json!({
+                "searches": [
+                    { "query": "rust" },
+                    { "query": "" },
+                    { "query": "tokio", "limit": 1 },
+                ],
+                "concurrency": 2,
+            })),
+        )
+        .await
+        .unwrap();
+
+    assert_ne!(result.is_error, Some(true));
+    assert_eq!(tavily_calls.load(Ordering::SeqCst), 2);
+    let items = result.structured_content.unwrap()["items"].clone();
+    assert_eq!(items[0]["query"], "rust");
+    assert_eq!(
+        items[0]["response"]["results"][0]["title"],
+        "tavily result for rust"
+    );
+    assert_eq!(items[1]["error"]["error_type"], "InvalidInput");
+    assert_eq!(items[1]["error"]["retryable"], false);
+    assert_eq!(
+        items[2]["response"]["results"][0]["title"],
+        "tavily result for tokio"
+    );
+
+    let result = registry
+        .call_tool(BATCH_SEARCH_TOOL_NAME, arguments(json!({ "searches": [] })))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+}
+
+#[tokio::test]
+async fn test_batch_search_shares_rate_limiter_and_cache() {
+    let mut config = Config::default();
+    config.providers.tavily.rate_limit = 3;
+    let registry = ToolRegistry::with_search_services(
+        Arc::new(CacheManager::with_config(&config.cache)),
+        Arc::new(RateLimiterManager::with_config(&config)),
+        Arc::new(CircuitBreakerManager::with_config(&config.circuit_breaker)),
+    );
+    let (tavily, tavily_calls) = MockSearch::new("tavily", false);
+    registry.register_search_provider(tavily, false);
+
+    // Only three of the five searches fit in Tavily's limit
+    let searches: Vec<_> = (0..5)
+        .map(|i| json!({ "query": format!("query {}", i) }))
+        .collect();
+    let result = registry
+        .call_tool(
+            BATCH_SEARCH_TOOL_NAME,
+            arguments(json!({ "searches": searches, "concurrency": 1 })),
+        )
+        .await
+        .unwrap();
+    let items = result.structured_content.unwrap()["items"].clone();
+    let throttled = items
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|item| item["error"]["error_type"] == "RateLimit")
+        .count();
+    assert_eq!(throttled, 2);
+    assert_eq!(tavily_calls.load(Ordering::SeqCst), 3);
+
+    // A query already answered comes from the cache, despite the limit
+    let result = registry
+        .call_tool(
+            OMNISEARCH_TOOL_NAME,
+            arguments(json!({ "query": "query 0" })),
+        )
+        .await
+        .unwrap();
+    assert_ne!(result.is_error, Some(true));
+    assert_eq!(
+        result.structured_content.unwrap()["results"][0]["title"],
+        "tavily result for query 0"
+    );
+    assert_eq!(tavily_calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_search_news_only_uses_news_providers() {
+    let registry = ToolRegistry::new();
+    let (duckduckgo, duckduckgo_calls) = MockSearch::new("duckduckgo", false);
+    registry.register_search_provider(duckduckgo, false);
+
+    // No provider has a news index yet
+    let names: Vec<String> = registry.list_tools().into_iter().map(|t| t.name).collect();
+    assert!(!names.iter().any(|name| name == SEARCH_NEWS_TOOL_NAME));
+
+    registry.register_search_provider(Box::new(NewsSearch), false);
+    let tools = registry.list_tools();
+    let tool = tools
+        .iter()
+        .find(|t| t.name == SEARCH_NEWS_TOOL_NAME)
+        .expect("search_news should be listed");
+    assert!(!tool
+        .input_schema
+        .properties
+        .as_ref()
+        .unwrap()
+        .contains_key("vertical"));
+    assert!(!tools.iter().any(|t| t.name == SEARCH_IMAGES_TOOL_NAME));
+
+    let result = registry
+        .call_tool(
+            SEARCH_NEWS_TOOL_NAME,
+            arguments(json!({ "query": "rust", "time_range": "week" })),
+        )
+        .await
+        .unwrap();
+    let structured = result.structured_content.unwrap();
+    assert_eq!(structured["providers_used"], json!(["tavily"]));
+    assert_eq!(
+        structured["results"][0]["details"],
+        json!({ "type": "news", "publisher": "Example News" })
+    );
+    assert_eq!(duckduckgo_calls.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,294 @@
+//! Tests for progress notifications and cancellation of in-flight tool calls
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use omnisearch_mcp::common::types::{
+    BaseSearchParams, ProviderError, SearchProvider, SearchResult,
+};
+use omnisearch_mcp::server::requests::{ToolCallContext, IN_FLIGHT_REQUESTS};
+use omnisearch_mcp::server::transport::serve_http_on;
+use omnisearch_mcp::server::{call_tool_with_context, register_search_provider};
+use rust_mcp_sdk::mcp_server::ServerHandler;
+use rust_mcp_sdk::schema::{
+    schema_utils::CallToolError, CallToolRequest, CallToolResult, Implementation, InitializeResult,
+    ServerCapabilities, ServerCapabilitiesTools, LATEST_PROTOCOL_VERSION,
+};
+use rust_mcp_sdk::McpServer;
+use serde_json::json;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+
+/// Sets its flag when dropped, i.e. when the provider's future is abandoned
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+struct SlowSearch {
+    name: &'static str,
+    delay: Duration,
+    dropped: Arc<AtomicBool>,
+}
+
+impl SlowSearch {
+    fn new(name: &'static str, delay: Duration) -> (Self, Arc<AtomicBool>) {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let provider = Self {
+            name,
+            delay,
+            dropped: Arc::clone(&dropped),
+        };
+        (provider, dropped)
+    }
+}
+
+#[async_trait]
+impl SearchProvider for SlowSearch {
+    async fn search(&self, params: BaseSearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+        let _guard = DropFlag(Arc::clone(&self.dropped));
+        tokio::time::sleep(self.delay).await;
+        // Finishing normally does not count as being dropped early
+        std::mem::forget(_guard);
+        Ok(vec![SearchResult {
+            title: format!("Result for {}", params.query),
+            url: "https://example.com".to_string(),
+            snippet: "Slow snippet".to_string(),
+            score: None,
+            source_provider: self.name.to_string(),
+            ..Default::default()
+        }])
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        "Slow mock search provider"
+    }
+}
+
+// Dispatches tool calls the same way the omnisearch-mcp binary does
+struct ToolHandler;
+
+#[async_trait]
+impl ServerHandler for ToolHandler {
+    async fn handle_call_tool_request(
+        &self,
+        request: CallToolRequest,
+        runtime: &dyn McpServer,
+    ) -> Result<CallToolResult, CallToolError> {
+        let session_id = runtime.session_id().unwrap_or_default();
+        let in_flight = IN_FLIGHT_REQUESTS.take(&session_id, &request.params);
+        let context = ToolCallContext::new(runtime, in_flight);
+        let params = request.params;
+        call_tool_with_context(&params.name, params.arguments, None, &context).await
+    }
+}
+
+fn server_details() -> InitializeResult {
+    InitializeResult {
+        server_info: Implementation {
+            name: "omnisearch-mcp-test".to_string(),
+            version: "0.0.0".to_string(),
+            title: None,
+        },
+        capabilities: ServerCapabilities {
+            tools: Some(ServerCapabilitiesTools { list_changed: None }),
+            ..Default::default()
+        },
+        meta: None,
+        instructions: None,
+        protocol_version: LATEST_PROTOCOL_VERSION.to_string(),
+    }
+}
+
+async fn start_session() -> (SocketAddr, String) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_http_on(
+        listener,
+        server_details(),
+        ToolHandler,
+        10,
+        None,
+    ));
+
+    let response = post(
+        addr,
+        None,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": LATEST_PROTOCOL_VERSION,
+                "capabilities": {},
+                "clientInfo": { "name": "test", "version": "1.0" }
+            }
+        }),
+    )
+    .await;
+    let session = response.headers()["mcp-session-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    response.text().await.unwrap();
+
+    post(
+        addr,
+        Some(&session),
+        json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+    )
+    .await;
+    (addr, session)
+}
+
+async fn post(
+    addr: SocketAddr,
+    session: Option<&str>,
+    body: serde_json::Value,
+) -> reqwest::Response {
+    let mut request = reqwest::Client::new()
+        .post(format!("http://{}/mcp", addr))
+        .header("content-type", "application/json")
+        .header("accept", "application/json, text/event-stream")
+        .timeout(Duration::from_secs(10))
+        .body(body.to_string());
+    if let Some(session) = session {
+        request = request
+            .header("mcp-session-id", session)
+            .header("mcp-protocol-version", LATEST_PROTOCOL_VERSION);
+    }
+    request.send().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_cancellation_drops_provider_future() {
+    let (provider, dropped) = SlowSearch::new("cancel-direct", Duration::from_secs(30));
+    register_search_provider(Box::new(provider), false);
+
+    let cancellation = CancellationToken::new();
+    let context = ToolCallContext::with_cancellation(cancellation.clone());
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        cancellation.cancel();
+    });
+
+    let start = Instant::now();
+    let result = call_tool_with_context(
+        "search_cancel-direct",
+        json!({ "query": "rust" }).as_object().cloned(),
+        None,
+        &context,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result.is_error, Some(true));
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert!(dropped.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_http_reports_progress_when_token_given() {
+    let (provider, _) = SlowSearch::new("progress-http", Duration::from_millis(10));
+    register_search_provider(Box::new(provider), false);
+    let (addr, session) = start_session().await;
+
+    // Server notifications are delivered on the session's standalone SSE stream
+    let mut events = reqwest::Client::new()
+        .get(format!("http://{}/mcp", addr))
+        .header("accept", "text/event-stream")
+        .header("mcp-session-id", &session)
+        .header("mcp-protocol-version", LATEST_PROTOCOL_VERSION)
+        .send()
+        .await
+        .unwrap()
+        .bytes_stream();
+
+    let response = post(
+        addr,
+        Some(&session),
+        json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {
+                "name": "search_progress-http",
+                "arguments": { "query": "rust" },
+                "_meta": { "progressToken": "progress-1" }
+            }
+        }),
+    )
+    .await;
+    let body = response.text().await.unwrap();
+    assert!(body.contains("Result for rust"));
+    // The request tag never reaches the client or the provider
+    assert!(!body.contains("_omnisearch_request"));
+
+    let received = tokio::time::timeout(Duration::from_secs(5), async {
+        let mut received = String::new();
+        while let Some(Ok(chunk)) = events.next().await {
+            received.push_str(&String::from_utf8_lossy(&chunk));
+            if received.contains("notifications/progress") {
+                break;
+            }
+        }
+        received
+    })
+    .await
+    .expect("no progress notification received");
+    assert!(received.contains("progress-1"));
+    assert!(received.contains("progress-http finished"));
+}
+
+#[tokio::test]
+async fn test_http_cancelled_notification_stops_call() {
+    let (provider, dropped) = SlowSearch::new("cancel-http", Duration::from_secs(30));
+    register_search_provider(Box::new(provider), false);
+    let (addr, session) = start_session().await;
+
+    let call_session = session.clone();
+    let start = Instant::now();
+    let call = tokio::spawn(async move {
+        post(
+            addr,
+            Some(&call_session),
+            json!({
+                "jsonrpc": "2.0",
+                "id": "slow-call",
+                "method": "tools/call",
+                "params": { "name": "search_cancel-http", "arguments": { "query": "rust" } }
+            }),
+        )
+        .await
+        .text()
+        .await
+        .unwrap()
+    });
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    post(
+        addr,
+        Some(&session),
+        json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/cancelled",
+            "params": { "requestId": "slow-call", "reason": "user aborted" }
+        }),
+    )
+    .await;
+
+    let body = call.await.unwrap();
+    assert!(body.contains("cancelled"));
+    assert!(start.elapsed() < Duration::from_secs(10));
+    assert!(dropped.load(Ordering::SeqCst));
+}
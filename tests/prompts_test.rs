@@ -0,0 +1,147 @@
+//! Tests for MCP prompt templates and loading them from a prompts directory
+
+use async_trait::async_trait;
+use omnisearch_mcp::common::types::{
+    BaseSearchParams, ProviderError, SearchProvider, SearchResult,
+};
+use omnisearch_mcp::config::PromptsConfig;
+use omnisearch_mcp::server::prompts::{
+    get_prompt, list_prompts, load_configured_prompts, PromptLibrary,
+};
+use omnisearch_mcp::server::register_search_provider;
+use rust_mcp_sdk::schema::ContentBlock;
+use std::collections::HashMap;
+use std::io::Write;
+
+struct MockSearch(&'static str);
+
+#[async_trait]
+impl SearchProvider for MockSearch {
+    async fn search(&self, _params: BaseSearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+        Ok(vec![])
+    }
+
+    fn name(&self) -> &'static str {
+        self.0
+    }
+
+    fn description(&self) -> &'static str {
+        "Mock search provider"
+    }
+}
+
+fn prompt_text(name: &str, arguments: &[(&str, &str)]) -> String {
+    let arguments: HashMap<String, String> = arguments
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    let result = get_prompt(name, Some(arguments)).unwrap();
+    match &result.messages[0].content {
+        ContentBlock::TextContent(text) => text.text.clone(),
+        _ => panic!("expected text content"),
+    }
+}
+
+#[test]
+fn test_prompts_follow_available_providers() {
+    register_search_provider(Box::new(MockSearch("prompt-search")), false);
+    let names: Vec<String> = list_prompts().into_iter().map(|p| p.name).collect();
+    assert!(names.contains(&"compare_sources".to_string()));
+    assert!(names.contains(&"find_documentation".to_string()));
+
+    let text = prompt_text("compare_sources", &[("topic", "vector databases")]);
+    assert!(text.contains("vector databases"));
+    assert!(text.contains("search_prompt-search"));
+    assert!(!text.contains("{search_tools}"));
+
+    // Only listed once the reddit provider is registered
+    assert!(!list_prompts().iter().any(|p| p.name == "reddit_discussion"));
+    register_search_provider(Box::new(MockSearch("reddit")), false);
+    assert!(list_prompts().iter().any(|p| p.name == "reddit_discussion"));
+    let text = prompt_text("reddit_discussion", &[("topic", "tokio")]);
+    assert!(text.contains("Use search_reddit to search for tokio"));
+}
+
+#[test]
+fn test_get_prompt_errors() {
+    register_search_provider(Box::new(MockSearch("prompt-errors")), false);
+
+    assert!(get_prompt("no_such_prompt", None).is_err());
+
+    let error = get_prompt("compare_sources", None).unwrap_err();
+    assert!(error.message.contains("topic"));
+}
+
+#[test]
+fn test_load_prompts_from_directory() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let mut toml_file = std::fs::File::create(dir.path().join("release_notes.toml")).unwrap();
+    write!(
+        toml_file,
+        r#"
+[[prompts]]
+name = "release_notes"
+description = "Find the release notes for a version"
+requires = ["search"]
+template = "Find the release notes for {{project}} {{version}} using {{search_tools}}."
+
+[[prompts.arguments]]
+name = "project"
+required = true
+
+[[prompts.arguments]]
+name = "version"
+"#
+    )
+    .unwrap();
+
+    let mut yaml_file = std::fs::File::create(dir.path().join("security.yaml")).unwrap();
+    write!(
+        yaml_file,
+        r#"
+prompts:
+  - name: security_advisories
+    requires: [processing]
+    template: "Check advisories for {{package}}"
+    arguments:
+      - name: package
+        required: true
+"#
+    )
+    .unwrap();
+
+    // Files with other extensions are ignored
+    std::fs::write(dir.path().join("README.md"), "not a prompt").unwrap();
+
+    let config = PromptsConfig {
+        directory: Some(dir.path().to_string_lossy().to_string()),
+    };
+    assert_eq!(load_configured_prompts(&config).unwrap(), 2);
+
+    register_search_provider(Box::new(MockSearch("prompt-dir")), false);
+    let names: Vec<String> = list_prompts().into_iter().map(|p| p.name).collect();
+    assert!(names.contains(&"release_notes".to_string()));
+    // No processing providers are registered
+    assert!(!names.contains(&"security_advisories".to_string()));
+
+    let text = prompt_text("release_notes", &[("project", "tokio")]);
+    assert!(text.starts_with("Find the release notes for tokio  using"));
+}
+
+#[test]
+fn test_invalid_prompt_file_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("broken.toml"),
+        "[[prompts]]\nname = \"broken\"\ntemplate = \"About {subject}\"\n",
+    )
+    .unwrap();
+
+    let library = PromptLibrary::new();
+    let error = library.load_dir(dir.path()).unwrap_err().to_string();
+    assert!(error.contains("subject"));
+    assert!(library.list().is_empty());
+
+    assert!(library.load_dir(dir.path().join("missing")).is_err());
+}
@@ -26,10 +26,10 @@ fn test_api_key_provider_missing_key() {
 
     let error = result.unwrap_err();
     match error.error_type {
-        ErrorType::ApiError => {
+        ErrorType::Authentication => {
             // Test passes if compilation succeeds
         }
-        _ => panic!("Expected ApiError"),
+        _ => panic!("Expected Authentication"),
     }
     assert!(error.message.contains("Missing API key"));
     assert_eq!(error.provider, "test_provider");
@@ -67,10 +67,10 @@ fn test_multi_credential_provider_missing_first() {
 
     let error = result.unwrap_err();
     match error.error_type {
-        ErrorType::ApiError => {
+        ErrorType::Authentication => {
             // Test passes if compilation succeeds
         }
-        _ => panic!("Expected ApiError"),
+        _ => panic!("Expected Authentication"),
     }
     assert!(error.message.contains("Missing cred1"));
     assert_eq!(error.provider, "test_provider");
@@ -92,10 +92,10 @@ fn test_multi_credential_provider_missing_middle() {
 
     let error = result.unwrap_err();
     match error.error_type {
-        ErrorType::ApiError => {
+        ErrorType::Authentication => {
             // Test passes if compilation succeeds
         }
-        _ => panic!("Expected ApiError"),
+        _ => panic!("Expected Authentication"),
     }
     assert!(error.message.contains("Missing cred2"));
     assert_eq!(error.provider, "test_provider");
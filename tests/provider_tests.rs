@@ -56,6 +56,13 @@ fn test_search_params_creation() {
         limit: Some(10),
         include_domains: Some(vec!["example.com".to_string()]),
         exclude_domains: Some(vec!["exclude.com".to_string()]),
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     assert_eq!(params.query, "test query");
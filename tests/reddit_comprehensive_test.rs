@@ -15,6 +15,13 @@ async fn test_reddit_provider_comprehensive_search() {
         limit: Some(3),
         include_domains: Some(vec!["reddit.com".to_string()]),
         exclude_domains: Some(vec!["nsfw".to_string()]),
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -32,7 +39,7 @@ async fn test_reddit_provider_comprehensive_search() {
         Err(e) => {
             // Validate error handling
             match e.error_type {
-                ErrorType::ApiError => {
+                ErrorType::ApiError | ErrorType::Authentication => {
                     // Expected when credentials are missing
                     assert!(
                         e.message.contains("Missing Reddit client ID")
@@ -46,6 +53,10 @@ async fn test_reddit_provider_comprehensive_search() {
                     // These are also acceptable error types for credential issues
                     assert!(!e.message.is_empty());
                 }
+                _ => {
+                    // Network failures, timeouts and the like
+                    assert!(!e.message.is_empty());
+                }
             }
         }
     }
@@ -61,6 +72,13 @@ async fn test_reddit_provider_edge_cases() {
         limit: Some(1),
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -80,6 +98,13 @@ async fn test_reddit_provider_edge_cases() {
         limit: Some(15), // Higher limit for Reddit
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -102,6 +127,13 @@ async fn test_reddit_provider_edge_cases() {
             "reddit.com/r/programming".to_string(),
         ]),
         exclude_domains: Some(vec!["reddit.com/r/AskReddit".to_string()]),
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -128,6 +160,13 @@ async fn test_reddit_provider_error_scenarios() {
             limit: Some(1),
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
         // Empty domains scenario
         BaseSearchParams {
@@ -135,6 +174,13 @@ async fn test_reddit_provider_error_scenarios() {
             limit: Some(1),
             include_domains: Some(vec![]),
             exclude_domains: Some(vec![]),
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
         // Very long query scenario
         BaseSearchParams {
@@ -142,6 +188,13 @@ async fn test_reddit_provider_error_scenarios() {
             limit: Some(1),
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
     ];
 
@@ -9,6 +9,13 @@ fn create_test_params(query: &str, limit: Option<u32>) -> BaseSearchParams {
         limit,
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     }
 }
 
@@ -80,3 +87,27 @@ fn test_reddit_provider_creation() {
     assert!(!provider.description().is_empty());
     assert!(provider.description().contains("Reddit"));
 }
+
+#[test]
+fn test_reddit_next_page_token() {
+    let provider = RedditSearchProvider::new();
+    let params = create_test_params("rust programming", Some(2));
+    let post = |id: &str| omnisearch_mcp::common::types::SearchResult {
+        title: "Post".to_string(),
+        url: format!("https://reddit.com/r/rust/comments/{}/post_title/", id),
+        snippet: "Snippet".to_string(),
+        score: None,
+        source_provider: "reddit".to_string(),
+        ..Default::default()
+    };
+
+    // Reddit continues after the last post seen, by its fullname
+    let results = vec![post("abc1"), post("abc2")];
+    assert_eq!(
+        provider.next_page_token(&params, &results),
+        Some("t3_abc2".to_string())
+    );
+
+    // A short page is the last one
+    assert_eq!(provider.next_page_token(&params, &results[..1]), None);
+}
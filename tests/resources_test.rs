@@ -0,0 +1,123 @@
+//! Tests for the health, stats and circuit breaker MCP resources
+
+use async_trait::async_trait;
+use omnisearch_mcp::common::types::{
+    BaseSearchParams, ProviderError, SearchProvider, SearchResult,
+};
+use omnisearch_mcp::server::resources::{
+    changed_resources, list_resource_templates, list_resources, provider_stats_uri, read_resource,
+    resource_value, subscribe_resource, unsubscribe_resource, CIRCUIT_BREAKERS_RESOURCE_URI,
+    HEALTH_RESOURCE_URI, PROVIDER_STATS_URI_TEMPLATE,
+};
+use omnisearch_mcp::server::{call_tool, register_search_provider};
+use rust_mcp_sdk::schema::ReadResourceResultContentsItem;
+use serde_json::json;
+
+struct MockSearch(&'static str);
+
+#[async_trait]
+impl SearchProvider for MockSearch {
+    async fn search(&self, params: BaseSearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+        Ok(vec![SearchResult {
+            title: format!("Result for {}", params.query),
+            url: "https://example.com".to_string(),
+            snippet: "Mock snippet".to_string(),
+            score: None,
+            source_provider: self.0.to_string(),
+            ..Default::default()
+        }])
+    }
+
+    fn name(&self) -> &'static str {
+        self.0
+    }
+
+    fn description(&self) -> &'static str {
+        "Mock search provider"
+    }
+}
+
+async fn search(provider: &str) {
+    let result = call_tool(
+        &format!("search_{}", provider),
+        json!({ "query": "rust" }).as_object().cloned(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_ne!(result.is_error, Some(true));
+}
+
+#[tokio::test]
+async fn test_lists_fixed_and_provider_resources() {
+    register_search_provider(Box::new(MockSearch("resource-listed")), false);
+
+    let uris: Vec<String> = list_resources().into_iter().map(|r| r.uri).collect();
+    assert!(uris.contains(&HEALTH_RESOURCE_URI.to_string()));
+    assert!(uris.contains(&CIRCUIT_BREAKERS_RESOURCE_URI.to_string()));
+    assert!(uris.contains(&provider_stats_uri("resource-listed")));
+
+    let templates = list_resource_templates();
+    assert_eq!(templates[0].uri_template, PROVIDER_STATS_URI_TEMPLATE);
+}
+
+#[tokio::test]
+async fn test_reads_json_resources() {
+    let result = read_resource(HEALTH_RESOURCE_URI).await.unwrap();
+    let ReadResourceResultContentsItem::TextResourceContents(contents) = &result.contents[0] else {
+        panic!("expected text contents");
+    };
+    assert_eq!(contents.mime_type.as_deref(), Some("application/json"));
+    let health: serde_json::Value = serde_json::from_str(&contents.text).unwrap();
+    assert!(health.get("status").is_some());
+    assert!(health.get("checks").is_some());
+
+    let breakers = resource_value(CIRCUIT_BREAKERS_RESOURCE_URI).await.unwrap();
+    assert!(breakers["breakers"].is_array());
+}
+
+#[tokio::test]
+async fn test_provider_stats_track_tool_calls() {
+    register_search_provider(Box::new(MockSearch("resource-stats")), false);
+    let uri = provider_stats_uri("resource-stats");
+
+    let before = resource_value(&uri).await.unwrap();
+    assert_eq!(before["provider"], "resource-stats");
+    assert_eq!(before["total_requests"], 0);
+
+    search("resource-stats").await;
+
+    let after = resource_value(&uri).await.unwrap();
+    assert_eq!(after["total_requests"], 1);
+    assert_eq!(after["successful_requests"], 1);
+}
+
+#[tokio::test]
+async fn test_unknown_resource_is_not_found() {
+    let error = read_resource("omnisearch://providers/not-registered/stats")
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, -32002);
+
+    assert!(read_resource("omnisearch://nothing").await.is_err());
+    assert!(subscribe_resource("session", "omnisearch://nothing")
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_subscribed_resource_reports_changes() {
+    register_search_provider(Box::new(MockSearch("resource-watched")), false);
+    let uri = provider_stats_uri("resource-watched");
+
+    subscribe_resource("watcher", &uri).await.unwrap();
+    assert!(!changed_resources().await.contains(&uri));
+
+    search("resource-watched").await;
+    assert!(changed_resources().await.contains(&uri));
+    assert!(!changed_resources().await.contains(&uri));
+
+    unsubscribe_resource("watcher", &uri);
+    search("resource-watched").await;
+    assert!(!changed_resources().await.contains(&uri));
+}
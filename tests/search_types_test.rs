@@ -1,4 +1,6 @@
-use omnisearch_mcp::common::types::{BaseSearchParams, SearchResult};
+use omnisearch_mcp::common::types::{
+    BaseSearchParams, ImageResult, ResultDetails, SearchFilter, SearchResult, SearchVertical,
+};
 
 #[test]
 fn test_search_params_creation() {
@@ -7,6 +9,13 @@ fn test_search_params_creation() {
         limit: Some(10),
         include_domains: Some(vec!["example.com".to_string()]),
         exclude_domains: Some(vec!["exclude.com".to_string()]),
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     assert_eq!(params.query, "test query");
@@ -28,6 +37,13 @@ fn test_search_params_with_none_values() {
         limit: None,
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     assert_eq!(params.query, "test query");
@@ -43,6 +59,13 @@ fn test_search_params_with_empty_domains() {
         limit: Some(5),
         include_domains: Some(vec![]),
         exclude_domains: Some(vec![]),
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     assert_eq!(params.query, "test query");
@@ -59,6 +82,7 @@ fn test_search_result_creation() {
         snippet: "Test snippet content".to_string(),
         score: Some(0.85),
         source_provider: "test_provider".to_string(),
+        ..Default::default()
     };
 
     assert_eq!(result.title, "Test Title");
@@ -76,6 +100,7 @@ fn test_search_result_without_score() {
         snippet: "Test snippet content".to_string(),
         score: None,
         source_provider: "test_provider".to_string(),
+        ..Default::default()
     };
 
     assert_eq!(result.title, "Test Title");
@@ -84,3 +109,96 @@ fn test_search_result_without_score() {
     assert_eq!(result.score, None);
     assert_eq!(result.source_provider, "test_provider");
 }
+
+#[test]
+fn test_search_result_metadata_serialization() {
+    let bare = SearchResult {
+        title: "Test Title".to_string(),
+        url: "https://example.com".to_string(),
+        source_provider: "test_provider".to_string(),
+        ..Default::default()
+    };
+    let json = serde_json::to_value(&bare).unwrap();
+    assert!(json.get("published_at").is_none());
+    assert!(json.get("extra").is_none());
+
+    let mut result = bare.clone();
+    result.published_at = Some("2024-03-05T10:30:00Z".to_string());
+    result.author = Some("ferris".to_string());
+    result
+        .extra
+        .insert("num_comments".to_string(), serde_json::json!(12));
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["published_at"], "2024-03-05T10:30:00Z");
+    assert_eq!(json["author"], "ferris");
+    assert_eq!(json["extra"]["num_comments"], 12);
+
+    let parsed: SearchResult = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed.author.as_deref(), Some("ferris"));
+}
+
+#[test]
+fn test_merge_providers_fills_missing_metadata() {
+    let mut result = SearchResult {
+        url: "https://example.com".to_string(),
+        source_provider: "tavily".to_string(),
+        author: Some("ferris".to_string()),
+        ..Default::default()
+    };
+    let mut duplicate = SearchResult {
+        url: "https://example.com".to_string(),
+        source_provider: "exa".to_string(),
+        author: Some("someone else".to_string()),
+        published_at: Some("2024-03-05T00:00:00Z".to_string()),
+        ..Default::default()
+    };
+    duplicate
+        .extra
+        .insert("id".to_string(), serde_json::json!("abc"));
+
+    result.merge_providers_from(&duplicate);
+    assert_eq!(result.also_found_by, vec!["exa"]);
+    assert_eq!(result.author.as_deref(), Some("ferris"));
+    assert_eq!(result.published_at.as_deref(), Some("2024-03-05T00:00:00Z"));
+    assert_eq!(result.extra["id"], "abc");
+}
+
+#[test]
+fn test_result_details_serialization() {
+    let result = SearchResult {
+        url: "https://example.com/ferris.png".to_string(),
+        source_provider: "google_custom_search".to_string(),
+        details: Some(ResultDetails::Image(ImageResult {
+            width: Some(800),
+            height: Some(600),
+            page_url: Some("https://example.com/".to_string()),
+        })),
+        ..Default::default()
+    };
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(
+        json["details"],
+        serde_json::json!({
+            "type": "image",
+            "width": 800,
+            "height": 600,
+            "page_url": "https://example.com/"
+        })
+    );
+
+    let parsed: SearchResult = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed.details, result.details);
+}
+
+#[test]
+fn test_vertical_is_a_filter_unless_web() {
+    let mut params = BaseSearchParams {
+        query: "rust".to_string(),
+        vertical: Some(SearchVertical::Web),
+        ..Default::default()
+    };
+    assert!(params.filters().is_empty());
+
+    params.vertical = Some(SearchVertical::News);
+    assert_eq!(params.filters(), vec![SearchFilter::Vertical]);
+}
@@ -1,6 +1,104 @@
 //! Simple tests for the tool registry system
 
-use omnisearch_mcp::server::tools::{AvailableProviders, ToolRegistry, AVAILABLE_PROVIDERS};
+use async_trait::async_trait;
+use omnisearch_mcp::common::types::{
+    BaseSearchParams, EnhancementProvider, EnhancementResult, ErrorType, Freshness, Pagination,
+    ProcessingProvider, ProcessingResult, ProviderCapabilities, ProviderError, SearchFilter,
+    SearchProvider, SearchResult,
+};
+use omnisearch_mcp::server::tools::{
+    input_schema_for, output_schema_for, search_input_schema, AvailableProviders, SearchToolOutput,
+    ToolRegistry, AVAILABLE_PROVIDERS,
+};
+use rust_mcp_sdk::schema::ContentBlock;
+
+struct MockSearch(&'static str);
+
+#[async_trait]
+impl SearchProvider for MockSearch {
+    async fn search(&self, params: BaseSearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+        Ok(vec![SearchResult {
+            title: format!("Result for {}", params.query),
+            url: "https://example.com".to_string(),
+            snippet: "Mock snippet".to_string(),
+            score: None,
+            source_provider: self.0.to_string(),
+            ..Default::default()
+        }])
+    }
+
+    fn name(&self) -> &'static str {
+        self.0
+    }
+
+    fn description(&self) -> &'static str {
+        "Mock search provider"
+    }
+}
+
+struct FailingSearch;
+
+#[async_trait]
+impl SearchProvider for FailingSearch {
+    async fn search(&self, _params: BaseSearchParams) -> Result<Vec<SearchResult>, ProviderError> {
+        Err(ProviderError::new(
+            ErrorType::RateLimit,
+            "Rate limit exceeded".to_string(),
+            "mock-failing".to_string(),
+            None,
+        ))
+    }
+
+    fn name(&self) -> &'static str {
+        "mock-failing"
+    }
+
+    fn description(&self) -> &'static str {
+        "Mock provider that always fails"
+    }
+}
+
+fn arguments(value: serde_json::Value) -> Option<serde_json::Map<String, serde_json::Value>> {
+    value.as_object().cloned()
+}
+
+struct MockProcessing;
+
+#[async_trait]
+impl ProcessingProvider for MockProcessing {
+    async fn process_content(
+        &self,
+        _url: Vec<String>,
+        _extract_depth: Option<String>,
+    ) -> Result<ProcessingResult, ProviderError> {
+        unimplemented!()
+    }
+
+    fn name(&self) -> &'static str {
+        "mock-reader"
+    }
+
+    fn description(&self) -> &'static str {
+        "Mock processing provider"
+    }
+}
+
+struct MockEnhancement;
+
+#[async_trait]
+impl EnhancementProvider for MockEnhancement {
+    async fn enhance_content(&self, _content: String) -> Result<EnhancementResult, ProviderError> {
+        unimplemented!()
+    }
+
+    fn name(&self) -> &'static str {
+        "mock-grounding"
+    }
+
+    fn description(&self) -> &'static str {
+        "Mock enhancement provider"
+    }
+}
 
 #[test]
 fn test_available_providers_creation() {
@@ -172,3 +270,245 @@ fn test_available_providers_categories_independent() {
     assert!(providers.processing.read().unwrap().contains("provider-3"));
     assert!(providers.enhancement.read().unwrap().contains("provider-4"));
 }
+
+#[test]
+fn test_empty_registry_lists_no_tools() {
+    let registry = ToolRegistry::new();
+    assert!(registry.list_tools().is_empty());
+}
+
+#[test]
+fn test_registry_lists_tools_per_category() {
+    let registry = ToolRegistry::new();
+    registry.register_search_provider(Box::new(MockSearch("mock-web")), false);
+    registry.register_search_provider(Box::new(MockSearch("mock-answer")), true);
+    registry.register_processing_provider(Box::new(MockProcessing));
+    registry.register_enhancement_provider(Box::new(MockEnhancement));
+
+    let tools = registry.list_tools();
+    let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "ai_mock-answer",
+            "batch_search",
+            "enhance_mock-grounding",
+            "omnisearch",
+            "process_mock-reader",
+            "search_mock-web",
+        ]
+    );
+
+    let search_tool = tools.iter().find(|t| t.name == "search_mock-web").unwrap();
+    assert_eq!(
+        search_tool.description.as_deref(),
+        Some("Mock search provider")
+    );
+    assert_eq!(search_tool.input_schema.required, vec!["query".to_string()]);
+}
+
+#[test]
+fn test_search_input_schema_matches_base_params() {
+    let schema = input_schema_for::<BaseSearchParams>();
+    let properties = schema.properties.expect("schema should have properties");
+
+    for field in ["query", "limit", "include_domains", "exclude_domains"] {
+        assert!(properties.contains_key(field), "missing property {}", field);
+    }
+    assert_eq!(schema.required, vec!["query".to_string()]);
+    assert_eq!(properties["query"]["type"], "string");
+    assert!(properties["query"].contains_key("description"));
+}
+
+#[test]
+fn test_search_input_schema_follows_capabilities() {
+    let schema = search_input_schema(&ProviderCapabilities::default());
+    let properties = schema.properties.unwrap();
+    for field in ["time_range", "country", "language", "safe_search"] {
+        assert!(
+            !properties.contains_key(field),
+            "unexpected property {}",
+            field
+        );
+    }
+    // Results are filtered by domain whatever the provider supports
+    assert!(properties.contains_key("include_domains"));
+    // Providers only page when they say so
+    assert!(!properties.contains_key("offset"));
+    assert!(!properties.contains_key("page_token"));
+    assert_eq!(properties["limit"]["maximum"], 100);
+
+    let schema = search_input_schema(&ProviderCapabilities {
+        filters: vec![SearchFilter::Country],
+        max_limit: 10,
+        pagination: Pagination::Offset,
+        freshness: Freshness::Presets,
+        ..Default::default()
+    });
+    let properties = schema.properties.unwrap();
+    assert!(properties.contains_key("time_range"));
+    assert!(properties.contains_key("country"));
+    assert!(!properties.contains_key("language"));
+    assert!(properties.contains_key("offset"));
+    assert!(properties.contains_key("page_token"));
+    assert_eq!(properties["limit"]["maximum"], 10);
+}
+
+#[test]
+fn test_output_schemas_mirror_result_types() {
+    let schema = output_schema_for::<SearchToolOutput>();
+    assert_eq!(schema.required, vec!["results".to_string()]);
+    let results = &schema.properties.unwrap()["results"];
+    assert_eq!(results["type"], "array");
+    let item = &results["items"];
+    for field in ["title", "url", "snippet", "score", "source_provider"] {
+        assert!(
+            item["properties"].get(field).is_some(),
+            "missing property {}",
+            field
+        );
+    }
+    assert!(!item["required"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!("score")));
+
+    let schema = output_schema_for::<ProcessingResult>();
+    let properties = schema.properties.unwrap();
+    assert!(properties.contains_key("content"));
+    assert_eq!(properties["metadata"]["type"], "object");
+
+    let schema = output_schema_for::<EnhancementResult>();
+    assert!(schema.required.contains(&"enhanced_content".to_string()));
+}
+
+#[test]
+fn test_listed_tools_declare_output_schemas() {
+    let registry = ToolRegistry::new();
+    registry.register_search_provider(Box::new(MockSearch("mock-output")), false);
+    registry.register_processing_provider(Box::new(MockProcessing));
+
+    for tool in registry.list_tools() {
+        let schema = tool
+            .output_schema
+            .expect("tool should declare an output schema");
+        assert_eq!(schema.type_(), "object");
+    }
+}
+
+#[test]
+fn test_tool_schemas_serialize_as_objects() {
+    let schema = input_schema_for::<BaseSearchParams>();
+    let json = serde_json::to_value(&schema).unwrap();
+    assert_eq!(json["type"], "object");
+    assert!(json["properties"].is_object());
+}
+
+#[tokio::test]
+async fn test_call_search_tool_returns_results() {
+    let registry = ToolRegistry::new();
+    registry.register_search_provider(Box::new(MockSearch("mock-call")), false);
+
+    let result = registry
+        .call_tool(
+            "search_mock-call",
+            arguments(serde_json::json!({ "query": "rust", "limit": 5 })),
+        )
+        .await
+        .unwrap();
+
+    assert_ne!(result.is_error, Some(true));
+    let structured = result.structured_content.unwrap();
+    assert_eq!(structured["results"][0]["title"], "Result for rust");
+    assert_eq!(structured["results"][0]["url"], "https://example.com");
+
+    // The text content is a markdown rendering of the same results
+    let ContentBlock::TextContent(text) = &result.content[0] else {
+        panic!("expected text content");
+    };
+    assert!(text.text.starts_with("Found 1 result:"));
+    assert!(text
+        .text
+        .contains("1. [Result for rust](<https://example.com>)"));
+}
+
+#[tokio::test]
+async fn test_call_search_tool_filters_domains_the_provider_cannot() {
+    let registry = ToolRegistry::new();
+    registry.register_search_provider(Box::new(MockSearch("mock-domains")), false);
+
+    let result = registry
+        .call_tool(
+            "search_mock-domains",
+            arguments(serde_json::json!({ "query": "rust", "include_domains": ["docs.rs"] })),
+        )
+        .await
+        .unwrap();
+    let structured = result.structured_content.unwrap();
+    assert_eq!(structured["results"], serde_json::json!([]));
+    assert!(structured.get("ignored_filters").is_none());
+
+    let result = registry
+        .call_tool(
+            "search_mock-domains",
+            arguments(serde_json::json!({ "query": "rust", "include_domains": ["example.com"] })),
+        )
+        .await
+        .unwrap();
+    let structured = result.structured_content.unwrap();
+    assert_eq!(structured["results"][0]["url"], "https://example.com");
+}
+
+#[tokio::test]
+async fn test_call_unknown_tool_is_rejected() {
+    let registry = ToolRegistry::new();
+    let result = registry.call_tool("search_does-not-exist", None).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_call_tool_with_invalid_arguments() {
+    let registry = ToolRegistry::new();
+    registry.register_search_provider(Box::new(MockSearch("mock-invalid")), false);
+
+    // Missing the required query
+    let result = registry
+        .call_tool("search_mock-invalid", arguments(serde_json::json!({})))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+
+    // Fails validation (limit out of range)
+    let result = registry
+        .call_tool(
+            "search_mock-invalid",
+            arguments(serde_json::json!({ "query": "rust", "limit": 500 })),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(
+        result.structured_content.unwrap()["error_type"],
+        "InvalidInput"
+    );
+}
+
+#[tokio::test]
+async fn test_call_tool_surfaces_provider_errors() {
+    let registry = ToolRegistry::new();
+    registry.register_search_provider(Box::new(FailingSearch), false);
+
+    let result = registry
+        .call_tool(
+            "search_mock-failing",
+            arguments(serde_json::json!({ "query": "rust" })),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.is_error, Some(true));
+    let structured = result.structured_content.unwrap();
+    assert_eq!(structured["error_type"], "RateLimit");
+    assert_eq!(structured["provider"], "mock-failing");
+    assert_eq!(structured["retryable"], true);
+}
@@ -1,9 +1,12 @@
 //! Comprehensive tests for Tavily provider to increase coverage
 
+use mockito::{Matcher, Server};
 use omnisearch_mcp::{
-    common::types::{BaseSearchParams, ErrorType, SearchProvider},
+    common::types::{BaseSearchParams, ErrorType, SearchProvider, SearchResult},
+    config::ProviderConfig,
     providers::search::TavilySearchProvider,
 };
+use serde_json::json;
 
 #[tokio::test]
 async fn test_tavily_provider_comprehensive_search() {
@@ -18,6 +21,13 @@ async fn test_tavily_provider_comprehensive_search() {
             "stackoverflow.com".to_string(),
         ]),
         exclude_domains: Some(vec!["reddit.com".to_string()]),
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -35,7 +45,7 @@ async fn test_tavily_provider_comprehensive_search() {
         Err(e) => {
             // Validate error handling
             match e.error_type {
-                ErrorType::ApiError => {
+                ErrorType::ApiError | ErrorType::Authentication => {
                     // Expected when API key is missing
                     assert!(
                         e.message.contains("Missing API key")
@@ -46,6 +56,10 @@ async fn test_tavily_provider_comprehensive_search() {
                     // These are also acceptable error types for API key issues
                     assert!(!e.message.is_empty());
                 }
+                _ => {
+                    // Network failures, timeouts and the like
+                    assert!(!e.message.is_empty());
+                }
             }
         }
     }
@@ -61,6 +75,13 @@ async fn test_tavily_provider_edge_cases() {
         limit: Some(1),
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -80,6 +101,13 @@ async fn test_tavily_provider_edge_cases() {
         limit: Some(20), // High limit
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -106,6 +134,13 @@ async fn test_tavily_provider_edge_cases() {
             "stackoverflow.com".to_string(),
             "reddit.com".to_string(),
         ]),
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     };
 
     match provider.search(params).await {
@@ -132,6 +167,13 @@ async fn test_tavily_provider_error_scenarios() {
             limit: Some(1),
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
         // Empty domains scenario
         BaseSearchParams {
@@ -139,6 +181,13 @@ async fn test_tavily_provider_error_scenarios() {
             limit: Some(1),
             include_domains: Some(vec![]),
             exclude_domains: Some(vec![]),
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
         // Very long query scenario
         BaseSearchParams {
@@ -146,6 +195,13 @@ async fn test_tavily_provider_error_scenarios() {
             limit: Some(1),
             include_domains: None,
             exclude_domains: None,
+            offset: None,
+            page_token: None,
+            time_range: None,
+            country: None,
+            language: None,
+            safe_search: None,
+            vertical: None,
         },
     ];
 
@@ -189,3 +245,57 @@ fn test_tavily_provider_construction() {
     // Both should have the same description
     assert_eq!(provider1.description(), provider2.description());
 }
+
+#[tokio::test]
+async fn test_tavily_pages_end_at_max_results() {
+    let mut server = Server::new_async().await;
+    let results: Vec<_> = (0..20)
+        .map(|i| {
+            json!({
+                "title": format!("Result {}", i),
+                "url": format!("https://example.com/{}", i),
+                "content": "Snippet",
+                "score": 0.5,
+            })
+        })
+        .collect();
+    // Tavily returns at most twenty results, however far the page goes
+    let mock = server
+        .mock("POST", "/search")
+        .match_body(Matcher::PartialJson(json!({ "max_results": 20 })))
+        .with_header("content-type", "application/json")
+        .with_body(json!({ "results": results, "response_time": "0.5" }).to_string())
+        .create_async()
+        .await;
+
+    let provider = TavilySearchProvider::with_config(ProviderConfig {
+        enabled: true,
+        api_key: Some("test-key".to_string()),
+        rate_limit: 100,
+        timeout_seconds: 5,
+        base_url: Some(server.url()),
+    });
+    let params = |offset: u32| BaseSearchParams {
+        query: "rust".to_string(),
+        limit: Some(10),
+        offset: Some(offset),
+        ..Default::default()
+    };
+
+    let page = provider.search(params(15)).await.unwrap();
+    mock.assert_async().await;
+    assert_eq!(page.len(), 5);
+    assert_eq!(page[0].title, "Result 15");
+    assert_eq!(provider.next_page_token(&params(15), &page), None);
+
+    // The second page of ten is the last one a token is issued for
+    let ten = vec![SearchResult::default(); 10];
+    assert_eq!(
+        provider.next_page_token(&params(0), &ten),
+        Some("10".to_string())
+    );
+    assert_eq!(provider.next_page_token(&params(10), &ten), None);
+
+    // Nothing is asked for past the last result
+    assert!(provider.search(params(20)).await.unwrap().is_empty());
+}
@@ -10,6 +10,13 @@ pub fn create_test_params(
         limit,
         include_domains: None,
         exclude_domains: None,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     }
 }
 
@@ -25,6 +32,13 @@ pub fn create_test_params_with_domains(
         limit,
         include_domains,
         exclude_domains,
+        offset: None,
+        page_token: None,
+        time_range: None,
+        country: None,
+        language: None,
+        safe_search: None,
+        vertical: None,
     }
 }
 